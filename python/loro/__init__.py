@@ -0,0 +1,7 @@
+from . import loro as _native
+from .loro import *  # noqa: F403
+from .orm import schema
+from .proxy import LoroDict, LoroSeq
+
+__doc__ = _native.__doc__
+__all__ = [*_native.__all__, "schema", "LoroDict", "LoroSeq"]
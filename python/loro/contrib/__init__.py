@@ -0,0 +1,4 @@
+"""Integrations with third-party libraries.
+
+The modules here import their library lazily, so none of them is a dependency of loro.
+"""
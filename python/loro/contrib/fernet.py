@@ -0,0 +1,40 @@
+"""Encrypt exported blobs with a key, using Fernet from the ``cryptography`` package::
+
+    from loro.contrib.fernet import generate_key, set_key
+
+    key = generate_key()
+    set_key(doc, key)
+    blob = doc.export(ExportMode.Snapshot())  # encrypted
+"""
+
+from __future__ import annotations
+
+from ..loro import LoroDoc
+
+__all__ = ["generate_key", "set_key"]
+
+
+def _fernet() -> type:
+    try:
+        from cryptography.fernet import Fernet
+    except ImportError as e:
+        raise ImportError(
+            "loro.contrib.fernet requires the `cryptography` package"
+        ) from e
+    return Fernet
+
+
+def generate_key() -> bytes:
+    """Generate a new random key for `set_key`."""
+    return _fernet().generate_key()
+
+
+def set_key(doc: LoroDoc, key: bytes) -> None:
+    """Encrypt the exports of `doc` and decrypt its imports with `key`.
+
+    This is `LoroDoc.set_encryption` with Fernet, which authenticates the blobs:
+    importing a blob encrypted with another key raises
+    ``cryptography.fernet.InvalidToken``.
+    """
+    fernet = _fernet()(key)
+    doc.set_encryption(fernet.encrypt, fernet.decrypt)
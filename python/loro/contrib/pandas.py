@@ -0,0 +1,64 @@
+"""pandas interop for tables stored as a list of maps.
+
+A table is a ``LoroList`` (or ``LoroMovableList``) whose items are ``LoroMap`` s, one per
+row::
+
+    from loro.contrib.pandas import from_dataframe, to_dataframe
+
+    from_dataframe(df, doc.get_list("rows"))
+    df = to_dataframe(doc.get_list("rows"))
+"""
+
+from __future__ import annotations
+
+import typing
+
+from ..loro import LoroList, LoroMap, LoroMovableList
+
+if typing.TYPE_CHECKING:
+    import pandas as pd
+
+__all__ = ["to_columns", "to_dataframe", "from_dataframe"]
+
+Table = typing.Union[LoroList, LoroMovableList]
+
+
+def to_columns(table: Table) -> typing.Dict[str, typing.List[typing.Any]]:
+    """Export the rows of `table` as a dict of column lists.
+
+    Columns are ordered by first appearance and rows missing a column get ``None``.
+    Items that aren't maps raise ``TypeError``.
+    """
+    rows = table.get_deep_value()
+    columns: typing.Dict[str, typing.List[typing.Any]] = {}
+    for i, row in enumerate(rows):
+        if not isinstance(row, dict):
+            raise TypeError(f"row {i} is a {type(row).__name__}, expected a map")
+        for key in row:
+            if key not in columns:
+                columns[key] = [None] * i
+        for key, column in columns.items():
+            column.append(row.get(key))
+    return columns
+
+
+def to_dataframe(table: Table) -> "pd.DataFrame":
+    """Export the rows of `table` as a ``pandas.DataFrame``."""
+    import pandas as pd
+
+    return pd.DataFrame(to_columns(table))
+
+
+def from_dataframe(df: "pd.DataFrame", table: Table, *, replace: bool = False) -> None:
+    """Append every row of `df` to `table` as a ``LoroMap``.
+
+    Missing values (``NaN``, ``NaT``, ``None``) are stored as ``None``. With
+    ``replace=True`` the existing rows of `table` are removed first.
+    """
+    if replace:
+        table.clear()
+    records = df.astype(object).where(df.notna(), None).to_dict("records")
+    for record in records:
+        row = table.push_container(LoroMap())
+        for key, value in record.items():
+            row.insert(str(key), value)
@@ -1,3 +1,5 @@
+import datetime as _datetime
+import os
 import typing
 from enum import Enum
 
@@ -20,6 +22,141 @@ Container = typing.Union[
 
 ContainerId = typing.Union[str, ContainerID]
 
+TreeIdLike = typing.Union[TreeID, str]
+TreeParent = typing.Union[TreeID, str, None]
+
+_T = typing.TypeVar("_T")
+
+def register_codec(
+    python_type: typing.Type[_T],
+    encode: typing.Callable[[_T], LoroValue],
+    decode: typing.Callable[[LoroValue], _T],
+    tag: typing.Optional[str] = None,
+) -> None:
+    r"""
+    Register a codec to store instances of `python_type` in Loro documents.
+
+    `encode` turns an instance into a value Loro supports natively, and `decode` turns that
+    value back into an instance. Encoded values are stored as a map
+    `{"$loro_codec": tag, "value": encoded}`. The `tag` defaults to the qualified name of
+    the type and must stay stable across versions of your app.
+
+    Registering a type again replaces its previous codec.
+    """
+    ...
+
+def unregister_codec(python_type: type) -> bool:
+    r"""
+    Remove the codec registered for `python_type`.
+
+    Return whether a codec was removed.
+    """
+    ...
+
+def to_msgpack(value: LoroValue) -> bytes:
+    r"""
+    Serialize a value to msgpack.
+
+    Map keys are written in sorted order so the output is deterministic. Container ids are
+    written as strings prefixed with `🦜:`, e.g. `"🦜:cid:0@<peer>:Text"` or
+    `"🦜:cid:root-<name>:Map"`. `from_msgpack` reads any string with that prefix back as a
+    container id, so a user string starting with `"🦜:cid:"` doesn't round-trip as a string.
+    """
+    ...
+
+def from_msgpack(data: bytes) -> LoroValue:
+    r"""
+    Deserialize a value produced by `to_msgpack`.
+    """
+    ...
+
+def enable_logging(level: int = 30) -> None:
+    r"""
+    Forward loro's internal log events to the `logging` module.
+
+    Events at `level` or above (a `logging` level such as `logging.INFO`) are logged to the
+    `loro` logger, or one of its children named after the Rust module, so they can be
+    configured like any other logger. Call it again to change the level.
+
+    Fails if another tracing subscriber is already installed in the process.
+    """
+    ...
+
+def inspect_blob(bytes: bytes, doc: typing.Optional[LoroDoc] = None) -> BlobInspection:
+    r"""
+    Check a blob without importing it, e.g. to reject a corrupt upload before it reaches
+    the document.
+
+    The checksum and the header of the blob are verified, then the blob is imported into a
+    fork of `doc`, or into an empty document if `doc` is `None`, so a truncated or corrupt
+    body and a blob the document can't accept are caught as well. The document itself is
+    never modified, but forking it is O(n). Encrypted blobs are decrypted with the hook set
+    by `LoroDoc.set_encryption` on `doc`.
+    """
+    ...
+
+def diff_blobs(a: bytes, b: bytes) -> DiffBatch:
+    r"""
+    The diff turning the document saved in blob `a` into the one saved in blob `b`, e.g. to
+    verify a backup or to see how two replicas diverged.
+
+    Both blobs are loaded into one document and diffed between their versions, so they may
+    come from unrelated documents. The diff is empty if the documents have the same state.
+    """
+    ...
+
+def merge(
+    base: typing.Union[LoroDoc, bytes],
+    ours: typing.Union[LoroDoc, bytes],
+    theirs: typing.Union[LoroDoc, bytes],
+) -> tuple[LoroDoc, list[MergeConflict]]:
+    r"""
+    Merge two versions of a document derived from `base`, like a three-way merge in Git.
+
+    Each argument is a `LoroDoc` or a blob exported from one. Returns a new document with
+    the history of all three, and the map keys that both `ours` and `theirs` changed since
+    `base`, sorted by container and key. Loro merges them without failing, keeping one of
+    the values; the conflicts let the application review them.
+    """
+    ...
+
+def peer_id_from_string(s: str) -> int:
+    r"""
+    Derive a stable PeerID from a string such as `"user-42/device-A"`.
+
+    The same string always maps to the same PeerID, across processes and versions, so an
+    application can reuse the id of a user's device instead of picking a random one.
+
+    Two writers must never share a PeerID: derive it from something unique to each
+    concurrent writer (e.g. the user *and* the device or session), not from the user alone.
+    Distinct strings collide with a probability of about `n² / 2⁶⁵` for `n` strings.
+    """
+    ...
+
+def set_rng_seed(seed: typing.Optional[int]) -> None:
+    r"""
+    Seed the PeerIDs picked for new documents, or make them random again with `None`.
+
+    Once seeded, `LoroDoc()`, `fork` and `fork_at` draw their PeerIDs from a generator, so a
+    simulation of several peers or a doctest makes the same changes on every run. The
+    fractional index jitter of trees stays random: leave it at 0 for reproducible trees.
+
+    A seeded process must not edit the same documents as other processes seeded alike, as
+    they would share PeerIDs.
+    """
+    ...
+
+def set_max_decompressed_size(max_bytes: int) -> None:
+    r"""
+    Set the largest size, in bytes, a blob compressed by `LoroDoc.export` may decompress to.
+
+    Compressed blobs are decompressed by every import, whether or not the application
+    compresses its own exports, so this bounds the memory a crafted blob can take before the
+    import filter and the quota hook see it. It defaults to 256 MiB and applies to the whole
+    process.
+    """
+    ...
+
 class AbsolutePosition:
     pos: int
     side: Side
@@ -37,11 +174,46 @@ class Awareness:
     @local_state.setter
     def local_state(self, value: LoroValue) -> None: ...
     def remove_outdated(self) -> list[int]: ...
+    def peers(self) -> list[int]:
+        r"""
+        The peers with a known state, including this one once its local state is set.
+        """
+        ...
+    def is_empty(self) -> bool: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, peer: int) -> bool: ...
+    def __repr__(self) -> str: ...
 
 class AwarenessPeerUpdate:
     updated: list[int]
     added: list[int]
 
+class BlobInspection:
+    r"""
+    The result of `inspect_blob`.
+    """
+    def __repr__(self) -> str: ...
+    valid: bool
+    error: typing.Optional[str]
+    meta: typing.Optional[ImportBlobMetadata]
+    pending: bool
+
+class ChangeInfo:
+    r"""
+    A change yielded by `LoroDoc.iter_changes`.
+    """
+    def __repr__(self) -> str: ...
+    meta: ChangeMeta
+    ops: list[OpInfo]
+
+class ChangeIter:
+    r"""
+    An iterator over the changes of a document, returned by `LoroDoc.iter_changes`.
+    """
+    def __iter__(self) -> ChangeIter: ...
+    def __next__(self) -> ChangeInfo: ...
+    def __len__(self) -> int: ...
+
 class ChangeMeta:
     lamport: int
     id: ID
@@ -49,6 +221,20 @@ class ChangeMeta:
     message: typing.Optional[str]
     deps: Frontiers
     len: int
+    @property
+    def datetime(self) -> _datetime.datetime:
+        r"""
+        The timestamp of the change as a UTC `datetime.datetime`.
+        """
+        ...
+    @property
+    def message_json(self) -> typing.Any:
+        r"""
+        The commit message parsed as JSON, or `None` if the change has no message.
+
+        Raises `ValueError` if the message isn't valid JSON.
+        """
+        ...
 
 class Configure:
     def __new__(cls) -> Configure: ...
@@ -99,6 +285,8 @@ class ContainerDiff:
     r"""
     A diff of a container.
     """
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
 
     target: ContainerID
     path: list[PathItem]
@@ -118,7 +306,30 @@ class CounterSpan:
     end: int
     def __new__(cls, start: int, end: int): ...
 
+class ContainerAnalysis:
+    r"""
+    The statistics of one container.
+    """
+    def __repr__(self) -> str: ...
+    id: ContainerID
+    container_type: ContainerType
+    ops: int
+    size: int
+    deep_size: int
+    children: int
+    depth: int
+    dropped: bool
+
 class Cursor:
+    def __repr__(self) -> str: ...
+    def __bytes__(self) -> bytes: ...
+    def __reduce__(self) -> tuple[typing.Any, ...]:
+        r"""
+        Pickle the cursor as its encoded bytes, e.g. to keep it in a session store.
+        """
+        ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
     id: typing.Optional[ID]
     side: Side
     container: ContainerID
@@ -137,12 +348,25 @@ class Cursor:
         ...
 
 class DiffEvent:
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
     triggered_by: EventTriggerKind
     origin: str
     current_target: typing.Optional[ContainerID]
     events: list[ContainerDiff]
 
 class Frontiers:
+    def __repr__(self) -> str: ...
+    def __bytes__(self) -> bytes: ...
+    def __reduce__(self) -> tuple[typing.Any, ...]:
+        r"""
+        Pickle the frontiers as its encoded bytes, e.g. to keep it in a session store.
+        """
+        ...
+    def __len__(self) -> int: ...
+    def __iter__(self) -> typing.Iterator[ID]: ...
+    def __contains__(self, id: ID) -> bool: ...
+    def __eq__(self, other: object) -> bool: ...
     def __new__(
         cls,
     ): ...
@@ -154,9 +378,24 @@ class Frontiers:
     @classmethod
     def decode(cls, bytes: bytes) -> Frontiers: ...
 
+class GcResult:
+    r"""
+    A shallow snapshot trimming the history, returned by `LoroDoc.gc`.
+    """
+    def __repr__(self) -> str: ...
+    snapshot: bytes
+    shallow_since: Frontiers
+    bytes_saved: int
+    changes_dropped: int
+
 class ID:
-    peer: int
-    counter: int
+    def __repr__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    @property
+    def peer(self) -> int: ...
+    @property
+    def counter(self) -> int: ...
     def __new__(cls, peer: int, counter: int): ...
 
 class IdSpan:
@@ -178,35 +417,72 @@ class ImportBlobMetadata:
     change_num: int
     mode: EncodedBlobMode
 
+class CommitInfo:
+    r"""
+    The change produced by `LoroDoc.commit` or `LoroDoc.commit_with`.
+    """
+    def __repr__(self) -> str: ...
+    span: IdSpan
+    frontiers: Frontiers
+    ops: int
+
 class ImportStatus:
     success: VersionRange
     pending: typing.Optional[VersionRange]
+    verify: typing.Optional[typing.Literal["partial", "strict"]]
+    @property
+    def complete(self) -> bool:
+        r"""
+        Whether everything was applied, with nothing left pending.
+        """
+        ...
+
+class ListIdIter:
+    r"""
+    An iterator over the `(index, id)` pairs of a list, returned by `LoroList.iter_ids`.
+    """
+    def __iter__(self) -> ListIdIter: ...
+    def __next__(self) -> tuple[int, ID]: ...
 
 class LoroCounter:
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
     id: ContainerID
     value: float
     def __float__(self) -> float: ...
     def __int__(self) -> int: ...
-    def __add__(self, other: typing.Any) -> float: ...
-    def __radd__(self, other: typing.Any) -> float: ...
-    def __sub__(self, other: typing.Any) -> float: ...
-    def __rsub__(self, other: typing.Any) -> float: ...
+    def __add__(self, other: typing.Union[float, LoroCounter]) -> float: ...
+    def __radd__(self, other: typing.Union[float, LoroCounter]) -> float: ...
+    def __sub__(self, other: typing.Union[float, LoroCounter]) -> float: ...
+    def __rsub__(self, other: typing.Union[float, LoroCounter]) -> float: ...
     def __neg__(self) -> float: ...
     def __abs__(self) -> float: ...
     def __new__(
         cls,
     ): ...
-    def increment(self, value: typing.Any) -> None:
+    def increment(self, value: float) -> None:
         r"""
         Increment the counter by the given value.
         """
         ...
 
-    def decrement(self, value: typing.Any) -> None:
+    def decrement(self, value: float) -> None:
         r"""
         Decrement the counter by the given value.
         """
         ...
+
+    def to_py(self) -> float:
+        r"""
+        The value of the counter as a `float`, the same as `get_value`.
+        """
+        ...
+
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state of the counter, see `LoroDoc.state_hash`.
+        """
+        ...
     
     def subscribe(self, callback: typing.Callable[[DiffEvent], None]) -> typing.Optional[Subscription]:
         r"""
@@ -233,6 +509,37 @@ class LoroCounter:
         ...
 
 class LoroDoc:
+    r"""
+    `LoroDoc` is the entry for the whole document.
+    When it's dropped, all the associated `Handler`s will be invalidated.
+
+    **Important:** Loro is a pure library and does not handle network protocols.
+    It is the responsibility of the user to manage the storage, loading, and synchronization
+    of the bytes exported by Loro in a manner suitable for their specific environment.
+
+    The hooks and settings of `set_metrics_hook`, `set_import_filter`, `set_encryption`,
+    `set_quota_hook`, `set_pending_hook`, `set_timestamp_provider`, `set_dispatcher`,
+    `set_readonly` and `set_datetime_decoding` belong to the document, not to this object.
+    Every `LoroDoc` object of the document, e.g. `container.doc()`, sees them, and they last
+    as long as the document. `fork` only copies the text style config and the datetime decoding.
+    """
+
+    def __repr__(self) -> str: ...
+    def __copy__(self) -> LoroDoc:
+        r"""
+        Copy the document with `fork`, so the copy gets its own PeerID and history.
+
+        A shallow copy sharing the document would let two objects commit with the same
+        PeerID, so `copy.copy` forks as well.
+        """
+        ...
+    def __deepcopy__(self, memo: typing.Any) -> LoroDoc:
+        r"""
+        Copy the document with `fork` for `copy.deepcopy`.
+        """
+        ...
+    def _repr_html_(self) -> str: ...
+    def _repr_markdown_(self) -> str: ...
     config: Configure
     is_detached_editing_enabled: bool
     oplog_vv: VersionVector
@@ -246,9 +553,20 @@ class LoroDoc:
     has_history_cache: bool
     def __new__(
         cls,
+        *,
+        peer_id: typing.Optional[typing.Union[int, str]] = None,
+        record_timestamp: typing.Optional[bool] = None,
+        merge_interval: typing.Optional[int] = None,
+        detached_editing: typing.Optional[bool] = None,
+        hide_empty_root_containers: typing.Optional[bool] = None,
     ):
         """
         `LoroDoc` is the entry for the whole document.
+
+        The keyword arguments configure the document before any op is created, like the
+        matching setters: `peer_id` (an int, or a string passed to
+        `peer_id_from_string`), `record_timestamp`, `merge_interval` in seconds
+        (`set_change_merge_interval`), `detached_editing` and `hide_empty_root_containers`.
         When it's dropped, all the associated [`Handler`]s will be invalidated.
 
         **Important:** Loro is a pure library and does not handle network protocols.
@@ -276,6 +594,29 @@ class LoroDoc:
         """
         ...
 
+    @classmethod
+    def new_with_seed(cls, seed: int) -> LoroDoc:
+        r"""
+        Create a document whose PeerID is derived from `seed`, so a simulation or a doctest
+        gets the same PeerIDs on every run. See `set_rng_seed` to seed every new document.
+        """
+        ...
+
+    def get_value_at(
+        self, frontiers: Frontiers, path: typing.Optional[str] = None
+    ) -> typing.Optional[LoroValue]:
+        r"""
+        Get the deep value of the document, or of the value at `path`, at the given version.
+
+        `path` is a path accepted by `get_by_str_path`, e.g. `"map/key"`. Returns `None` if
+        nothing is at the path in that version. The document is left at its current version:
+        the value is read from a fork, so it's O(n) like `fork_at`.
+
+        Raises if `frontiers` isn't a version of the document, e.g. it has ops this document
+        hasn't imported or is before the start of a shallow snapshot.
+        """
+        ...
+
     def get_change(self, id: ID) -> typing.Optional[ChangeMeta]:
         r"""
         Get `Change` at the given id.
@@ -294,6 +635,81 @@ class LoroDoc:
         """
         ...
 
+    def get_changed_ops_in(self, id_span: IdSpan) -> list[OpInfo]:
+        r"""
+        Get the ops in the given id span, described with their container, kind and content.
+
+        Together with `get_change`, it can show which change an edit belongs to and what
+        else that change did.
+        """
+        ...
+
+    def export_change_graph(self, format: typing.Literal["mermaid", "dot"] = "mermaid") -> str:
+        r"""
+        Render the change DAG for debugging concurrent history.
+
+        Each change is a node labelled with its id, peer, lamport and commit message, with an
+        edge from every dependency to the change depending on it. `format` is `"mermaid"` or
+        `"dot"` (Graphviz).
+        """
+        ...
+
+    def iter_changes(
+        self,
+        from_: typing.Optional[VersionVector] = None,
+        to: typing.Optional[VersionVector] = None,
+        peer: typing.Optional[int] = None,
+    ) -> ChangeIter:
+        r"""
+        Iterate the changes with ops in the range `(from_, to]`.
+
+        Each item has the `ChangeMeta` of a change and its `ops`, described with their
+        container, kind and content. The ops are only decoded when accessed. Changes
+        partially in the range only list the ops inside it.
+
+        By default, it iterates the whole history, parents before their children. Pass `peer`
+        to only get the changes of that peer, in counter order.
+        """
+        ...
+
+    def frontiers_at_timestamp(self, timestamp: int) -> Frontiers:
+        r"""
+        Get the latest version made only of changes with a timestamp up to `timestamp`.
+
+        Timestamps are in seconds and only recorded when `set_record_timestamp(True)` is
+        enabled. Each peer's changes are taken in counter order up to its first change made
+        after `timestamp`; a peer whose clock went backwards has its later changes left out
+        even if their timestamps are earlier.
+        Changes depending on later ones, which happen when clocks are skewed, are left out
+        too, so the result can always be checked out.
+        """
+        ...
+
+    def timeline(
+        self,
+        points: int,
+        by: typing.Literal["changes", "timestamp"] = "changes",
+    ) -> list[TimelinePoint]:
+        r"""
+        Get up to `points` versions spread evenly over the history, e.g. for the checkpoints of
+        a history scrubber. The last one is the latest version.
+
+        With `by="changes"`, the versions are made of the first `i * n / points` changes,
+        ordered by lamport. With `by="timestamp"`, they are the versions at evenly spaced
+        timestamps, see `frontiers_at_timestamp`. Versions with no change or equal to the
+        previous one are skipped.
+        """
+        ...
+
+    def changes_between(self, start: int, end: int) -> list[ChangeMeta]:
+        r"""
+        Get the changes with `start <= timestamp < end`, parents before their children.
+
+        Each peer's changes are binary searched by timestamp until its clock first goes
+        backwards and scanned after that, so a peer's timestamps don't need to be sorted.
+        """
+        ...
+
     @classmethod
     def decode_import_blob_meta(
         cls, bytes: bytes, check_checksum: bool
@@ -317,6 +733,47 @@ class LoroDoc:
         """
         ...
 
+    def set_timestamp_provider(
+        self, callback: typing.Optional[typing.Callable[[], int]]
+    ) -> None:
+        r"""
+        Set a callback returning the Unix timestamp, in seconds, of each new change.
+
+        It's called on every commit, including the implicit ones, instead of reading the local
+        wall clock: tests can produce deterministic histories and servers can use a trusted
+        clock. It takes precedence over the `timestamp` passed to `commit_with`, and stamps the
+        changes even if `set_record_timestamp` wasn't enabled. Exceptions it raises are reported
+        with `sys.unraisablehook` and the change keeps its default timestamp.
+
+        Pass `None` to remove it.
+        """
+        ...
+
+    def set_dispatcher(
+        self,
+        dispatcher: typing.Union[
+            typing.Literal["inline", "thread"],
+            typing.Callable[[typing.Callable[[], None]], typing.Any],
+        ],
+    ) -> None:
+        r"""
+        Set where the callbacks of `subscribe`, `subscribe_root` and `subscribe_jsonpath` run.
+
+        - `"inline"`, the default, calls them during the commit, import or checkout that
+          emits the event.
+        - `"thread"` queues them to a thread dedicated to this document, so committing never
+          waits for the GIL on their behalf, e.g. while another thread holds it.
+        - A callable, such as `ThreadPoolExecutor.submit` or `queue.Queue.put`, is given each
+          invocation as a function without arguments to run whenever it sees fit. It's called
+          from a thread dedicated to this document too, so committing doesn't wait for the GIL
+          to submit them either.
+
+        Exceptions raised on the dispatch thread, including by the callable, are reported with
+        `sys.unraisablehook`. The setting applies to existing subscriptions as well. Container
+        subscriptions always run inline.
+        """
+        ...
+
     def set_detached_editing(self, enable: bool) -> None:
         r"""
         Enables editing in detached mode, which is disabled by default.
@@ -329,6 +786,7 @@ class LoroDoc:
         - Ensure no concurrent operations share the same PeerID if set manually.
         - Importing does not affect the document's state or version; changes are
           recorded in the [OpLog] only. Call `checkout` to apply changes.
+        - It can't be enabled on a document made read-only with `set_readonly`.
         """
         ...
 
@@ -353,6 +811,21 @@ class LoroDoc:
         """
         ...
 
+    def set_datetime_decoding(self, enabled: bool) -> None:
+        r"""
+        Set whether the `datetime.datetime` and `datetime.date` values read from the document
+        are converted back.
+
+        They are always stored as tagged maps with an ISO 8601 string, e.g.
+        `{"$loro_codec": "datetime", "value": "2025-01-01T12:00:00+00:00"}`, so the timezone
+        survives the round trip. When decoding is disabled (the default), the tagged map is
+        returned as a dict, which keeps reads identical to other Loro implementations.
+
+        It applies to the values read through the document and its containers. Values of
+        events and of detached containers aren't converted.
+        """
+        ...
+
     def attach(self) -> None:
         r"""
         Attach the document state to the latest known version.
@@ -398,11 +871,13 @@ class LoroDoc:
         """
         ...
 
-    def cmp_frontiers(self, a: Frontiers, b: Frontiers) -> typing.Optional[Ordering]:
+    def cmp_frontiers(self, a: Frontiers, b: Frontiers) -> Ordering:
         r"""
-        Compare two frontiers.
+        Compare two frontiers by causal order, [Ordering::Concurrent] if neither includes the
+        other.
 
-        If the frontiers are not included in the document, return [`FrontiersNotIncluded`].
+        Both frontiers must be included in the document's history, otherwise a `ValueError`
+        is raised.
         """
         ...
 
@@ -417,6 +892,19 @@ class LoroDoc:
         ...
 
     def import_batch(self, bytes: typing.Sequence[bytes]) -> ImportStatus: ...
+    def import_dir(
+        self, path: typing.Union[str, os.PathLike[str]], pattern: str = "*.bin"
+    ) -> ImportStatus:
+        r"""
+        Import the files of the directory `path` whose names match `pattern`, e.g. the update
+        blobs written by an append-only persistence scheme.
+
+        Identical files are imported once. The blobs are imported like `import_batch`: in
+        causal order whatever their names, with a single event flush. `pattern` matches the
+        whole file name and supports the `*` and `?` wildcards.
+        """
+        ...
+
     def get_movable_list(self, obj: ContainerId) -> LoroMovableList:
         r"""
         Get a [LoroMovableList] by container id.
@@ -457,7 +945,7 @@ class LoroDoc:
         """
         ...
 
-    def get_counter(self, obj: typing.Any) -> LoroCounter:
+    def get_counter(self, obj: ContainerId) -> LoroCounter:
         r"""
         Get a [LoroCounter] by container id.
 
@@ -465,7 +953,7 @@ class LoroDoc:
         """
         ...
 
-    def commit(self) -> None:
+    def commit(self) -> typing.Optional[CommitInfo]:
         r"""
         Commit the cumulative auto commit transaction.
 
@@ -476,6 +964,9 @@ class LoroDoc:
         - `doc.export(mode)` is called.
         - `doc.import(data)` is called.
         - `doc.checkout(version)` is called.
+
+        Returns what was committed, or `None` if there were no pending ops. Raises if the
+        quota hook set with `set_quota_hook` rejects the commit, leaving the ops pending.
         """
         ...
 
@@ -485,13 +976,16 @@ class LoroDoc:
         timestamp: typing.Optional[int] = ...,
         immediate_renew: typing.Optional[bool] = ...,
         commit_msg: typing.Optional[str] = ...,
-    ) -> None:
+    ) -> typing.Optional[CommitInfo]:
         r"""
         Commit the cumulative auto commit transaction with custom configure.
 
         There is a transaction behind every operation.
         It will automatically commit when users invoke export or import.
         The event will be sent after a transaction is committed
+
+        Returns what was committed, or `None` if there were no pending ops. Raises if the
+        quota hook set with `set_quota_hook` rejects the commit, leaving the ops pending.
         """
         ...
 
@@ -508,18 +1002,36 @@ class LoroDoc:
         """
         ...
 
-    def import_(self, bytes: bytes) -> ImportStatus:
+    def import_(
+        self,
+        bytes: bytes,
+        verify: typing.Literal["partial", "strict"] = "partial",
+    ) -> ImportStatus:
         r"""
         Import updates/snapshot exported by [`LoroDoc::export_snapshot`] or [`LoroDoc::export_from`].
+
+        The checksum of the blob is always validated, and a corrupted blob raises.
+
+        `verify` sets what happens to a blob depending on changes the document lacks:
+        - `"partial"`, the default, applies it partially, with the rest reported in `pending`.
+        - `"strict"` rejects it, leaving the document untouched. Finding out imports the blob
+          into a fork of the document, which is O(n). The import filter reuses that fork.
+
+        The returned status tells which of them ran and whether anything was left pending.
         """
         ...
 
-    def import_with(self, bytes: bytes, origin: str) -> ImportStatus:
+    def import_with(
+        self,
+        bytes: bytes,
+        origin: str,
+        verify: typing.Literal["partial", "strict"] = "partial",
+    ) -> ImportStatus:
         r"""
         Import updates/snapshot exported by [`LoroDoc::export_snapshot`] or [`LoroDoc::export_from`].
 
         It marks the import with a custom `origin` string. It can be used to track the import source
-        in the generated events.
+        in the generated events. `verify` works as in `import_`.
         """
         ...
 
@@ -536,6 +1048,8 @@ class LoroDoc:
     ) -> str:
         r"""
         Export the current state with json-string format of the document.
+
+        Raise if an encryption callback is set with `set_encryption`.
         """
         ...
 
@@ -551,6 +1065,59 @@ class LoroDoc:
         """
         ...
 
+    def version_info(self) -> VersionInfo:
+        r"""
+        Get the version vectors and frontiers of both the `DocState` and the `OpLog` at once.
+        """
+        ...
+
+    def missing_spans(self, remote_vv: VersionVector) -> SyncGap:
+        r"""
+        Compare the history with the version vector of a remote peer, e.g. to request and
+        send the changes needed to catch up.
+
+        `missing` are the spans to request from the peer, `ahead` the spans to send to it, which
+        can be exported with `ExportMode.UpdatesInRange`.
+        """
+        ...
+
+    def analyze(self) -> DocAnalysis:
+        r"""
+        Analyze the container info of the doc
+
+        Return the op count, state size, deep size and child count of every container, which
+        helps to find the container that makes a document large or slow.
+
+        This is used for development and debugging. It can be slow.
+        """
+        ...
+
+    def storage_stats(self) -> StorageStats:
+        r"""
+        Get the storage statistics of the document: the size of its state and history, and
+        the number of changes, ops and containers.
+
+        It exports the document to measure it, so it's O(n). Use it to monitor how documents
+        grow and to decide when to trim the history with a shallow snapshot.
+        """
+        ...
+
+    def gc(
+        self,
+        *,
+        keep_last: typing.Optional[int] = None,
+        keep_since: typing.Optional[Frontiers] = None,
+    ) -> GcResult:
+        r"""
+        Export a shallow snapshot trimming the history, and measure what it saves.
+
+        Pass exactly one policy: `keep_last` keeps the last N changes, `keep_since` keeps the
+        history since the given `Frontiers`. The document itself is left untouched; load the
+        returned `snapshot` in its place to drop the trimmed history. Time travel before
+        `shallow_since` isn't possible in the loaded document.
+        """
+        ...
+
     def get_value(self) -> LoroValue:
         r"""
         Get the shallow value of the document.
@@ -563,6 +1130,45 @@ class LoroDoc:
         """
         ...
 
+    def deep_equal(self, other: LoroDoc, history: bool = False) -> bool:
+        r"""
+        Whether `other` has the same deep value, compared in Rust without converting the
+        states to Python.
+
+        If `history` is true, the documents must also have the same history, i.e. the same
+        `oplog_vv`. Replicas that imported each other's updates converge to equal documents.
+        """
+        ...
+
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state, so replicas can check they converged without
+        exchanging snapshots.
+
+        It's the hash of the deep value, the same across processes, platforms and versions of
+        loro-py. Text styles aren't part of the deep value: use `LoroText.state_hash` to
+        include them.
+        """
+        ...
+
+    def get_deep_value_msgpack(self) -> bytes:
+        r"""
+        Get the entire state of the current DocState, serialized as msgpack.
+
+        It's the same as `loro.to_msgpack(doc.get_deep_value())` without building the
+        intermediate Python objects.
+        """
+        ...
+
+    def get_deep_value_json(self, pretty: bool = False) -> str:
+        r"""
+        Get the entire state of the current DocState as a JSON string.
+
+        It's serialized in Rust, which is much faster than `json.dumps(doc.get_deep_value())`
+        for large documents.
+        """
+        ...
+
     def get_deep_value_with_id(self) -> LoroValue:
         r"""
         Get the entire state of the current DocState with container id
@@ -572,17 +1178,22 @@ class LoroDoc:
     @property
     def peer_id(self) -> int: ...
     @peer_id.setter
-    def peer_id(self, peer: int) -> None:
+    def peer_id(self, peer: typing.Union[int, str]) -> None:
         r"""
         Change the PeerID
 
+        A string is turned into a stable PeerID with `peer_id_from_string`.
+
         NOTE: You need to make sure there is no chance two peer have the same PeerID.
         If it happens, the document will be corrupted.
         """
         ...
 
     def subscribe(
-        self, container_id: ContainerID, callback: typing.Callable[[DiffEvent], None]
+        self,
+        container_id: ContainerID,
+        callback: typing.Callable[[DiffEvent], None],
+        kinds: typing.Optional[typing.Iterable[EventTriggerKind]] = None,
     ) -> Subscription:
         r"""
         Subscribe the events of a container.
@@ -597,6 +1208,9 @@ class LoroDoc:
         - `doc.import(data)` is called.
         - `doc.checkout(version)` is called.
 
+        Pass a set of `EventTriggerKind`s as `kinds` to receive only the events they trigger,
+        e.g. `{EventTriggerKind.Import}` for the remote changes.
+
         # Example
 
         ```
@@ -633,7 +1247,9 @@ class LoroDoc:
         ...
 
     def subscribe_root(
-        self, callback: typing.Callable[[DiffEvent], None]
+        self,
+        callback: typing.Callable[[DiffEvent], None],
+        kinds: typing.Optional[typing.Iterable[EventTriggerKind]] = None,
     ) -> Subscription:
         r"""
         Subscribe all the events.
@@ -647,6 +1263,36 @@ class LoroDoc:
         - `doc.export(mode)` is called.
         - `doc.import(data)` is called.
         - `doc.checkout(version)` is called.
+
+        `kinds` filters the events like in `subscribe`.
+        """
+        ...
+
+    def subscribe_once(
+        self,
+        container_id: typing.Optional[ContainerID],
+        callback: typing.Callable[[DiffEvent], None],
+    ) -> Subscription:
+        r"""
+        Subscribe to the next event of the container `container_id`, or of the whole document
+        if it's `None`.
+
+        The subscription ends once `callback` has received one event.
+        """
+        ...
+
+    async def wait_for_change(
+        self,
+        container_id: typing.Optional[ContainerID] = None,
+        timeout: typing.Optional[float] = None,
+    ) -> DiffEvent:
+        r"""
+        Wait for the next event of the container `container_id`, or of the whole document if
+        it's `None`, e.g. until an import made by another task lands.
+
+        Returns an awaitable of the `DiffEvent`, which raises `asyncio.TimeoutError` after
+        `timeout` seconds. Must be called from a running event loop; the event may come from
+        any thread.
         """
         ...
 
@@ -662,79 +1308,319 @@ class LoroDoc:
         self, callback: typing.Callable[[ID], bool]
     ) -> Subscription:
         r"""
-        Subscribe the peer id change of the document.
+        Subscribe the peer id change of the document.
+        """
+        ...
+
+    def active_subscriptions(self) -> list[SubscriptionInfo]:
+        r"""
+        The subscriptions made through this document that are still active, e.g. to find the
+        observers left behind before unloading it.
+
+        Subscriptions made on containers or detached with `Subscription.detach` aren't listed.
+        """
+        ...
+
+    def unsubscribe_all(self) -> int:
+        r"""
+        Unsubscribe all the subscriptions listed by `active_subscriptions` and return how many
+        there were.
+        """
+        ...
+
+    def get_by_path(
+        self, path: typing.Sequence[typing.Union[Index, str, int, TreeID]]
+    ) -> typing.Optional[ValueOrContainer]:
+        r"""
+        Get the handler by the path.
+
+        The steps of the path are `Index`es, or str keys, int positions and `TreeID`s.
+        """
+        ...
+
+    def get_by_str_path(self, path: str) -> typing.Optional[ValueOrContainer]:
+        r"""
+        Get the handler by the string path.
+        """
+        ...
+
+    def get_container(
+        self, id: typing.Union[ContainerID, str]
+    ) -> typing.Optional[Container]:
+        r"""
+        Get a container by its ContainerID.
+
+        `id` is either a `ContainerID` or its serialized `"cid:..."` form, as found in
+        JSON exports and persisted references. The container is returned with its
+        concrete type, or `None` if it doesn't exist in the document.
+        """
+        ...
+
+    def get_cursor_pos(self, cursor: Cursor) -> PosQueryResult:
+        r"""
+        Get the absolute position of the given cursor.
+
+        # Example
+
+        ```
+        # use loro::{LoroDoc, ToJson};
+        let doc = LoroDoc::new();
+        let text = &doc.get_text("text");
+        text.insert(0, "01234").unwrap();
+        let pos = text.get_cursor(5, Default::default()).unwrap();
+        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 5);
+        text.insert(0, "01234").unwrap();
+        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 10);
+        text.delete(0, 10).unwrap();
+        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 0);
+        text.insert(0, "01234").unwrap();
+        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 5);
+        ```
+        """
+        ...
+
+    def free_history_cache(self) -> None:
+        r"""
+        Free the history cache that is used for making checkout faster.
+
+        If you use checkout that switching to an old/concurrent version, the history cache will be built.
+        You can free it by calling this method.
+        """
+        ...
+
+    def free_diff_calculator(self) -> None:
+        r"""
+        Free the cached diff calculator that is used for checkout.
+        """
+        ...
+
+    def compact_change_store(self) -> None:
+        r"""
+        Encoded all ops and history cache to bytes and store them in the kv store.
+
+        This will free up the memory that used by parsed ops
+        """
+        ...
+
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Union[ExportMode, typing.Literal["snapshot"]],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+    ) -> bytes: ...
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Literal["updates"],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        *,
+        from_: typing.Optional[VersionVector] = None,
+    ) -> bytes: ...
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Literal["updates-in-range"],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        *,
+        spans: typing.Sequence[IdSpan],
+    ) -> bytes: ...
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Literal["updates-in-time-range"],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        *,
+        start_ts: int,
+        end_ts: int,
+    ) -> bytes: ...
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Literal["shallow-snapshot"],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        *,
+        frontiers: Frontiers,
+    ) -> bytes: ...
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Literal["state-only"],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        *,
+        frontiers: typing.Optional[Frontiers] = None,
+    ) -> bytes: ...
+    @typing.overload
+    def export(
+        self,
+        mode: typing.Literal["snapshot-at"],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        *,
+        version: Frontiers,
+    ) -> bytes: ...
+    def export(
+        self,
+        mode: typing.Union[ExportMode, str],
+        compression: typing.Optional[typing.Literal["zstd"]] = None,
+        level: int = 3,
+        **options: typing.Any,
+    ) -> bytes:
+        r"""
+        Export the document in the given mode.
+
+        `mode` is an `ExportMode`, or the name of one with its fields as keywords:
+        `"snapshot"`, `"updates"` with an optional `from_` version, `"updates-in-range"`
+        with `spans`, `"updates-in-time-range"` with `start_ts` and `end_ts`,
+        `"shallow-snapshot"` with `frontiers`, `"state-only"` with optional `frontiers`, or
+        `"snapshot-at"` with `version`.
+
+        With `compression="zstd"`, the blob is compressed at the zstd `level`, which pays off
+        for snapshots of text-heavy documents. Compressed blobs are detected and decompressed
+        on import, up to the size set with `set_max_decompressed_size`. They start with a
+        `lorozst` header that only these bindings understand: loro-js and the Rust crate can't
+        import them.
+        """
+        ...
+
+    def export_chunks(self, mode: ExportMode, max_bytes: int) -> list[bytes]:
+        r"""
+        Export the updates of `mode` as blobs of at most `max_bytes` each, e.g. to push them
+        through a transport that limits the size of its messages.
+
+        `mode` is `ExportMode.Snapshot()` for the whole history, `ExportMode.Updates`,
+        `ExportMode.UpdatesInRange` or `ExportMode.UpdatesInTimeRange`. The blobs are in causal
+        order, so importing them one by one leaves nothing pending. A change too large for a
+        blob is split between its ops; an error is raised if a single op doesn't fit.
         """
         ...
 
-    def get_by_path(
-        self, path: typing.Sequence[Index]
-    ) -> typing.Optional[ValueOrContainer]:
+    def set_metrics_hook(
+        self, callback: typing.Optional[typing.Callable[[OperationMetrics], None]]
+    ) -> None:
         r"""
-        Get the handler by the path.
+        Set a callback invoked after every import, export and commit of this document.
+
+        The callback receives an [OperationMetrics] with the operation name, its duration,
+        the bytes read or written and the number of ops it added, which makes it easy to feed
+        a metrics system without wrapping every call site. Exceptions raised by the callback
+        are reported with `sys.unraisablehook` instead of failing the operation.
+
+        Pass `None` to remove it.
         """
         ...
 
-    def get_by_str_path(self, path: str) -> typing.Optional[ValueOrContainer]:
+    def set_import_filter(
+        self, callback: typing.Optional[typing.Callable[[ImportRequest], bool]]
+    ) -> None:
         r"""
-        Get the handler by the string path.
+        Set a callback that decides whether a blob may be imported.
+
+        Before `import_`, `import_with`, `import_batch` and `import_json_updates` apply their
+        updates, the callback receives an [ImportRequest] describing them: the decoded blob
+        metadata, the peers and spans of their changes and, on demand, the containers they
+        touch. The blobs of `import_batch` are described together by a single request. If it
+        returns a falsy value the import fails without changing the document; exceptions it
+        raises are propagated. Servers can use it to reject updates from unauthorized peers or
+        to read-only containers.
+
+        The updates are imported into a fork of the document first, so the request describes
+        the changes they really contain whatever the blob headers claim. This costs about as
+        much as the import itself.
+
+        Pass `None` to remove it.
         """
         ...
 
-    def get_container(self, id: ContainerID) -> typing.Optional[Container]:
+    def set_encryption(
+        self,
+        encrypt: typing.Optional[typing.Callable[[bytes], bytes]],
+        decrypt: typing.Optional[typing.Callable[[bytes], bytes]],
+    ) -> None:
         r"""
-        Get a container by its ID.
+        Set the callbacks that encrypt the blobs returned by `export`, `export_chunks` and
+        `gc`, and decrypt the blobs passed to `import_`, `import_with` and `import_batch`.
+
+        Both take and return `bytes`. Encrypted blobs are tagged, so importing a plain blob while
+        a `decrypt` callback is set fails, and so does importing an encrypted blob without one;
+        plain and encrypted snapshots can't be mixed up silently. Import filters see the
+        decrypted blob. Pass `None` to remove a callback.
+
+        JSON can't carry an encrypted payload, so `export_json_updates` and
+        `export_json_in_id_span` raise while an `encrypt` callback is set instead of leaking the
+        plain history.
         """
         ...
 
-    def get_cursor_pos(self, cursor: Cursor) -> PosQueryResult:
+    def set_quota_hook(
+        self, callback: typing.Optional[typing.Callable[[QuotaRequest], bool]]
+    ) -> None:
         r"""
-        Get the absolute position of the given cursor.
+        Set a callback that can veto commits and imports that would make the document too
+        large, to enforce per-document quotas.
 
-        # Example
+        Before `commit`, `commit_with`, `import_`, `import_with` and `import_batch`, the callback
+        receives a [QuotaRequest] with the number of ops the operation would add, the size of
+        the imported blobs and the current number of ops. If it returns a falsy value the
+        operation fails; exceptions it raises are propagated. A rejected import leaves the
+        document unchanged. Loro can't discard the ops of a transaction, so the ops of a
+        rejected commit stay pending: the next `commit` asks the hook again, while the implicit
+        commits done by other methods, e.g. `export`, commit them without asking. Check the
+        quota before making large edits when that matters.
 
-        ```
-        # use loro::{LoroDoc, ToJson};
-        let doc = LoroDoc::new();
-        let text = &doc.get_text("text");
-        text.insert(0, "01234").unwrap();
-        let pos = text.get_cursor(5, Default::default()).unwrap();
-        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 5);
-        text.insert(0, "01234").unwrap();
-        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 10);
-        text.delete(0, 10).unwrap();
-        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 0);
-        text.insert(0, "01234").unwrap();
-        assert_eq!(doc.get_cursor_pos(&pos).unwrap().current.pos, 5);
-        ```
+        Pass `None` to remove it.
         """
         ...
 
-    def free_history_cache(self) -> None:
+    def set_pending_hook(
+        self, callback: typing.Optional[typing.Callable[[list[IdSpan]], None]]
+    ) -> None:
         r"""
-        Free the history cache that is used for making checkout faster.
+        Set a callback invoked when imported changes that were waiting for their dependencies
+        get applied.
 
-        If you use checkout that switching to an old/concurrent version, the history cache will be built.
-        You can free it by calling this method.
+        The callback receives the list of `IdSpan`s applied, out of those reported by
+        `pending_changes`. Exceptions raised by the callback are reported with
+        `sys.unraisablehook` instead of failing the import.
+
+        Pass `None` to remove it.
         """
         ...
 
-    def free_diff_calculator(self) -> None:
+    def pending_changes(self) -> list[IdSpan]:
         r"""
-        Free the cached diff calculator that is used for checkout.
+        Get the spans of the imported changes waiting for missing dependencies, by peer.
+
+        They are applied once the changes they depend on are imported; `missing_spans` with
+        the version of the peer that sent them tells what to request. The imports made
+        through any `LoroDoc` object of the document are tracked.
         """
         ...
 
-    def compact_change_store(self) -> None:
+    def set_readonly(self, readonly: bool, allow_import: bool = True) -> None:
         r"""
-        Encoded all ops and history cache to bytes and store them in the kv store.
+        Make the local edits of the document fail, e.g. in viewer processes or to guard
+        against accidental writes in request handlers.
 
-        This will free up the memory that used by parsed ops
+        The document is detached with detached editing disabled, so every mutation raises.
+        Imports are still applied and the state follows them, unless `allow_import` is false:
+        then imports raise too. Their events are reported as `EventTriggerKind.Import` with the
+        origin of the import, as on a writable document, and `is_detached` leaves out the
+        detaching done by this mode. `checkout` still works for viewing older versions, and
+        `attach` or `checkout_to_latest` go back to the latest version without allowing
+        edits. `set_readonly(False)` restores the detached state and the detached editing
+        setting the document had before, attaching it again unless it was detached.
         """
         ...
 
-    def export(self, mode: ExportMode) -> bytes:
+    def is_readonly(self) -> bool:
         r"""
-        Export the document in the given mode.
+        Whether the document was made read-only with `set_readonly`.
         """
         ...
 
@@ -863,7 +1749,9 @@ class LoroDoc:
         """
         ...
 
-    def config_default_text_style(self, text_style: typing.Optional[ExpandType] = None) -> None:
+    def config_default_text_style(
+        self, text_style: typing.Optional[typing.Union[ExpandType, str]] = None
+    ) -> None:
         r"""
         Configures the default text style for the document.
 
@@ -897,6 +1785,9 @@ class LoroDoc:
         r"""
         Set whether to hide empty root containers.
 
+        Root containers are created on first access, so reading a root that was never
+        written leaves an empty container in `get_deep_value()`. Hidden, they don't show up.
+
         # Example
         ```
         use loro::LoroDoc;
@@ -910,7 +1801,15 @@ class LoroDoc:
         """
         ...
     
-    def delete_root_container(self, cid: ContainerID) -> None:
+    @typing.overload
+    def delete_root_container(self, cid: ContainerID) -> None: ...
+    @typing.overload
+    def delete_root_container(self, cid: str, container_type: ContainerType) -> None: ...
+    def delete_root_container(
+        self,
+        cid: typing.Union[ContainerID, str],
+        container_type: typing.Optional[ContainerType] = None,
+    ) -> None:
         r"""
         Delete all content from a root container and hide it from the document.
 
@@ -918,7 +1817,9 @@ class LoroDoc:
         - It won't show up in `get_deep_value()` results
         - It won't be included in document snapshots
 
-        Only works on root containers (containers without parents).
+        Only works on root containers (containers without parents). `cid` is the ContainerID
+        of the container, or its name if `container_type` is given, e.g.
+        `doc.delete_root_container("drafts", ContainerType.Map())`.
         """
         ...
     
@@ -968,6 +1869,15 @@ class LoroDoc:
         """
         ...
 
+    def is_container_deleted(self, id: ContainerID) -> bool:
+        r"""
+        Check if the target container was deleted, e.g. because its parent container or the
+        map entry holding it was removed.
+
+        Returns `False` if the doc doesn't contain the container, see `has_container`.
+        """
+        ...
+
     def export_json_in_id_span(self, id_span: IdSpan) -> str:
         r"""
         Exports changes within the specified ID span to JSON schema format.
@@ -978,6 +1888,8 @@ class LoroDoc:
         This method can also export pending changes from the uncommitted transaction that have not yet been applied to the OpLog.
 
         This method will NOT trigger a new commit implicitly.
+
+        Raise if an encryption callback is set with `set_encryption`.
         """
         ...
 
@@ -1010,7 +1922,40 @@ class LoroDoc:
         """
         ...
 
+    def apply_ops(
+        self,
+        ops: typing.Sequence[
+            typing.Tuple[
+                typing.Union[Container, ContainerID, str],
+                str,
+                typing.Sequence[typing.Any],
+            ]
+        ],
+        origin: typing.Optional[str] = None,
+        commit_msg: typing.Optional[str] = None,
+    ) -> None:
+        r"""
+        Apply a batch of edits across containers in a single call, then commit them.
+
+        Each op is a `(target, op, args)` tuple:
+
+        - `target`: a container, a `ContainerID` or a path accepted by `get_by_str_path`.
+        - `op`: the name of the edit, e.g. `"insert"`, `"delete"`, `"push"`, `"mark"`,
+          `"set"`, `"mov"`, `"increment"` or `"create"`.
+        - `args`: the arguments of the edit, in the same order as the container method.
+
+        Ops that were already pending are committed first, so the batch's change only holds
+        its own ops. The batch is committed like `commit_with`, so the quota hook set with
+        `set_quota_hook` can reject it, leaving its ops pending.
+
+        If an op fails, the error is raised after committing the ops applied before it. The
+        raised exception is the one of the op, with a note giving the index of the op.
+        """
+        ...
+
 class LoroList:
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
     is_attached: bool
     id: ContainerID
     def __new__(
@@ -1028,9 +1973,37 @@ class LoroList:
         """
         ...
 
-    def get(self, index: int) -> typing.Optional[ValueOrContainer]:
+    @typing.overload
+    def get(
+        self,
+        index: int,
+        default: None = None,
+        *,
+        resolve: typing.Literal[False] = False,
+    ) -> typing.Optional[ValueOrContainer]: ...
+    @typing.overload
+    def get(
+        self, index: int, default: _T, *, resolve: typing.Literal[False] = False
+    ) -> typing.Union[ValueOrContainer, _T]: ...
+    @typing.overload
+    def get(
+        self, index: int, default: None = None, *, resolve: typing.Literal[True]
+    ) -> typing.Optional[LoroValue]: ...
+    @typing.overload
+    def get(
+        self, index: int, default: _T, *, resolve: typing.Literal[True]
+    ) -> typing.Union[LoroValue, _T]: ...
+    def get(
+        self, index: int, default: typing.Any = None, *, resolve: bool = False
+    ) -> typing.Any:
         r"""
         Get the value at the given position.
+
+        With `resolve=True`, a child container is returned as its deep value, so the result is
+        a plain Python value.
+
+        Returns `default` if the index is out of range. A stored null is returned as a value,
+        so the length of the list tells the two apart.
         """
         ...
 
@@ -1040,6 +2013,12 @@ class LoroList:
         """
         ...
 
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state of the list, see `LoroDoc.state_hash`.
+        """
+        ...
+
     def get_value(self) -> LoroValue:
         r"""
         Get the shallow value of the container.
@@ -1198,6 +2177,24 @@ class LoroList:
         """
         ...
 
+    def iter_ids(self) -> ListIdIter:
+        r"""
+        Iterate over the `(index, id)` pairs of the list items, e.g. to key UI rows by the
+        stable IDs of the elements.
+
+        The IDs are read one at a time as the iterator advances, so stopping early skips the
+        rest. Edits made while iterating affect the items not reached yet, like for a Python
+        `list`.
+        """
+        ...
+
+    def to_py(self) -> LoroValue:
+        r"""
+        The items of the list as a Python `list`, with child containers converted to
+        plain values recursively, the same as in `LoroDoc.get_deep_value`.
+        """
+        ...
+
     def doc(self) -> typing.Optional[LoroDoc]:
         r"""
         Get the LoroDoc of the container.
@@ -1221,6 +2218,8 @@ class LoroList:
         ...
 
 class LoroMap:
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
     is_attached: bool
     id: ContainerID
     def __new__(
@@ -1246,7 +2245,6 @@ class LoroMap:
 
     def __contains__(self, key: str) -> bool: ...
 
-    @typing.overload
     def __getitem__(self, key: str) -> ValueOrContainer: ...
 
     def __setitem__(self, key: str, value: LoroValue) -> None: ...
@@ -1259,9 +2257,37 @@ class LoroMap:
         """
         ...
 
-    def get(self, key: str) -> typing.Optional[ValueOrContainer]:
+    @typing.overload
+    def get(
+        self,
+        key: str,
+        default: None = None,
+        *,
+        resolve: typing.Literal[False] = False,
+    ) -> typing.Optional[ValueOrContainer]: ...
+    @typing.overload
+    def get(
+        self, key: str, default: _T, *, resolve: typing.Literal[False] = False
+    ) -> typing.Union[ValueOrContainer, _T]: ...
+    @typing.overload
+    def get(
+        self, key: str, default: None = None, *, resolve: typing.Literal[True]
+    ) -> typing.Optional[LoroValue]: ...
+    @typing.overload
+    def get(
+        self, key: str, default: _T, *, resolve: typing.Literal[True]
+    ) -> typing.Union[LoroValue, _T]: ...
+    def get(
+        self, key: str, default: typing.Any = None, *, resolve: bool = False
+    ) -> typing.Any:
         r"""
         Get the value of the map with the given key.
+
+        With `resolve=True`, a child container is returned as its deep value, so the result is
+        a plain Python value.
+
+        Returns `default` if the key is missing, like `dict.get`. A stored null is returned
+        as a value, so `key in map` tells the two apart.
         """
         ...
 
@@ -1300,6 +2326,12 @@ class LoroMap:
         """
         ...
 
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state of the map, see `LoroDoc.state_hash`.
+        """
+        ...
+
     def get_or_create_container(self, key: str, child: Container) -> Container:
         r"""
         Get or create a container with the given key.
@@ -1332,6 +2364,39 @@ class LoroMap:
         """
         ...
 
+    def get_all(self, key: str) -> list[tuple[int, typing.Optional[LoroValue]]]:
+        r"""
+        The values written concurrently to the key, with the peers that wrote them.
+
+        The map keeps the last write, but peers may have set the key at the same time without
+        seeing each other's value. This returns all of them, the current value first, so the
+        application can tell "someone else also set this". A deletion has the value `None`.
+
+        It reads the history up to the current state, so a document checked out at an older
+        version gets the writes of that version. Every change is visited, but only the ones
+        that touch the map are decoded, so the cost grows with the size of the history.
+        Returns an empty list if the map is detached or the key was never written.
+        """
+        ...
+
+    def get_entry_meta(self, key: str) -> typing.Optional[MapEntryMeta]:
+        r"""
+        The op id, peer and lamport timestamp of the write that set the current value of the
+        key, e.g. to explain which of the concurrent writes returned by `get_all` won.
+
+        It reads the history up to the current state like `get_all`, so a document checked out
+        at an older version gets the write of the value it shows. Returns `None` if the map is
+        detached, the key is unset or deleted, or the write was trimmed by a shallow snapshot.
+        """
+        ...
+
+    def to_py(self) -> LoroValue:
+        r"""
+        The entries of the map as a Python `dict`, with child containers converted to
+        plain values recursively, the same as in `LoroDoc.get_deep_value`.
+        """
+        ...
+
     def doc(self) -> typing.Optional[LoroDoc]:
         r"""
         Get the LoroDoc of the container.
@@ -1355,6 +2420,8 @@ class LoroMap:
         ...
 
 class LoroMovableList:
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
     id: ContainerID
     is_attached: bool
     def __new__(
@@ -1372,9 +2439,37 @@ class LoroMovableList:
         """
         ...
 
-    def get(self, index: int) -> typing.Optional[ValueOrContainer]:
+    @typing.overload
+    def get(
+        self,
+        index: int,
+        default: None = None,
+        *,
+        resolve: typing.Literal[False] = False,
+    ) -> typing.Optional[ValueOrContainer]: ...
+    @typing.overload
+    def get(
+        self, index: int, default: _T, *, resolve: typing.Literal[False] = False
+    ) -> typing.Union[ValueOrContainer, _T]: ...
+    @typing.overload
+    def get(
+        self, index: int, default: None = None, *, resolve: typing.Literal[True]
+    ) -> typing.Optional[LoroValue]: ...
+    @typing.overload
+    def get(
+        self, index: int, default: _T, *, resolve: typing.Literal[True]
+    ) -> typing.Union[LoroValue, _T]: ...
+    def get(
+        self, index: int, default: typing.Any = None, *, resolve: bool = False
+    ) -> typing.Any:
         r"""
         Get the value at the given position.
+
+        With `resolve=True`, a child container is returned as its deep value, so the result is
+        a plain Python value.
+
+        Returns `default` if the index is out of range. A stored null is returned as a value,
+        so the length of the list tells the two apart.
         """
         ...
 
@@ -1406,6 +2501,12 @@ class LoroMovableList:
         """
         ...
 
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state of the list, see `LoroDoc.state_hash`.
+        """
+        ...
+
     def pop(self) -> typing.Optional[ValueOrContainer]:
         r"""
         Pop the last element of the list.
@@ -1524,12 +2625,18 @@ class LoroMovableList:
         """
         ...
 
-    def for_each(self, f: typing.Any) -> None:
+    def for_each(self, f: typing.Callable[[ValueOrContainer], None]) -> None:
         r"""
         Iterate over the elements of the list.
         """
         ...
 
+    @typing.overload
+    def __getitem__(self, index: int) -> ValueOrContainer: ...
+
+    @typing.overload
+    def __getitem__(self, index: slice) -> list[ValueOrContainer]: ...
+
     @typing.overload
     def __setitem__(self, index: int, value: LoroValue) -> None: ...
 
@@ -1560,6 +2667,13 @@ class LoroMovableList:
         """
         ...
 
+    def to_py(self) -> LoroValue:
+        r"""
+        The items of the list as a Python `list`, with child containers converted to
+        plain values recursively, the same as in `LoroDoc.get_deep_value`.
+        """
+        ...
+
     def doc(self) -> typing.Optional[LoroDoc]:
         r"""
         Get the LoroDoc of the container.
@@ -1590,6 +2704,10 @@ class LoroText:
     len_utf16: int
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
+    def _repr_html_(self) -> str: ...
+    def _repr_markdown_(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
+    def __rich__(self) -> typing.Any: ...
     def __len__(self) -> int: ...
     @typing.overload
     def __getitem__(self, index: int) -> str: ...
@@ -1689,7 +2807,7 @@ class LoroText:
         It uses Myers' diff algorithm to compute the optimal difference.
 
         This could take a long time for large texts (e.g. > 50_000 characters).
-        In that case, you should use `updateByLine` instead.
+        In that case, you should use `update_by_line` instead.
         """
         ...
 
@@ -1777,12 +2895,39 @@ class LoroText:
         """
         ...
 
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state of the text, with its styles, see `LoroDoc.state_hash`.
+        """
+        ...
+
     def to_string(self) -> str:
         r"""
         Get the text content of the text container.
         """
         ...
 
+    def to_string_at(self, frontiers: Frontiers) -> str:
+        r"""
+        Get the text content at the given version of the document.
+
+        The text is read from a fork of the document, so it's O(n) like `LoroDoc.fork_at`, and
+        the document is left at its current version. Raises if `frontiers` isn't a version of
+        the document.
+        """
+        ...
+
+    def to_delta_at(self, frontiers: Frontiers) -> list[TextDelta]:
+        r"""
+        Get the text in [Delta](https://quilljs.com/docs/delta/) format at the given version
+        of the document.
+
+        The text is read from a fork of the document, so it's O(n) like `LoroDoc.fork_at`, and
+        the document is left at its current version. Raises if `frontiers` isn't a version of
+        the document.
+        """
+        ...
+
     def get_cursor(self, pos: int, side: Side) -> typing.Optional[Cursor]:
         r"""
         Get the cursor at the given position in the given Unicode position.
@@ -1836,6 +2981,13 @@ class LoroText:
         """
         ...
 
+    def to_py(self) -> str:
+        r"""
+        The content of the text as a `str`, the same as `to_string`. Rich text styles
+        are dropped, see `to_delta` to keep them.
+        """
+        ...
+
     def doc(self) -> typing.Optional[LoroDoc]:
         r"""
         Get the LoroDoc of the container.
@@ -1859,18 +3011,23 @@ class LoroText:
         ...
 
 class LoroTree:
+    def __repr__(self) -> str: ...
+    def _repr_html_(self) -> str: ...
+    def _repr_markdown_(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
+    def __rich__(self) -> typing.Any: ...
     is_attached: bool
     roots: list[TreeID]
     id: ContainerID
-    def __contains__(self, target: TreeID) -> bool: ...
+    def __contains__(self, target: TreeIdLike) -> bool: ...
     def __new__(
         cls,
     ): ...
-    def create(self, parent: typing.Optional[TreeID] = None) -> TreeID:
+    def create(self, parent: TreeParent = None) -> TreeID:
         r"""
         Create a new tree node and return the [`TreeID`].
 
-        If the `parent` is `None`, the created node is the root of a tree.
+        If the `parent` is `None` or `"root"`, the created node is the root of a tree.
         Otherwise, the created node is a child of the parent tree node.
 
         # Example
@@ -1888,11 +3045,11 @@ class LoroTree:
         """
         ...
 
-    def create_at(self, index: int, parent: typing.Optional[TreeID] = None) -> TreeID:
+    def create_at(self, index: int, parent: TreeParent = None) -> TreeID:
         r"""
         Create a new tree node at the given index and return the [`TreeID`].
 
-        If the `parent` is `None`, the created node is the root of a tree.
+        If the `parent` is `None` or `"root"`, the created node is the root of a tree.
         If the `index` is greater than the number of children of the parent, error will be returned.
 
         # Example
@@ -1912,11 +3069,11 @@ class LoroTree:
         """
         ...
 
-    def mov(self, target: TreeID, parent: typing.Optional[TreeID] = None) -> None:
+    def mov(self, target: TreeIdLike, parent: TreeParent = None) -> None:
         r"""
         Move the `target` node to be a child of the `parent` node.
 
-        If the `parent` is `None`, the `target` node will be a root.
+        If the `parent` is `None` or `"root"`, the `target` node will be a root.
 
         # Example
 
@@ -1933,12 +3090,10 @@ class LoroTree:
         """
         ...
 
-    def mov_to(
-        self, target: TreeID, to: int, parent: typing.Optional[TreeID] = None
-    ) -> None:
+    def mov_to(self, target: TreeIdLike, to: int, parent: TreeParent = None) -> None:
         r"""
         Move the `target` node to be a child of the `parent` node at the given index.
-        If the `parent` is `None`, the `target` node will be a root.
+        If the `parent` is `None` or `"root"`, the `target` node will be a root.
 
         # Example
 
@@ -1957,7 +3112,7 @@ class LoroTree:
         """
         ...
 
-    def mov_after(self, target: TreeID, after: TreeID) -> None:
+    def mov_after(self, target: TreeIdLike, after: TreeIdLike) -> None:
         r"""
         Move the `target` node to be a child after the `after` node with the same parent.
 
@@ -1978,7 +3133,7 @@ class LoroTree:
         """
         ...
 
-    def mov_before(self, target: TreeID, before: TreeID) -> None:
+    def mov_before(self, target: TreeIdLike, before: TreeIdLike) -> None:
         r"""
         Move the `target` node to be a child before the `before` node with the same parent.
 
@@ -1999,7 +3154,7 @@ class LoroTree:
         """
         ...
 
-    def delete(self, target: TreeID) -> None:
+    def delete(self, target: TreeIdLike) -> None:
         r"""
         Delete a tree node.
 
@@ -2019,7 +3174,7 @@ class LoroTree:
         """
         ...
 
-    def get_meta(self, target: TreeID) -> LoroMap:
+    def get_meta(self, target: TreeIdLike) -> LoroMap:
         r"""
         Get the associated metadata map handler of a tree node.
 
@@ -2036,7 +3191,7 @@ class LoroTree:
         """
         ...
 
-    def parent(self, target: TreeID) -> typing.Optional[typing.Optional[TreeID]]:
+    def parent(self, target: TreeIdLike) -> typing.Optional[typing.Optional[TreeID]]:
         r"""
         Return the parent of target node.
 
@@ -2045,13 +3200,13 @@ class LoroTree:
         """
         ...
 
-    def contains(self, target: TreeID) -> bool:
+    def contains(self, target: TreeIdLike) -> bool:
         r"""
         Return whether target node exists. including deleted node.
         """
         ...
 
-    def is_node_deleted(self, target: TreeID) -> bool:
+    def is_node_deleted(self, target: TreeIdLike) -> bool:
         r"""
         Return whether target node is deleted.
 
@@ -2073,25 +3228,37 @@ class LoroTree:
         """
         ...
 
-    def children(
-        self, parent: typing.Optional[TreeID] = None
-    ) -> typing.Optional[list[TreeID]]:
+    def get_nodes_at(
+        self, frontiers: Frontiers, with_deleted: bool = False
+    ) -> list[tuple[TreeNode, LoroValue]]:
+        r"""
+        Return all nodes at the given version of the document, each with the deep value of
+        its metadata map. If `with_deleted` is true, the deleted nodes will be included.
+
+        The document is left at its current version, see `LoroDoc.get_value_at`. Raises if
+        `frontiers` isn't a version of the document.
+        """
+        ...
+
+    def children(self, parent: TreeParent = None) -> typing.Optional[list[TreeID]]:
         r"""
         Return all children of the target node.
 
-        If the parent node does not exist, return `None`.
+        `parent` is a node, `None` or `"root"` for the roots, or `"deleted"` for the roots
+        of the deleted subtrees. If the parent node does not exist, return `None`.
         """
         ...
 
-    def children_num(
-        self, parent: typing.Optional[TreeID] = None
-    ) -> typing.Optional[int]:
+    def children_num(self, parent: TreeParent = None) -> typing.Optional[int]:
         r"""
         Return the number of children of the target node.
+
+        `parent` is a node, `None` or `"root"` for the roots, or `"deleted"` for the roots
+        of the deleted subtrees.
         """
         ...
 
-    def fractional_index(self, target: TreeID) -> typing.Optional[str]:
+    def fractional_index(self, target: TreeIdLike) -> typing.Optional[str]:
         r"""
         Return the fractional index of the target node with hex format.
         """
@@ -2112,6 +3279,12 @@ class LoroTree:
         """
         ...
 
+    def state_hash(self) -> int:
+        r"""
+        A stable hash of the current state of the tree, with the metadata of its nodes, see `LoroDoc.state_hash`.
+        """
+        ...
+
     def is_fractional_index_enabled(self) -> bool:
         r"""
         Whether the fractional index is enabled.
@@ -2146,17 +3319,58 @@ class LoroTree:
         """
         ...
 
-    def get_last_move_id(self, target: TreeID) -> typing.Optional[ID]:
+    def get_last_move_id(self, target: TreeIdLike) -> typing.Optional[ID]:
         r"""
         Get the last move id of the target node.
         """
         ...
 
+    def to_py(self) -> LoroValue:
+        r"""
+        The roots of the tree as a Python `list` of `dict`s, with their `children` nested and
+        their metadata maps converted to plain values, the same as `get_value_with_meta`.
+        """
+        ...
+
+    def doc(self) -> typing.Optional[LoroDoc]:
+        r"""
+        Get the LoroDoc of the container.
+        """
+        ...
+    
+    def subscribe(self, callback: typing.Callable[[DiffEvent], None]) -> typing.Optional[Subscription]:
+        r"""
+        Subscribe the events of a container.
+        
+        The callback will be invoked when the container is changed.
+        Returns a subscription that can be used to unsubscribe.
+        
+        The events will be emitted after a transaction is committed. A transaction is committed when:
+        
+        - `doc.commit()` is called.
+        - `doc.export(mode)` is called.
+        - `doc.import(data)` is called.
+        - `doc.checkout(version)` is called.
+        """
+        ...
+
+class LoroUnknown:
+    def __repr__(self) -> str: ...
+    def __rich_repr__(self) -> typing.List[typing.Any]: ...
+    id: ContainerID
+
     def doc(self) -> typing.Optional[LoroDoc]:
         r"""
         Get the LoroDoc of the container.
         """
         ...
+
+    def to_py(self) -> ContainerID:
+        r"""
+        Get the container id, which stands for the content of an unknown container in
+        `LoroDoc.get_deep_value`.
+        """
+        ...
     
     def subscribe(self, callback: typing.Callable[[DiffEvent], None]) -> typing.Optional[Subscription]:
         r"""
@@ -2174,39 +3388,120 @@ class LoroTree:
         """
         ...
 
-class LoroUnknown:
-    id: ContainerID
-
-    def doc(self) -> typing.Optional[LoroDoc]:
+class MergeConflict:
+    r"""
+    A map key written by both sides of a `merge`.
+
+    A value is `None` if the key was deleted. `value` is the one the merge kept.
+    """
+    def __repr__(self) -> str: ...
+    container: ContainerID
+    key: str
+    ours: typing.Optional[ValueOrContainer]
+    theirs: typing.Optional[ValueOrContainer]
+    value: typing.Optional[ValueOrContainer]
+
+class MapDelta:
+    def __repr__(self) -> str: ...
+    updated: dict[str, typing.Optional[ValueOrContainer]]
+
+class OpInfo:
+    r"""
+    An op of a change, described for inspection.
+    """
+    def __repr__(self) -> str: ...
+    id: ID
+    container: ContainerID
+    kind: str
+    content: str
+
+class MapEntryMeta:
+    r"""
+    The write that set the current value of a map entry.
+    """
+    def __repr__(self) -> str: ...
+    id: ID
+    peer: int
+    lamport: int
+
+class TimelinePoint:
+    r"""
+    A version of the history, returned by `LoroDoc.timeline`.
+    """
+    def __repr__(self) -> str: ...
+    frontiers: Frontiers
+    changes: int
+    change: ChangeMeta
+
+class OperationMetrics:
+    r"""
+    The measurements of one document operation, passed to the hook set by
+    `LoroDoc.set_metrics_hook`.
+    """
+    def __repr__(self) -> str: ...
+    operation: str
+    duration: float
+    bytes: int
+    ops: int
+
+class ImportRequest:
+    r"""
+    An import about to be applied, passed to the filter set by `LoroDoc.set_import_filter`.
+    """
+    def __repr__(self) -> str: ...
+    @property
+    def meta(self) -> typing.Optional[ImportBlobMetadata]:
+        r"""
+        The metadata decoded from the header of the blob, `None` for JSON updates and for
+        batches of several blobs.
+
+        The header is written by the sender, so `peers` and `spans`, which come from the
+        decoded changes, are what filters should trust.
+        """
+        ...
+
+    @property
+    def peers(self) -> list[int]:
+        r"""
+        The peers that authored the new changes of the import.
+        """
+        ...
+
+    @property
+    def spans(self) -> list[IdSpan]:
         r"""
-        Get the LoroDoc of the container.
+        The new ops of the import, sorted by peer.
+
+        Ops the document already has are left out, while the ops waiting for missing
+        dependencies are included.
         """
         ...
-    
-    def subscribe(self, callback: typing.Callable[[DiffEvent], None]) -> typing.Optional[Subscription]:
+
+    @property
+    def containers(self) -> list[ContainerID]:
         r"""
-        Subscribe the events of a container.
-        
-        The callback will be invoked when the container is changed.
-        Returns a subscription that can be used to unsubscribe.
-        
-        The events will be emitted after a transaction is committed. A transaction is committed when:
-        
-        - `doc.commit()` is called.
-        - `doc.export(mode)` is called.
-        - `doc.import(data)` is called.
-        - `doc.checkout(version)` is called.
+        The containers the new ops of the import apply to, decoded on first access.
         """
         ...
 
-class MapDelta:
-    updated: dict[str, typing.Optional[ValueOrContainer]]
+class QuotaRequest:
+    r"""
+    The growth a commit or an import would cause, passed to the hook set by
+    `LoroDoc.set_quota_hook`.
+    """
+    def __repr__(self) -> str: ...
+    operation: str
+    ops: int
+    bytes: typing.Optional[int]
+    doc_ops: int
 
 class PathItem:
+    def __repr__(self) -> str: ...
     container: ContainerID
     index: Index
 
 class PeerInfo:
+    def __repr__(self) -> str: ...
     state: LoroValue
     counter: int
     timestamp: int
@@ -2215,16 +3510,65 @@ class PosQueryResult:
     update: typing.Optional[Cursor]
     current: AbsolutePosition
 
+class StorageStats:
+    r"""
+    The size of a document, returned by `LoroDoc.storage_stats`.
+    """
+    def __repr__(self) -> str: ...
+    state_bytes: int
+    oplog_bytes: int
+    changes: int
+    ops: int
+    containers: int
+    is_shallow: bool
+
 class StyleConfigMap:
     def __new__(
         cls,
-    ): ...
-    def insert(self, key: str, value: ExpandType) -> None: ...
+        styles: typing.Optional[
+            typing.Mapping[str, typing.Union[ExpandType, str]]
+        ] = None,
+    ):
+        r"""
+        Create a config, optionally from a dict such as `{"bold": ExpandType.After}`.
+
+        The values can also be the names of the expand types: `"before"`, `"after"`, `"both"`
+        or `"none"`.
+        """
+        ...
+
+    def __repr__(self) -> str: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, key: str) -> bool: ...
+    def __iter__(self) -> typing.Iterator[str]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def insert(self, key: str, value: typing.Union[ExpandType, str]) -> None:
+        r"""
+        Set how the style `key` expands. Raises `ValueError` if the key contains `':'`.
+        """
+        ...
+
     def get(self, key: str) -> typing.Optional[ExpandType]: ...
+    def keys(self) -> list[str]:
+        r"""
+        The configured style keys, in insertion order.
+
+        For a config read from a document, these are the keys set with `config_text_style`
+        and the keys of the default rich text config.
+        """
+        ...
+
+    def to_dict(self) -> dict[str, ExpandType]:
+        r"""
+        The config as a dict from style key to [ExpandType].
+        """
+        ...
+
     @classmethod
     def default_rich_text_config(cls) -> StyleConfigMap: ...
 
 class Subscription:
+    def __repr__(self) -> str: ...
     def detach(self) -> None:
         """
         Detaches the subscription from this handle. The callback will
@@ -2245,17 +3589,44 @@ class Subscription:
         """
         ...
 
+class SubscriptionInfo:
+    r"""
+    A subscription made through a `LoroDoc`, listed by `LoroDoc.active_subscriptions`.
+    """
+    def __repr__(self) -> str: ...
+    kind: str
+    target: typing.Optional[str]
+    created_at: typing.Optional[str]
+
 class TreeDiff:
+    def __repr__(self) -> str: ...
     diff: list[TreeDiffItem]
 
 class TreeDiffItem:
+    def __repr__(self) -> str: ...
     target: TreeID
     action: TreeExternalDiff
 
 class TreeID:
-    peer: int
-    counter: int
+    r"""
+    The id of a tree node. `str()` gives the `counter@peer` form of loro-js, e.g. `12@9876`,
+    which `TreeID.parse` reads back and the `LoroTree` methods accept in place of a `TreeID`.
+    """
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    @property
+    def peer(self) -> int: ...
+    @property
+    def counter(self) -> int: ...
     def __new__(cls, peer: int, counter: int): ...
+    @staticmethod
+    def parse(s: str) -> TreeID:
+        r"""
+        Parse the `counter@peer` form given by `str()`, e.g. `TreeID.parse("12@9876")`.
+        """
+        ...
 
 class TreeNode:
     r"""
@@ -2268,12 +3639,52 @@ class TreeNode:
     index: int
 
 class UndoItemMeta:
+    r"""
+    The metadata of an undo stack item, returned by the `on_push` listener and passed back
+    to the `on_pop` listener.
+
+    It usually stores the selection before the change, so it can be restored after undoing
+    it.
+    """
+
     value: LoroValue
     cursors: list[CursorWithPos]
+    def __new__(
+        cls,
+        value: typing.Optional[LoroValue] = None,
+        cursors: list[CursorWithPos] = [],
+    ): ...
+    def __repr__(self) -> str: ...
+    def transform_cursors(self, doc: LoroDoc) -> list[CursorWithPos]:
+        r"""
+        The stored cursors with their positions in the current state of `doc`, e.g. after
+        the item was undone or redone.
+
+        Cursors whose container was deleted are left out.
+        """
+        ...
 
 class CursorWithPos:
     cursor: Cursor
     pos: AbsolutePosition
+    def __new__(cls, cursor: Cursor, pos: AbsolutePosition): ...
+    def __repr__(self) -> str: ...
+    @staticmethod
+    def capture(doc: LoroDoc, cursor: Cursor) -> CursorWithPos:
+        r"""
+        Capture `cursor` with its current position in `doc`.
+
+        Get the cursor of a selection end with e.g. `LoroText.get_cursor`.
+        """
+        ...
+
+    def transform(self, doc: LoroDoc) -> CursorWithPos:
+        r"""
+        This cursor with its position in the current state of `doc`.
+
+        Fails if the container of the cursor was deleted.
+        """
+        ...
 
 class UndoManager:
     def __new__(cls, doc: LoroDoc): ...
@@ -2413,6 +3824,13 @@ class UndoManager:
         ...
 
 class VersionVector:
+    def __repr__(self) -> str: ...
+    def __bytes__(self) -> bytes: ...
+    def __reduce__(self) -> tuple[typing.Any, ...]:
+        r"""
+        Pickle the version vector as its encoded bytes, e.g. to keep it in a session store.
+        """
+        ...
     def __new__(
         cls,
     ): ...
@@ -2477,11 +3895,39 @@ class VersionVector:
     @classmethod
     def decode(cls, bytes: bytes) -> VersionVector: ...
 
+class VersionInfo:
+    r"""
+    The versions of a document's state and history, returned by `LoroDoc.version_info`.
+    """
+    def __repr__(self) -> str: ...
+    state_vv: VersionVector
+    oplog_vv: VersionVector
+    state_frontiers: Frontiers
+    oplog_frontiers: Frontiers
+
+class SyncGap:
+    r"""
+    The changes two peers have to exchange to catch up, returned by `LoroDoc.missing_spans`.
+
+    `missing` are the spans the remote peer has and the local document lacks, `ahead` the
+    spans the local document has and the remote peer lacks.
+    """
+    def __repr__(self) -> str: ...
+    def __bool__(self) -> bool:
+        r"""
+        Whether the peers have changes to exchange.
+        """
+        ...
+    missing: list[IdSpan]
+    ahead: list[IdSpan]
+
 class VersionVectorDiff:
+    def __repr__(self) -> str: ...
     retreat: VersionRange
     forward: VersionRange
 
 class VersionRange:
+    def __repr__(self) -> str: ...
     is_empty: bool
     def __new__(
         cls,
@@ -2503,18 +3949,24 @@ class VersionRange:
     def inner(self) -> dict[int, tuple[int, int]]: ...
 
 class ContainerID:
+    def __repr__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
     class Root(ContainerID):
+        __match_args__ = ("name", "container_type")
         def __init__(self, name: str, container_type: ContainerType): ...
         name: str
         container_type: ContainerType
 
     class Normal(ContainerID):
+        __match_args__ = ("peer", "counter", "container_type")
         def __init__(self, peer: int, counter: int, container_type: ContainerType): ...
         peer: int
         counter: int
         container_type: ContainerType
 
 class ContainerType:
+    def __repr__(self) -> str: ...
     class Text(ContainerType):
         pass
 
@@ -2534,32 +3986,40 @@ class ContainerType:
         pass
 
     class Unknown(ContainerType):
+        __match_args__ = ("kind",)
         def __init__(self, kind: int): ...
         kind: int
 
 class DiffBatch:
+    def __repr__(self) -> str: ...
     def __init__(self): ...
     def push(self, cid: ContainerID, diff: Diff) -> None: ...
     def get_diff(self) -> list[tuple[ContainerID, Diff]]: ...
 
 class Diff:
+    def __repr__(self) -> str: ...
     class List(Diff):
+        __match_args__ = ("diff",)
         def __init__(self, diff: list[ListDiffItem]): ...
         diff: list[ListDiffItem]
 
     class Text(Diff):
+        __match_args__ = ("diff",)
         def __init__(self, diff: list[TextDelta]): ...
         diff: list[TextDelta]
 
     class Map(Diff):
+        __match_args__ = ("diff",)
         def __init__(self, diff: MapDelta): ...
         diff: MapDelta
 
     class Tree(Diff):
+        __match_args__ = ("diff",)
         def __init__(self, diff: TreeDiff): ...
         diff: TreeDiff
 
     class Counter(Diff):
+        __match_args__ = ("diff",)
         def __init__(self, diff: float): ...
         diff: float
 
@@ -2567,20 +4027,34 @@ class Diff:
         pass
 
 class EncodedBlobMode(Enum):
-    Snapshot = "snapshot"
-    OutdatedSnapshot = "outdated_snapshot"
-    ShallowSnapshot = "shallow_snapshot"
-    OutdatedRle = "outdated_rle"
-    Updates = "updates"
+    Snapshot = 0
+    OutdatedSnapshot = 1
+    ShallowSnapshot = 2
+    OutdatedRle = 3
+    Updates = 4
+
 
+class DocAnalysis:
+    r"""
+    The per-container statistics of a document, returned by `LoroDoc.analyze`.
+    """
+    def __repr__(self) -> str: ...
+    def __len__(self) -> int: ...
+    containers: list[ContainerAnalysis]
+    total_size: int
+    def get(self, id: ContainerID) -> typing.Optional[ContainerAnalysis]:
+        r"""
+        Get the statistics of the container `id`, if it's in the document.
+        """
+        ...
 
 class EventTriggerKind(Enum):
     r"""
     The kind of the event trigger.
     """
-    Local = "local"
-    Import = "import"
-    Checkout = "checkout"
+    Local = 0
+    Import = 1
+    Checkout = 2
 
 class ExpandType(Enum):
     r"""
@@ -2589,111 +4063,262 @@ class ExpandType(Enum):
     - Before: when inserting new text before this style, the new text should inherit this style.
     - After: when inserting new text after this style, the new text should inherit this style.
     - Both: when inserting new text before or after this style, the new text should inherit this style.
-    - None: when inserting new text before or after this style, the new text should **not** inherit this style.
+    - Null: when inserting new text before or after this style, the new text should **not** inherit this style.
     """
-    Before = "before"
-    After = "after"
-    Both = "both"
-    Null = "null"
+    Before = 0
+    After = 1
+    Both = 2
+    Null = 3
+
+    @staticmethod
+    def from_str(s: str) -> ExpandType:
+        r"""
+        Parse the name of an expand type: `"before"`, `"after"`, `"both"` or `"none"`
+        (case-insensitive), `"none"` standing for [ExpandType::Null].
+        """
+        ...
+
+    def __str__(self) -> str:
+        r"""
+        The name of the expand type, as accepted by `from_str`.
+        """
+        ...
 
 class ExportMode:
+    @classmethod
+    def snapshot(cls) -> ExportMode:
+        r"""
+        Export the whole document.
+        """
+        ...
+    @classmethod
+    def updates(cls, from_: typing.Optional[VersionVector] = None) -> ExportMode:
+        r"""
+        Export the changes missing from `from_`, all of them by default.
+        """
+        ...
+    @classmethod
+    def updates_in_range(cls, spans: typing.Sequence[IdSpan]) -> ExportMode:
+        r"""
+        Export the changes within `spans`.
+        """
+        ...
+    @classmethod
+    def updates_in_time_range(cls, start_ts: int, end_ts: int) -> ExportMode:
+        r"""
+        Export the changes with `start_ts <= timestamp < end_ts`, in Unix seconds.
+
+        Raises `ValueError` if `start_ts` is after `end_ts`.
+        """
+        ...
+    @classmethod
+    def shallow_snapshot(cls, frontiers: Frontiers) -> ExportMode:
+        r"""
+        Export a snapshot without the history before `frontiers`.
+
+        Raises `ValueError` if `frontiers` is empty.
+        """
+        ...
+    @classmethod
+    def state_only(cls, frontiers: typing.Optional[Frontiers] = None) -> ExportMode:
+        r"""
+        Export the state at `frontiers`, the latest by default, without history.
+        """
+        ...
+    @classmethod
+    def snapshot_at(cls, version: Frontiers) -> ExportMode:
+        r"""
+        Export a snapshot of the document at `version`.
+        """
+        ...
+
     class Snapshot(ExportMode):
         pass
 
     class Updates(ExportMode):
+        __match_args__ = ("from_",)
         def __init__(self, from_: VersionVector): ...
         from_: VersionVector
 
     class UpdatesInRange(ExportMode):
+        __match_args__ = ("spans",)
         def __init__(self, spans: list[IdSpan]): ...
         spans: list[IdSpan]
 
     class ShallowSnapshot(ExportMode):
+        __match_args__ = ("frontiers",)
         def __init__(self, frontiers: Frontiers): ...
         frontiers: Frontiers
 
     class StateOnly(ExportMode):
+        __match_args__ = ("frontiers",)
         def __init__(self, frontiers: typing.Optional[Frontiers]): ...
         frontiers: typing.Optional[Frontiers]
 
     class SnapshotAt(ExportMode):
+        __match_args__ = ("version",)
         def __init__(self, version: Frontiers): ...
         version: Frontiers
 
+    class UpdatesInTimeRange(ExportMode):
+        r"""
+        The changes with `start_ts <= timestamp < end_ts`, in Unix seconds.
+        """
+        __match_args__ = ("start_ts", "end_ts")
+        def __init__(self, start_ts: int, end_ts: int): ...
+        start_ts: int
+        end_ts: int
+
 class Index:
+    r"""
+    A step of a path to a container: a map key, a list position or a tree node.
+
+    `str()` gives the key, the position or the node id as `counter@peer`, and a `Seq`
+    can be used as a list index. The APIs taking an `Index` also accept a `str` key, an
+    `int` position or a `TreeID` instead.
+    """
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def __index__(self) -> int: ...
     class Key(Index):
+        __match_args__ = ("key",)
         def __init__(self, key: str): ...
         key: str
 
     class Seq(Index):
+        __match_args__ = ("index",)
         def __init__(self, index: int): ...
         index: int
 
     class Node(Index):
+        __match_args__ = ("target",)
         def __init__(self, target: TreeID): ...
         target: TreeID
 
 class ListDiffItem:
+    r"""
+    An item of a list diff. Branch on `kind` or match the variants::
+
+        match item:
+            case ListDiffItem.Insert(values, is_move): ...
+            case ListDiffItem.Delete(n): ...
+            case ListDiffItem.Retain(n): ...
+    """
+    def __repr__(self) -> str: ...
+    @property
+    def kind(self) -> typing.Literal["insert", "delete", "retain"]:
+        r"""
+        The variant of the item: `"insert"`, `"delete"` or `"retain"`.
+        """
+        ...
+
+    @property
+    def len(self) -> int:
+        r"""
+        The number of elements the item inserts, deletes or retains.
+        """
+        ...
+
     class Insert(ListDiffItem):
+        __match_args__ = ("insert", "is_move")
         def __init__(self, insert: list[ValueOrContainer], is_move: bool): ...
         insert: list[ValueOrContainer]
         is_move: bool
 
     class Delete(ListDiffItem):
+        __match_args__ = ("delete",)
         def __init__(self, delete: int): ...
         delete: int
 
     class Retain(ListDiffItem):
+        __match_args__ = ("retain",)
         def __init__(self, retain: int): ...
         retain: int
 
 class Ordering(Enum):
-    Less = "less"
-    Equal = "equal"
-    Greater = "greater"
+    Less = 0
+    Equal = 1
+    Greater = 2
+    Concurrent = 3
 
 class Side(Enum):
-    Left = "left"
-    Middle = "middle"
-    Right = "right"
+    Left = -1
+    Middle = 0
+    Right = 1
 
 class PositionType(Enum):
-    Bytes = "bytes"
-    Unicode = "unicode"
-    Utf16 = "utf16"
-    Event = "event"
-    Entity = "entity"
+    Bytes = 0
+    Unicode = 1
+    Utf16 = 2
+    Event = 3
+    Entity = 4
 
 class TextDelta:
+    def __repr__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    @staticmethod
+    def retain(
+        retain: int, attributes: typing.Optional[typing.Mapping[str, LoroValue]] = None
+    ) -> TextDelta.Retain:
+        r"""
+        Keep the next `retain` characters, applying `attributes` to them if given.
+        """
+        ...
+
+    @staticmethod
+    def insert(
+        insert: str, attributes: typing.Optional[typing.Mapping[str, LoroValue]] = None
+    ) -> TextDelta.Insert:
+        r"""
+        Insert the text `insert` with the optional `attributes`.
+        """
+        ...
+
+    @staticmethod
+    def delete(delete: int) -> TextDelta.Delete:
+        r"""
+        Delete the next `delete` characters.
+        """
+        ...
+
     class Retain(TextDelta):
+        __match_args__ = ("retain", "attributes")
         def __init__(
             self,
             retain: int,
-            attributes: typing.Optional[typing.Mapping[str, LoroValue]],
+            attributes: typing.Optional[typing.Mapping[str, LoroValue]] = None,
         ): ...
         retain: int
         attributes: typing.Optional[dict[str, LoroValue]]
 
     class Insert(TextDelta):
+        __match_args__ = ("insert", "attributes")
         def __init__(
             self,
             insert: str,
-            attributes: typing.Optional[typing.Mapping[str, LoroValue]],
+            attributes: typing.Optional[typing.Mapping[str, LoroValue]] = None,
         ): ...
         insert: str
         attributes: typing.Optional[dict[str, LoroValue]]
 
     class Delete(TextDelta):
+        __match_args__ = ("delete",)
         def __init__(self, delete: int): ...
         delete: int
 
 class TreeExternalDiff:
+    def __repr__(self) -> str: ...
     class Create(TreeExternalDiff):
+        __match_args__ = ("parent", "index", "fractional_index")
+        def __init__(
+            self, parent: typing.Optional[TreeID], index: int, fractional_index: str
+        ): ...
         parent: typing.Optional[TreeID]
         index: int
         fractional_index: str
 
     class Move(TreeExternalDiff):
+        __match_args__ = ("parent", "index", "fractional_index", "old_parent", "old_index")
         def __init__(
             self,
             parent: typing.Optional[TreeID],
@@ -2709,20 +4334,23 @@ class TreeExternalDiff:
         old_index: int
 
     class Delete(TreeExternalDiff):
+        __match_args__ = ("old_parent", "old_index")
         def __init__(self, old_parent: typing.Optional[TreeID], old_index: int): ...
         old_parent: typing.Optional[TreeID]
         old_index: int
 
 class UndoOrRedo(Enum):
-    Undo = "undo"
-    Redo = "redo"
+    Undo = 0
+    Redo = 1
 
 class ValueOrContainer:
     class Value(ValueOrContainer):
+        __match_args__ = ("value",)
         def __init__(self, value: LoroValue): ...
         value: LoroValue
 
     class Container(ValueOrContainer):
+        __match_args__ = ("container",)
         def __init__(self, container: Container): ...
         container: Container
 
@@ -2734,6 +4362,76 @@ class ValueOrContainer:
     def is_container(
         cls, value: ValueOrContainer | None
     ) -> typing.TypeGuard[ValueOrContainer.Container]: ...
+    def container_id(self) -> typing.Optional[ContainerID]:
+        r"""
+        The id of the container, or of the container referenced by the value.
+
+        Return `None` if it's a plain value.
+        """
+        ...
+
+    def as_text(self) -> typing.Optional[LoroText]:
+        r"""
+        The container as a `LoroText`, or `None` if it's a value or another kind of
+        container.
+        """
+        ...
+
+    def as_map(self) -> typing.Optional[LoroMap]:
+        r"""
+        The container as a `LoroMap`, or `None` if it's a value or another kind of
+        container.
+        """
+        ...
+
+    def as_list(self) -> typing.Optional[LoroList]:
+        r"""
+        The container as a `LoroList`, or `None` if it's a value or another kind of
+        container.
+        """
+        ...
+
+    def as_movable_list(self) -> typing.Optional[LoroMovableList]:
+        r"""
+        The container as a `LoroMovableList`, or `None` if it's a value or another kind of
+        container.
+        """
+        ...
+
+    def as_tree(self) -> typing.Optional[LoroTree]:
+        r"""
+        The container as a `LoroTree`, or `None` if it's a value or another kind of
+        container.
+        """
+        ...
+
+    def as_counter(self) -> typing.Optional[LoroCounter]:
+        r"""
+        The container as a `LoroCounter`, or `None` if it's a value or another kind of
+        container.
+        """
+        ...
+
+    def to_py(self) -> typing.Any:
+        r"""
+        Convert to a plain Python object.
+
+        Containers are converted to their deep value.
+        """
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        r"""
+        Compare deep values, so an entry holding a container equals its deep value, another
+        entry or container with the same content, and plain Python objects.
+        """
+        ...
+
+    def __getitem__(self, key: typing.Union[int, str]) -> ValueOrContainer:
+        r"""
+        Get the child at `key` of a list or map, either a value or a container.
+        """
+        ...
 
 class EphemeralStore:
     r"""
@@ -2855,7 +4553,12 @@ class IdLp:
     r"""
     ID with Lamport timestamp.
     """
-    peer: int
-    lamport: int
+    def __repr__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    @property
+    def peer(self) -> int: ...
+    @property
+    def lamport(self) -> int: ...
     
     def __new__(cls, peer: int, lamport: int) -> IdLp: ...
@@ -0,0 +1,401 @@
+"""Map dataclasses onto Loro containers.
+
+Decorate a dataclass with :func:`schema` and bind it to a ``LoroMap`` to read and write
+its fields through the CRDT::
+
+    @loro.schema
+    @dataclass
+    class Todo:
+        title: str
+        done: bool = False
+
+    @loro.schema
+    @dataclass
+    class Board:
+        name: str
+        notes: LoroText
+        todos: List[Todo] = field(default_factory=list)
+
+    board = Board.bind(doc.get_map("board"))
+    board.name = "Sprint"
+    board.notes.insert(0, "Ship it")
+    board.todos.append(Todo(title="Write docs"))
+    board.todos[0].done = True
+
+Fields are stored by type:
+
+- Loro containers (``LoroText``, ``LoroList``, ...) are child containers of the map.
+- Other schema classes are child ``LoroMap`` s.
+- ``List[Model]`` of a schema class is a child ``LoroMovableList`` of ``LoroMap`` s.
+- Everything else is stored as a plain value under the field name.
+
+Reading never creates child containers: missing fields read as their default, and views of
+nested schema classes and lists create their container on the first write. Accessing a
+container field such as ``board.notes`` on a live view does create it, since the handle is
+returned for editing.
+"""
+
+from __future__ import annotations
+
+import dataclasses
+import typing
+
+from .loro import (
+    LoroCounter,
+    LoroList,
+    LoroMap,
+    LoroMovableList,
+    LoroText,
+    LoroTree,
+    ValueOrContainer,
+)
+
+__all__ = ["schema"]
+
+T = typing.TypeVar("T")
+
+_CONTAINER_TYPES = (LoroCounter, LoroList, LoroMap, LoroMovableList, LoroText, LoroTree)
+_SCHEMA_ATTR = "__loro_schema__"
+_GENERATED = ("bind", "load", "save")
+
+_VALUE = "value"
+_CONTAINER = "container"
+_MODEL = "model"
+_MODEL_LIST = "model_list"
+
+
+class _Field:
+    __slots__ = ("name", "kind", "type", "default")
+
+    def __init__(self, name: str, kind: str, type_: typing.Any, default: dataclasses.Field):
+        self.name = name
+        self.kind = kind
+        self.type = type_
+        self.default = default
+
+    def has_default(self) -> bool:
+        return (
+            self.default.default is not dataclasses.MISSING
+            or self.default.default_factory is not dataclasses.MISSING
+        )
+
+    def default_value(self, model: type) -> typing.Any:
+        if self.default.default is not dataclasses.MISSING:
+            return self.default.default
+        if self.default.default_factory is not dataclasses.MISSING:
+            return self.default.default_factory()
+        raise KeyError(f"{model.__name__}.{self.name} is not set")
+
+
+def _is_schema(tp: typing.Any) -> bool:
+    return isinstance(tp, type) and _SCHEMA_ATTR in tp.__dict__
+
+
+def _resolve(name: str, hint: typing.Any, default: dataclasses.Field) -> _Field:
+    if isinstance(hint, type) and issubclass(hint, _CONTAINER_TYPES):
+        return _Field(name, _CONTAINER, hint, default)
+    if _is_schema(hint):
+        return _Field(name, _MODEL, hint, default)
+    if typing.get_origin(hint) in (list, typing.List):
+        args = typing.get_args(hint)
+        if args and _is_schema(args[0]):
+            return _Field(name, _MODEL_LIST, args[0], default)
+    return _Field(name, _VALUE, hint, default)
+
+
+def _fields(model: type) -> typing.Dict[str, _Field]:
+    # Resolved lazily, so fields can refer to schema classes defined later.
+    fields = model.__dict__[_SCHEMA_ATTR]
+    if fields is None:
+        hints = typing.get_type_hints(model)
+        fields = {
+            f.name: _resolve(f.name, hints.get(f.name, typing.Any), f)
+            for f in dataclasses.fields(model)
+        }
+        setattr(model, _SCHEMA_ATTR, fields)
+    return fields
+
+
+def schema(cls: typing.Type[T]) -> typing.Type[T]:
+    """Make a dataclass bindable to a ``LoroMap``.
+
+    The class gets three classmethods:
+
+    - ``bind(map)`` returns a live view whose attributes read and write the map.
+    - ``load(map)`` returns a plain instance holding the current values.
+    - ``save(map, instance)`` writes all the fields of ``instance`` into the map.
+
+    Classes that aren't dataclasses yet are turned into one.
+    """
+    if not dataclasses.is_dataclass(cls):
+        cls = dataclasses.dataclass(cls)
+    for name in _GENERATED:
+        if any(f.name == name for f in dataclasses.fields(cls)):
+            raise TypeError(f"field '{name}' of {cls.__name__} clashes with loro.schema")
+    setattr(cls, _SCHEMA_ATTR, None)
+    cls.bind = classmethod(_bind)  # type: ignore[attr-defined]
+    cls.load = classmethod(_load)  # type: ignore[attr-defined]
+    cls.save = classmethod(_save)  # type: ignore[attr-defined]
+    return cls
+
+
+def _bind(model: typing.Type[T], map: LoroMap) -> T:
+    return typing.cast(T, ModelView(model, map))
+
+
+def _load(model: typing.Type[T], map: LoroMap) -> T:
+    view = ModelView(model, map)
+    values = {}
+    for field in _fields(model).values():
+        if field.kind == _VALUE:
+            values[field.name] = view._read(field)
+            continue
+        child = view._existing(field)
+        if child is None and field.has_default():
+            values[field.name] = field.default_value(model)
+        elif field.kind == _MODEL:
+            # A missing child reads as an empty detached map, so its own defaults apply.
+            values[field.name] = _load(field.type, LoroMap() if child is None else child)
+        elif field.kind == _MODEL_LIST:
+            values[field.name] = [] if child is None else ModelList(field.type, child).load()
+        else:
+            values[field.name] = field.type() if child is None else child
+    return model(**values)
+
+
+def _save(model: type, map: LoroMap, instance: typing.Any) -> None:
+    view = ModelView(model, map)
+    for field in _fields(model).values():
+        view._write(field, getattr(instance, field.name))
+
+
+def _container_type(field: _Field) -> type:
+    if field.kind == _MODEL:
+        return LoroMap
+    if field.kind == _MODEL_LIST:
+        return LoroMovableList
+    return field.type
+
+
+class ModelView:
+    """A live view of a schema class bound to a ``LoroMap``.
+
+    The view of a nested schema class is bound to its parent view instead, and creates its
+    map on the first write.
+    """
+
+    __slots__ = ("_model", "_map", "_parent", "_field")
+
+    def __init__(
+        self,
+        model: type,
+        map: typing.Optional[LoroMap],
+        parent: typing.Optional[ModelView] = None,
+        field: typing.Optional[_Field] = None,
+    ):
+        object.__setattr__(self, "_model", model)
+        object.__setattr__(self, "_map", map)
+        object.__setattr__(self, "_parent", parent)
+        object.__setattr__(self, "_field", field)
+
+    @property
+    def container(self) -> LoroMap:
+        """The map backing this view, created if it doesn't exist yet."""
+        return self._target(create=True)
+
+    def __getattr__(self, name: str) -> typing.Any:
+        field = _fields(self._model).get(name)
+        if field is None:
+            raise AttributeError(f"{self._model.__name__} has no field '{name}'")
+        if field.kind == _MODEL:
+            return ModelView(field.type, self._existing(field), self, field)
+        if field.kind == _MODEL_LIST:
+            return ModelList(field.type, self._existing(field), self, field)
+        return self._read(field)
+
+    def __setattr__(self, name: str, value: typing.Any) -> None:
+        field = _fields(self._model).get(name)
+        if field is None:
+            raise AttributeError(f"{self._model.__name__} has no field '{name}'")
+        self._write(field, value)
+
+    def __dir__(self) -> typing.List[str]:
+        return [*super().__dir__(), *_fields(self._model)]
+
+    def __eq__(self, other: object) -> bool:
+        if isinstance(other, ModelView):
+            return self._model is other._model and self._key() == other._key()
+        return NotImplemented
+
+    def __hash__(self) -> int:
+        return hash(self._key())
+
+    def __repr__(self) -> str:
+        if self._parent is not None:
+            return f"{self._parent!r}.{self._field.name}"
+        return f"{self._model.__name__}.bind({self._map!r})"
+
+    def _key(self) -> typing.Any:
+        # Nested views are keyed by their path, which doesn't change once the map is created.
+        if self._parent is not None:
+            return (self._parent._key(), self._field.name)
+        return self._map.id
+
+    def _target(self, create: bool) -> typing.Optional[LoroMap]:
+        if self._map is None and self._parent is not None:
+            if create:
+                map = self._parent._child(self._field)
+            else:
+                map = self._parent._existing(self._field)
+            object.__setattr__(self, "_map", map)
+        return self._map
+
+    def _existing(self, field: _Field) -> typing.Any:
+        """Return the child container of `field`, or `None` if it doesn't exist yet."""
+        map = self._target(create=False)
+        entry = None if map is None else map.get(field.name)
+        if entry is None or not ValueOrContainer.is_container(entry):
+            return None
+        if not isinstance(entry.container, _container_type(field)):
+            return None
+        return entry.container
+
+    def _child(self, field: _Field) -> typing.Any:
+        map = self._target(create=True)
+        return map.get_or_create_container(field.name, _container_type(field)())
+
+    def _read(self, field: _Field) -> typing.Any:
+        if field.kind == _CONTAINER:
+            return self._child(field)
+        map = self._target(create=False)
+        entry = None if map is None else map.get(field.name)
+        if entry is None:
+            return field.default_value(self._model)
+        if ValueOrContainer.is_container(entry):
+            return entry.container
+        return entry.value
+
+    def _write(self, field: _Field, value: typing.Any) -> None:
+        map = self._target(create=True)
+        if field.kind == _VALUE:
+            map.insert(field.name, value)
+        elif field.kind == _CONTAINER:
+            self._write_container(field, value)
+        elif field.kind == _MODEL:
+            child = map.insert_container(field.name, LoroMap())
+            _save(field.type, child, value)
+        else:
+            items = ModelList(field.type, map.insert_container(field.name, LoroMovableList()))
+            for item in value:
+                items.append(item)
+
+    def _write_container(self, field: _Field, value: typing.Any) -> None:
+        if isinstance(value, field.type):
+            entry = self._map.get(field.name)
+            if (
+                entry is not None
+                and ValueOrContainer.is_container(entry)
+                and entry.container.id == value.id
+            ):
+                # Saving an instance loaded from this very map.
+                return
+            self._map.insert_container(field.name, value)
+        elif field.type is LoroText and isinstance(value, str):
+            self._child(field).update(value)
+        elif field.type is LoroCounter and isinstance(value, (int, float)):
+            counter = self._child(field)
+            counter.increment(value - counter.value)
+        else:
+            raise TypeError(
+                f"cannot assign {type(value).__name__} to {self._model.__name__}.{field.name}"
+            )
+
+
+class ModelList(typing.Generic[T]):
+    """A live list of schema class instances, backed by a ``LoroMovableList`` of maps.
+
+    The list of a schema class field is created on the first insert.
+    """
+
+    __slots__ = ("_model", "_items", "_parent", "_field")
+
+    def __init__(
+        self,
+        model: typing.Type[T],
+        items: typing.Optional[LoroMovableList],
+        parent: typing.Optional[ModelView] = None,
+        field: typing.Optional[_Field] = None,
+    ):
+        self._model = model
+        self._items = items
+        self._parent = parent
+        self._field = field
+
+    @property
+    def container(self) -> LoroMovableList:
+        """The list backing this view, created if it doesn't exist yet."""
+        return self._target(create=True)
+
+    def __len__(self) -> int:
+        items = self._target(create=False)
+        return 0 if items is None else len(items)
+
+    def __getitem__(self, index: int) -> T:
+        return _bind(self._model, self._map_at(index))
+
+    def __delitem__(self, index: int) -> None:
+        index = self._normalize(index)
+        self._items.delete(index, 1)
+
+    def __iter__(self) -> typing.Iterator[T]:
+        for i in range(len(self)):
+            yield self[i]
+
+    def __repr__(self) -> str:
+        if self._items is None and self._parent is not None:
+            return f"{self._parent!r}.{self._field.name}"
+        return f"ModelList[{self._model.__name__}]({self._items!r})"
+
+    def append(self, item: T) -> T:
+        """Append an instance and return its live view."""
+        return self.insert(len(self), item)
+
+    def insert(self, index: int, item: T) -> T:
+        """Insert an instance at `index` and return its live view."""
+        if index < 0:
+            index = max(0, len(self) + index)
+        child = self.container.insert_container(min(index, len(self)), LoroMap())
+        _save(self._model, child, item)
+        return _bind(self._model, child)
+
+    def move(self, from_: int, to: int) -> None:
+        """Move the item at `from_` to `to`."""
+        from_, to = self._normalize(from_), self._normalize(to)
+        self._items.mov(from_, to)
+
+    def load(self) -> typing.List[T]:
+        """Return plain instances holding the current values."""
+        return [_load(self._model, self._map_at(i)) for i in range(len(self))]
+
+    def _target(self, create: bool) -> typing.Optional[LoroMovableList]:
+        if self._items is None and self._parent is not None:
+            if create:
+                items = self._parent._child(self._field)
+            else:
+                items = self._parent._existing(self._field)
+            self._items = items
+        return self._items
+
+    def _normalize(self, index: int) -> int:
+        length = len(self)
+        if index < 0:
+            index += length
+        if not 0 <= index < length:
+            raise IndexError("list index out of range")
+        return index
+
+    def _map_at(self, index: int) -> LoroMap:
+        entry = self._items.get(self._normalize(index))
+        if entry is None or not ValueOrContainer.is_container(entry):
+            raise TypeError(f"item {index} is not a {self._model.__name__}")
+        return entry.container
@@ -0,0 +1,152 @@
+"""Share the cursors of the peers editing a document.
+
+A ``Presence`` keeps the local peer's cursor, selection and user info in an
+``EphemeralStore``, whose updates the app carries to the other peers like any other
+ephemeral data::
+
+    presence = Presence(doc)
+    presence.store.subscribe_local_updates(lambda update: send(update) or True)
+    presence.set_user({"name": "Ann"})
+    presence.set_cursor(text.get_cursor(5, Side.Middle))
+
+    # for every update received from another peer
+    presence.store.apply(update)
+    for cursor in presence.remote_cursors().values():
+        draw(cursor.head.pos, cursor.user["name"])
+
+States expire when they aren't updated within the store's timeout, so call ``refresh``
+more often than that while the local peer is around, and ``leave`` once it's gone.
+"""
+
+from __future__ import annotations
+
+import typing
+
+from .loro import AbsolutePosition, Cursor, EphemeralStore, LoroDoc
+
+__all__ = ["Presence", "RemoteCursor"]
+
+# The prefix of the store keys, so the store can be shared with other ephemeral data.
+_KEY_PREFIX = "presence/"
+
+
+def _is_loro_error(e: BaseException) -> bool:
+    # Loro raises some of its errors as plain `BaseException`s.
+    return isinstance(e, Exception) or type(e) is BaseException
+
+
+class RemoteCursor(typing.NamedTuple):
+    """The cursor of a remote peer, resolved in the local document."""
+
+    peer: int
+    head: AbsolutePosition
+    # The other end of the selection, `None` if nothing is selected.
+    anchor: typing.Optional[AbsolutePosition]
+    user: typing.Dict[str, typing.Any]
+
+
+class Presence:
+    """The cursors and user info of the peers editing `doc`.
+
+    Given a `LoroDoc`, the presence creates its own `EphemeralStore`, whose states
+    expire after `timeout` milliseconds. Given an `EphemeralStore`, it also needs the
+    `doc` the cursors point into. The local state is keyed by the document's current
+    peer id.
+    """
+
+    def __init__(
+        self,
+        doc_or_store: typing.Union[LoroDoc, EphemeralStore],
+        doc: typing.Optional[LoroDoc] = None,
+        timeout: int = 30_000,
+    ) -> None:
+        if isinstance(doc_or_store, LoroDoc):
+            doc, store = doc_or_store, EphemeralStore(timeout)
+        else:
+            store = doc_or_store
+        if doc is None:
+            raise TypeError("a Presence over an EphemeralStore needs the doc as well")
+        self.doc = doc
+        self.store = store
+        self._user: typing.Dict[str, typing.Any] = {}
+        self._head: typing.Optional[Cursor] = None
+        self._anchor: typing.Optional[Cursor] = None
+
+    def _key(self) -> str:
+        return f"{_KEY_PREFIX}{self.doc.peer_id}"
+
+    def refresh(self) -> None:
+        """Publish the local state again, so it doesn't expire."""
+        self.store.set(
+            self._key(),
+            {
+                "user": self._user,
+                "head": self._head.encode() if self._head is not None else None,
+                "anchor": self._anchor.encode() if self._anchor is not None else None,
+            },
+        )
+
+    def set_user(self, user: typing.Dict[str, typing.Any]) -> None:
+        """Set the user info shown to the other peers, e.g. a name and a color."""
+        self._user = dict(user)
+        self.refresh()
+
+    def set_cursor(
+        self, head: typing.Optional[Cursor], anchor: typing.Optional[Cursor] = None
+    ) -> None:
+        """Set the local cursor, and the other end of the selection if any.
+
+        Pass `None` to hide the cursor while keeping the user info.
+        """
+        self._head = head
+        self._anchor = anchor if head is not None else None
+        self.refresh()
+
+    def leave(self) -> None:
+        """Remove the local state, e.g. when the document is closed."""
+        self.store.delete(self._key())
+
+    def _resolve(self, encoded: typing.Any) -> typing.Optional[AbsolutePosition]:
+        if not isinstance(encoded, bytes):
+            return None
+        try:
+            cursor = Cursor.decode(encoded)
+            # The remote peer may point at changes the local document hasn't received yet.
+            if cursor.id is not None and not self.doc.oplog_vv.includes_id(cursor.id):
+                return None
+            if not self.doc.has_container(cursor.container):
+                return None
+            return self.doc.get_cursor_pos(cursor).current
+        except BaseException as e:
+            if not _is_loro_error(e):
+                raise
+            return None
+
+    def remote_cursors(self) -> typing.Dict[int, RemoteCursor]:
+        """The cursors of the other peers, by peer id, at their current positions.
+
+        Expired states are dropped first. Peers without a cursor, or whose cursor points
+        at changes the document doesn't have yet, are left out, and so are the malformed
+        states a peer may have written.
+        """
+        self.store.remove_outdated()
+        local = self._key()
+        ans = {}
+        for key, state in self.store.get_all_states().items():
+            if not key.startswith(_KEY_PREFIX) or key == local:
+                continue
+            try:
+                peer = int(key[len(_KEY_PREFIX) :])
+            except ValueError:
+                continue
+            if not isinstance(state, dict):
+                continue
+            head = self._resolve(state.get("head"))
+            if head is None:
+                continue
+            anchor = self._resolve(state.get("anchor"))
+            user = state.get("user")
+            ans[peer] = RemoteCursor(
+                peer, head, anchor, user if isinstance(user, dict) else {}
+            )
+        return ans
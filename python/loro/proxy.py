@@ -0,0 +1,285 @@
+"""Use Loro containers through the ``dict`` and ``list`` interfaces.
+
+``LoroDict`` wraps a ``LoroMap`` as a ``MutableMapping`` and ``LoroSeq`` wraps a
+``LoroList`` or ``LoroMovableList`` as a ``MutableSequence``, so code written against
+dicts and lists can edit a document by swapping the type::
+
+    settings = LoroDict(doc.get_map("settings"))
+    settings["theme"] = "dark"
+    settings["recent"] = ["a.txt"]
+    settings["recent"].append("b.txt")
+
+Nested dicts and lists are stored as child containers and read back as proxies, so
+edits to them reach the document as well. Other containers, e.g. ``LoroText``, are
+returned as is.
+
+Every edit is committed right away, unless the proxy is created with
+``auto_commit=False``. Edits made within ``with proxy.batch():`` are committed once at
+the end of the block.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import typing
+from collections.abc import Mapping, MutableMapping, MutableSequence, Sequence
+
+from .loro import (
+    LoroCounter,
+    LoroDoc,
+    LoroList,
+    LoroMap,
+    LoroMovableList,
+    LoroText,
+    LoroTree,
+    ValueOrContainer,
+)
+
+__all__ = ["LoroDict", "LoroSeq"]
+
+_CONTAINER_TYPES = (LoroCounter, LoroList, LoroMap, LoroMovableList, LoroText, LoroTree)
+
+
+class _Session:
+    """The commit settings shared by a proxy and the proxies of its children."""
+
+    __slots__ = ("doc", "auto_commit", "depth")
+
+    def __init__(self, doc: typing.Optional[LoroDoc], auto_commit: bool) -> None:
+        self.doc = doc
+        self.auto_commit = auto_commit
+        # The number of open `batch` blocks.
+        self.depth = 0
+
+    def changed(self) -> None:
+        if self.auto_commit and self.depth == 0 and self.doc is not None:
+            self.doc.commit()
+
+    @contextlib.contextmanager
+    def batch(self) -> typing.Iterator[None]:
+        self.depth += 1
+        try:
+            yield
+        finally:
+            self.depth -= 1
+            if self.depth == 0 and self.doc is not None:
+                self.doc.commit()
+
+
+def _wrap(entry: typing.Optional[ValueOrContainer], session: _Session) -> typing.Any:
+    if entry is None:
+        return None
+    if not ValueOrContainer.is_container(entry):
+        return entry.value
+    container = entry.container
+    if isinstance(container, LoroMap):
+        return LoroDict._child(container, session)
+    if isinstance(container, (LoroList, LoroMovableList)):
+        return LoroSeq._child(container, session)
+    return container
+
+
+def _is_sequence(value: typing.Any) -> bool:
+    return isinstance(value, Sequence) and not isinstance(value, (str, bytes))
+
+
+def _copy(value: typing.Any) -> typing.Any:
+    """The plain value of a proxy, read before its container may be overwritten."""
+    if isinstance(value, (LoroDict, LoroSeq)):
+        return value.container.get_deep_value()
+    return value
+
+
+def _fill(container: typing.Any, value: typing.Any) -> None:
+    """Copy the items of a dict or list into a new child container."""
+    if isinstance(container, LoroMap):
+        for key, item in value.items():
+            _set_in_map(container, key, item)
+    else:
+        for index, item in enumerate(value):
+            _insert_in_list(container, index, item)
+
+
+def _set_in_map(map: LoroMap, key: str, value: typing.Any) -> None:
+    if isinstance(value, Mapping):
+        _fill(map.insert_container(key, LoroMap()), value)
+    elif _is_sequence(value):
+        _fill(map.insert_container(key, LoroList()), value)
+    elif isinstance(value, _CONTAINER_TYPES):
+        map.insert_container(key, value)
+    else:
+        map.insert(key, value)
+
+
+def _insert_in_list(
+    items: typing.Union[LoroList, LoroMovableList], index: int, value: typing.Any
+) -> None:
+    if isinstance(value, Mapping):
+        _fill(items.insert_container(index, LoroMap()), value)
+    elif _is_sequence(value):
+        _fill(items.insert_container(index, LoroList()), value)
+    elif isinstance(value, _CONTAINER_TYPES):
+        items.insert_container(index, value)
+    else:
+        items.insert(index, value)
+
+
+class LoroDict(MutableMapping):
+    """A ``MutableMapping`` view of a ``LoroMap``.
+
+    Edits are committed with `doc`, by default the document of the map. A detached map
+    has nothing to commit.
+    """
+
+    __slots__ = ("_map", "_session")
+
+    def __init__(
+        self,
+        map: LoroMap,
+        doc: typing.Optional[LoroDoc] = None,
+        auto_commit: bool = True,
+    ) -> None:
+        self._map = map
+        self._session = _Session(doc if doc is not None else map.doc(), auto_commit)
+
+    @classmethod
+    def _child(cls, map: LoroMap, session: _Session) -> LoroDict:
+        ans = cls.__new__(cls)
+        ans._map = map
+        ans._session = session
+        return ans
+
+    @property
+    def container(self) -> LoroMap:
+        return self._map
+
+    def batch(self) -> typing.ContextManager[None]:
+        """Commit the edits made within the block once, at its end."""
+        return self._session.batch()
+
+    def __getitem__(self, key: str) -> typing.Any:
+        entry = self._map.get(key)
+        if entry is None:
+            raise KeyError(key)
+        return _wrap(entry, self._session)
+
+    def __setitem__(self, key: str, value: typing.Any) -> None:
+        _set_in_map(self._map, key, _copy(value))
+        self._session.changed()
+
+    def __delitem__(self, key: str) -> None:
+        del self._map[key]
+        self._session.changed()
+
+    def __iter__(self) -> typing.Iterator[str]:
+        return iter(self._map.keys())
+
+    def __len__(self) -> int:
+        return len(self._map)
+
+    def __contains__(self, key: object) -> bool:
+        return isinstance(key, str) and key in self._map
+
+    def __repr__(self) -> str:
+        return f"LoroDict({dict(self)!r})"
+
+
+class LoroSeq(MutableSequence):
+    """A ``MutableSequence`` view of a ``LoroList`` or a ``LoroMovableList``.
+
+    Edits are committed with `doc`, by default the document of the list. A detached list
+    has nothing to commit.
+    """
+
+    __slots__ = ("_list", "_session")
+
+    def __init__(
+        self,
+        items: typing.Union[LoroList, LoroMovableList],
+        doc: typing.Optional[LoroDoc] = None,
+        auto_commit: bool = True,
+    ) -> None:
+        self._list = items
+        self._session = _Session(doc if doc is not None else items.doc(), auto_commit)
+
+    @classmethod
+    def _child(
+        cls, items: typing.Union[LoroList, LoroMovableList], session: _Session
+    ) -> LoroSeq:
+        ans = cls.__new__(cls)
+        ans._list = items
+        ans._session = session
+        return ans
+
+    @property
+    def container(self) -> typing.Union[LoroList, LoroMovableList]:
+        return self._list
+
+    def batch(self) -> typing.ContextManager[None]:
+        """Commit the edits made within the block once, at its end."""
+        return self._session.batch()
+
+    def _index(self, index: int) -> int:
+        length = len(self._list)
+        if index < 0:
+            index += length
+        if not 0 <= index < length:
+            raise IndexError("list index out of range")
+        return index
+
+    def __getitem__(self, index: typing.Union[int, slice]) -> typing.Any:
+        if isinstance(index, slice):
+            return [self[i] for i in range(len(self._list))[index]]
+        return _wrap(self._list.get(self._index(index)), self._session)
+
+    def __setitem__(self, index: typing.Union[int, slice], value: typing.Any) -> None:
+        if not isinstance(index, slice):
+            index = self._index(index)
+            value = _copy(value)
+            self._list.delete(index, 1)
+            _insert_in_list(self._list, index, value)
+            self._session.changed()
+            return
+        positions = range(len(self._list))[index]
+        values = [_copy(item) for item in value]
+        if index.step not in (None, 1):
+            if len(values) != len(positions):
+                raise ValueError(
+                    f"attempt to assign sequence of size {len(values)} "
+                    f"to extended slice of size {len(positions)}"
+                )
+            for position, item in zip(positions, values):
+                self._list.delete(position, 1)
+                _insert_in_list(self._list, position, item)
+        else:
+            start = positions.start
+            self._list.delete(start, len(positions))
+            for offset, item in enumerate(values):
+                _insert_in_list(self._list, start + offset, item)
+        self._session.changed()
+
+    def __delitem__(self, index: typing.Union[int, slice]) -> None:
+        if isinstance(index, slice):
+            for position in sorted(range(len(self._list))[index], reverse=True):
+                self._list.delete(position, 1)
+        else:
+            self._list.delete(self._index(index), 1)
+        self._session.changed()
+
+    def __len__(self) -> int:
+        return len(self._list)
+
+    def insert(self, index: int, value: typing.Any) -> None:
+        length = len(self._list)
+        if index < 0:
+            index = max(index + length, 0)
+        _insert_in_list(self._list, min(index, length), value)
+        self._session.changed()
+
+    def __eq__(self, other: object) -> bool:
+        if not _is_sequence(other):
+            return NotImplemented
+        return list(self) == list(typing.cast(Sequence, other))
+
+    def __repr__(self) -> str:
+        return f"LoroSeq({list(self)!r})"
@@ -0,0 +1,476 @@
+"""Sync two documents over any byte channel.
+
+A ``SyncSession`` turns the sync handshake into messages, which the app carries to the
+other peer however it likes (a socket, a queue, HTTP requests...)::
+
+    session = SyncSession(doc)
+    for message in session.start():
+        send(message)
+
+    # for every message received from the other peer
+    for reply in session.receive(message):
+        send(reply)
+
+    # after local commits
+    for message in session.poll():
+        send(message)
+
+Each message is one tag byte followed by its payload:
+
+- ``VERSION``: the sender's version vector. The receiver replies with the updates the
+  sender is missing, and with a ``REQUEST`` carrying its own version, so the sender
+  learns it and sends back what the receiver is missing, if anything.
+- ``REQUEST``: the sender's version vector, asking for the updates it's missing.
+- ``UPDATES``: updates to import.
+
+``WebSocketClient`` runs a session against a relay server over WebSocket, and ``serve``
+runs such a server. They need the ``websockets`` package (``pip install loro[sync]``).
+"""
+
+from __future__ import annotations
+
+import asyncio
+import inspect
+import typing
+
+from .loro import ID, ExportMode, LoroDoc, VersionVector
+
+__all__ = [
+    "SyncSession",
+    "WebSocketClient",
+    "RelayServer",
+    "serve",
+    "VERSION",
+    "REQUEST",
+    "UPDATES",
+]
+
+VERSION = 1
+REQUEST = 2
+UPDATES = 3
+
+
+def _message(tag: int, payload: bytes) -> bytes:
+    return bytes([tag]) + payload
+
+
+class SyncSession:
+    """The sync state of `doc` with one remote peer.
+
+    The session doesn't do any IO: every method returns the messages to send to the
+    peer, in order, and assumes they are delivered. Updates from the peer are imported
+    with `origin`.
+    """
+
+    def __init__(self, doc: LoroDoc, origin: str = "sync") -> None:
+        self.doc = doc
+        self.origin = origin
+        self._outbox: typing.List[bytes] = []
+        # What the peer is known to have, `None` until it has sent its version.
+        self._remote_vv: typing.Optional[VersionVector] = None
+        self._subscription = doc.subscribe_local_update(self._on_local_update)
+
+    def _on_local_update(self, updates: bytes) -> bool:
+        self._outbox.append(_message(UPDATES, bytes(updates)))
+        return True
+
+    def _reply_with_updates(self, vv: VersionVector) -> typing.List[bytes]:
+        self._remote_vv = vv
+        oplog_vv = self.doc.oplog_vv
+        if vv.includes_vv(oplog_vv):
+            return []
+        updates = self.doc.export(ExportMode.Updates(vv))
+        vv.merge(oplog_vv)
+        return [_message(UPDATES, updates)]
+
+    @property
+    def synced(self) -> bool:
+        """Whether both peers are known to have the same history."""
+        if self._remote_vv is None or self._outbox:
+            return False
+        oplog_vv = self.doc.oplog_vv
+        return self._remote_vv.includes_vv(oplog_vv) and oplog_vv.includes_vv(
+            self._remote_vv
+        )
+
+    def start(self) -> typing.List[bytes]:
+        """Start the handshake by announcing the local version."""
+        # The peer answers with a request for everything it's missing, which covers the
+        # pending local updates.
+        self._outbox.clear()
+        self._remote_vv = None
+        return [_message(VERSION, self.doc.oplog_vv.encode())]
+
+    def receive(self, message: bytes) -> typing.List[bytes]:
+        """Handle a message from the peer and return the replies.
+
+        Raises ``ValueError`` for empty messages or unknown tags.
+        """
+        if not message:
+            raise ValueError("empty sync message")
+        tag, payload = message[0], bytes(message[1:])
+        if tag == VERSION:
+            replies = self._reply_with_updates(VersionVector.decode(payload))
+            # Sent even when nothing is missing: the peer only knows it's in sync once
+            # it has seen the local version.
+            replies.append(_message(REQUEST, self.doc.oplog_vv.encode()))
+            return replies
+        if tag == REQUEST:
+            return self._reply_with_updates(VersionVector.decode(payload))
+        if tag == UPDATES:
+            status = self.doc.import_with(payload, self.origin)
+            if self._remote_vv is not None:
+                for peer, (_, end) in status.success.inner().items():
+                    self._remote_vv.extend_to_include_end_id(ID(peer, end))
+            if status.pending is not None:
+                # Some updates depend on changes that haven't been received yet.
+                return [_message(REQUEST, self.doc.oplog_vv.encode())]
+            return []
+        raise ValueError(f"unknown sync message tag: {tag}")
+
+    def poll(self, coalesce: bool = False) -> typing.List[bytes]:
+        """Take the messages carrying the local updates committed since the last call.
+
+        With `coalesce`, several pending updates are merged into one message once the
+        peer's version is known, so a slow channel doesn't fall further behind.
+        """
+        messages, self._outbox = self._outbox, []
+        if not messages or self._remote_vv is None:
+            return messages
+        if coalesce and len(messages) > 1:
+            updates = self.doc.export(ExportMode.Updates(self._remote_vv))
+            messages = [_message(UPDATES, updates)]
+        self._remote_vv.merge(self.doc.oplog_vv)
+        return messages
+
+    def close(self) -> None:
+        """Stop tracking the local updates."""
+        self._subscription.unsubscribe()
+
+
+def _import_websockets() -> typing.Any:
+    try:
+        import websockets
+    except ImportError as e:
+        raise ImportError(
+            "WebSocket sync requires the `websockets` package: pip install loro[sync]"
+        ) from e
+    return websockets
+
+
+class WebSocketClient:
+    """Keep `doc` in sync with a relay server at `url`.
+
+    ::
+
+        client = WebSocketClient(doc, "ws://localhost:8765/my-room")
+        task = asyncio.create_task(client.run())
+        await client.wait_synced()
+        ...
+        await client.close()
+
+    `run` reconnects with exponential backoff, from `reconnect_delay` up to
+    `max_reconnect_delay` seconds, until `close` is called. Local updates are sent in
+    the background; while the socket is slow they are coalesced into one message
+    instead of queueing up. `on_sync` is called every time the handshake completes,
+    i.e. once per connection.
+    """
+
+    def __init__(
+        self,
+        doc: LoroDoc,
+        url: str,
+        *,
+        on_sync: typing.Optional[typing.Callable[[], None]] = None,
+        origin: str = "sync",
+        reconnect_delay: float = 0.5,
+        max_reconnect_delay: float = 30.0,
+    ) -> None:
+        self.doc = doc
+        self.url = url
+        self.on_sync = on_sync
+        self.reconnect_delay = reconnect_delay
+        self.max_reconnect_delay = max_reconnect_delay
+        self.session = SyncSession(doc, origin)
+        self._closed = False
+        self._ws: typing.Any = None
+        self._loop: typing.Optional[asyncio.AbstractEventLoop] = None
+        self._dirty: typing.Optional[asyncio.Event] = None
+        self._synced: typing.Optional[asyncio.Event] = None
+        self._subscription = doc.subscribe_local_update(self._on_local_update)
+
+    def _on_local_update(self, _updates: bytes) -> bool:
+        # Commits may happen on another thread than the event loop.
+        if self._loop is not None and self._dirty is not None:
+            self._loop.call_soon_threadsafe(self._dirty.set)
+        return True
+
+    @property
+    def connected(self) -> bool:
+        return self._ws is not None
+
+    @property
+    def synced(self) -> bool:
+        """Whether the handshake of the current connection has completed."""
+        return self._synced is not None and self._synced.is_set()
+
+    async def wait_synced(self) -> None:
+        """Wait until the handshake of the current connection completes."""
+        if self._synced is None:
+            self._synced = asyncio.Event()
+        await self._synced.wait()
+
+    async def run(self) -> None:
+        """Connect and sync until `close` is called."""
+        websockets = _import_websockets()
+        self._loop = asyncio.get_running_loop()
+        self._dirty = asyncio.Event()
+        if self._synced is None:
+            self._synced = asyncio.Event()
+        delay = self.reconnect_delay
+        while not self._closed:
+            try:
+                async with websockets.connect(self.url) as ws:
+                    self._ws = ws
+                    delay = self.reconnect_delay
+                    await self._sync(ws)
+            except (OSError, websockets.exceptions.WebSocketException):
+                pass
+            finally:
+                self._ws = None
+                self._synced.clear()
+            if self._closed:
+                break
+            await asyncio.sleep(delay)
+            delay = min(delay * 2, self.max_reconnect_delay)
+
+    async def _sync(self, ws: typing.Any) -> None:
+        assert self._synced is not None
+        for message in self.session.start():
+            await ws.send(message)
+        sender = asyncio.ensure_future(self._send_local_updates(ws))
+        try:
+            async for message in ws:
+                if isinstance(message, str):
+                    continue
+                for reply in self.session.receive(message):
+                    await ws.send(reply)
+                if self.session.synced and not self._synced.is_set():
+                    self._synced.set()
+                    if self.on_sync is not None:
+                        self.on_sync()
+        finally:
+            sender.cancel()
+            try:
+                await sender
+            except (asyncio.CancelledError, Exception):
+                pass
+
+    async def _send_local_updates(self, ws: typing.Any) -> None:
+        assert self._dirty is not None
+        while True:
+            await self._dirty.wait()
+            self._dirty.clear()
+            # `send` waits while the socket's buffer is full, so the updates committed
+            # meanwhile are merged on the next round.
+            for message in self.session.poll(coalesce=True):
+                await ws.send(message)
+
+    async def close(self) -> None:
+        """Disconnect and stop `run`."""
+        self._closed = True
+        self._subscription.unsubscribe()
+        self.session.close()
+        if self._ws is not None:
+            await self._ws.close()
+
+
+DocsProvider = typing.Callable[[str], typing.Union[LoroDoc, typing.Awaitable[LoroDoc]]]
+SnapshotCallback = typing.Callable[
+    [str, bytes], typing.Optional[typing.Awaitable[None]]
+]
+
+
+class _Room:
+    def __init__(self, name: str, doc: LoroDoc) -> None:
+        self.name = name
+        self.doc = doc
+        self.peers: typing.Set[typing.Any] = set()
+        self.dirty = False
+
+
+def _room_name(ws: typing.Any, path: typing.Optional[str]) -> str:
+    if path is None:
+        # `websockets` >= 13 passes the request instead of the path.
+        request = getattr(ws, "request", None)
+        path = request.path if request is not None else ws.path
+    return path.split("?", 1)[0].strip("/")
+
+
+class RelayServer:
+    """A room-based WebSocket relay.
+
+    Peers connect to ``ws://host:port/<room>``. Each room is backed by the document that
+    `docs_provider(room)` returns (or resolves to), which is kept while the room has
+    peers: every peer syncs with it and its updates are forwarded to the other peers.
+    Edits committed to the document on the server, e.g. by the app, are sent to every
+    peer like on a `WebSocketClient`.
+
+    If `on_snapshot` is set, it's called with the room name and a snapshot of its
+    document every `snapshot_interval` seconds while the room changes, and when its last
+    peer leaves, so `docs_provider` can load the room from the store it writes to.
+    """
+
+    def __init__(
+        self,
+        docs_provider: DocsProvider,
+        *,
+        snapshot_interval: typing.Optional[float] = 30.0,
+        on_snapshot: typing.Optional[SnapshotCallback] = None,
+    ) -> None:
+        self.docs_provider = docs_provider
+        self.snapshot_interval = snapshot_interval
+        self.on_snapshot = on_snapshot
+        self._rooms: typing.Dict[str, _Room] = {}
+        self._server: typing.Any = None
+        self._snapshots: typing.Optional[asyncio.Future] = None
+
+    @property
+    def port(self) -> int:
+        """The port the server listens on, useful when started on port 0."""
+        return next(iter(self._server.sockets)).getsockname()[1]
+
+    def rooms(self) -> typing.List[str]:
+        """The names of the rooms with connected peers."""
+        return sorted(self._rooms)
+
+    async def start(self, host: str = "localhost", port: int = 8765) -> None:
+        websockets = _import_websockets()
+        self._server = await websockets.serve(self._handle, host, port)
+        if self.on_snapshot is not None and self.snapshot_interval is not None:
+            self._snapshots = asyncio.ensure_future(self._snapshot_loop())
+
+    async def close(self) -> None:
+        """Stop accepting peers, disconnect them and snapshot the changed rooms."""
+        if self._snapshots is not None:
+            self._snapshots.cancel()
+        if self._server is not None:
+            self._server.close()
+            await self._server.wait_closed()
+        for room in list(self._rooms.values()):
+            await self._snapshot(room)
+
+    async def __aenter__(self) -> RelayServer:
+        return self
+
+    async def __aexit__(self, *_exc: typing.Any) -> None:
+        await self.close()
+
+    async def _room(self, name: str) -> _Room:
+        room = self._rooms.get(name)
+        if room is not None:
+            return room
+        doc = self.docs_provider(name)
+        if inspect.isawaitable(doc):
+            doc = await doc
+        # Another peer may have opened the room while the provider was awaited.
+        return self._rooms.setdefault(name, _Room(name, typing.cast(LoroDoc, doc)))
+
+    async def _handle(self, ws: typing.Any, path: typing.Optional[str] = None) -> None:
+        room = await self._room(_room_name(ws, path))
+        room.peers.add(ws)
+        session = SyncSession(room.doc)
+        loop = asyncio.get_running_loop()
+        dirty = asyncio.Event()
+
+        def on_local_update(_updates: bytes) -> bool:
+            # Commits may happen on another thread than the event loop.
+            loop.call_soon_threadsafe(dirty.set)
+            return True
+
+        subscription = room.doc.subscribe_local_update(on_local_update)
+        sender: typing.Optional[asyncio.Future] = None
+        try:
+            for message in session.start():
+                await ws.send(message)
+            sender = asyncio.ensure_future(
+                self._send_local_updates(room, ws, session, dirty)
+            )
+            async for message in ws:
+                if isinstance(message, str):
+                    continue
+                for reply in session.receive(message):
+                    await ws.send(reply)
+                if message[:1] == bytes([UPDATES]):
+                    room.dirty = True
+                    await self._broadcast(room, ws, message)
+        finally:
+            subscription.unsubscribe()
+            if sender is not None:
+                sender.cancel()
+                try:
+                    await sender
+                except (asyncio.CancelledError, Exception):
+                    pass
+            session.close()
+            room.peers.discard(ws)
+            if not room.peers and self._rooms.get(room.name) is room:
+                del self._rooms[room.name]
+                await self._snapshot(room)
+
+    async def _send_local_updates(
+        self, room: _Room, ws: typing.Any, session: SyncSession, dirty: asyncio.Event
+    ) -> None:
+        while True:
+            await dirty.wait()
+            dirty.clear()
+            room.dirty = True
+            for message in session.poll(coalesce=True):
+                await ws.send(message)
+
+    async def _broadcast(self, room: _Room, sender: typing.Any, message: bytes) -> None:
+        peers = [peer for peer in room.peers if peer is not sender]
+        # A peer that disconnected meanwhile is cleaned up by its own handler.
+        await asyncio.gather(
+            *(peer.send(message) for peer in peers), return_exceptions=True
+        )
+
+    async def _snapshot(self, room: _Room) -> None:
+        if not room.dirty or self.on_snapshot is None:
+            return
+        room.dirty = False
+        result = self.on_snapshot(room.name, room.doc.export(ExportMode.Snapshot()))
+        if inspect.isawaitable(result):
+            await result
+
+    async def _snapshot_loop(self) -> None:
+        assert self.snapshot_interval is not None
+        while True:
+            await asyncio.sleep(self.snapshot_interval)
+            for room in list(self._rooms.values()):
+                await self._snapshot(room)
+
+
+async def serve(
+    docs_provider: DocsProvider,
+    host: str = "localhost",
+    port: int = 8765,
+    *,
+    snapshot_interval: typing.Optional[float] = 30.0,
+    on_snapshot: typing.Optional[SnapshotCallback] = None,
+) -> None:
+    """Run a `RelayServer` on `host:port` until cancelled.
+
+    ::
+
+        docs = {}
+        asyncio.run(serve(lambda room: docs.setdefault(room, LoroDoc())))
+    """
+    server = RelayServer(
+        docs_provider, snapshot_interval=snapshot_interval, on_snapshot=on_snapshot
+    )
+    await server.start(host, port)
+    try:
+        await asyncio.Future()
+    finally:
+        await server.close()
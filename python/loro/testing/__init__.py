@@ -0,0 +1,256 @@
+"""Helpers to test code built on loro, e.g. to fuzz a CRDT schema::
+
+    from loro.testing import assert_converged, make_docs, random_ops
+
+    docs = make_docs(3)
+    random_ops(docs, 1000, seed=42)
+    assert_converged(docs)
+
+The documents have fixed peer ids and the random ops come from a seeded generator, so a
+failing run can be replayed with the same seed.
+"""
+
+from __future__ import annotations
+
+import json
+import random
+import typing
+
+from ..loro import ExportMode, LoroDoc, TreeID
+
+__all__ = [
+    "CONTAINER_TYPES",
+    "make_doc",
+    "make_docs",
+    "random_ops",
+    "sync_all",
+    "assert_converged",
+    "apply_script",
+]
+
+CONTAINER_TYPES = ("text", "list", "map", "movable_list", "tree", "counter")
+"""The root containers edited by `random_ops`, one of each type, named after it."""
+
+_KEYS = ("a", "b", "c", "d")
+_VALUES = (None, True, False, 0, 1, -7, 2**40, 0.5, "", "x", "hello", b"\x00\xff")
+
+
+def make_doc(
+    peer_id: int = 1, *, timestamp: typing.Optional[int] = None, **config: typing.Any
+) -> LoroDoc:
+    """Create a document with a fixed peer id.
+
+    If `timestamp` is given, every change is stamped with it instead of the wall clock.
+    The other keyword arguments are passed to the `LoroDoc` constructor.
+    """
+    doc = LoroDoc(peer_id=peer_id, **config)
+    if timestamp is not None:
+        doc.set_timestamp_provider(lambda: timestamp)
+    return doc
+
+
+def make_docs(
+    n: int, *, first_peer_id: int = 1, **kwargs: typing.Any
+) -> typing.List[LoroDoc]:
+    """Create `n` documents with the peer ids `first_peer_id`, `first_peer_id + 1`...
+
+    The keyword arguments are passed to `make_doc`.
+    """
+    return [make_doc(first_peer_id + i, **kwargs) for i in range(n)]
+
+
+def random_ops(
+    docs: typing.Sequence[LoroDoc],
+    count: int,
+    *,
+    seed: typing.Optional[int] = None,
+    containers: typing.Iterable[str] = CONTAINER_TYPES,
+    commit_probability: float = 0.2,
+    sync_probability: float = 0.05,
+) -> None:
+    """Apply `count` random ops to random documents of `docs`.
+
+    The ops edit the root containers named in `containers` (see `CONTAINER_TYPES`).
+    After each op, the document commits with `commit_probability` and two random
+    documents exchange their updates with `sync_probability`, so the ops interleave like
+    concurrent edits. Every document is committed at the end.
+    """
+    rng = random.Random(seed)
+    containers = list(containers)
+    for name in containers:
+        if name not in CONTAINER_TYPES:
+            raise ValueError(f"unknown container type {name!r}")
+    for _ in range(count):
+        doc = rng.choice(docs)
+        _random_op(rng, doc, rng.choice(containers))
+        if rng.random() < commit_probability:
+            doc.commit()
+        if len(docs) > 1 and rng.random() < sync_probability:
+            a, b = rng.sample(list(docs), 2)
+            _sync(a, b)
+    for doc in docs:
+        doc.commit()
+
+
+def _random_op(rng: random.Random, doc: LoroDoc, container: str) -> None:
+    if container == "text":
+        value: typing.Any = rng.choice("abcxyz🦜 \n")
+    elif container == "counter":
+        value = rng.choice((-2, -1, 1, 3))
+    else:
+        value = rng.choice(_VALUES)
+    _edit(doc, container, rng.random(), rng.randrange(2**32), value)
+
+
+def _edit(
+    doc: LoroDoc, container: str, kind: float, index: int, value: typing.Any
+) -> None:
+    """Edit the root container named after its type `container`.
+
+    `kind` in ``[0, 1)`` picks the method and `index` picks the positions, modulo the
+    length of the container, so any values make a valid edit.
+    """
+    if container == "text":
+        text = doc.get_text("text")
+        n = len(text)
+        if n and kind < 0.3:
+            pos = index % n
+            text.delete(pos, 1 + index // n % (n - pos))
+        else:
+            if not isinstance(value, str) or not value:
+                value = "x"
+            text.insert(index % (n + 1), value)
+    elif container in ("list", "movable_list"):
+        if container == "list":
+            items: typing.Any = doc.get_list(container)
+        else:
+            items = doc.get_movable_list(container)
+        n = len(items)
+        if n and kind < 0.25:
+            items.delete(index % n, 1)
+        elif n and kind < 0.5 and container == "movable_list":
+            items.mov(index % n, index // n % n)
+        elif n and kind < 0.6 and container == "movable_list":
+            items.set(index % n, value)
+        else:
+            items.insert(index % (n + 1), value)
+    elif container == "map":
+        map = doc.get_map("map")
+        key = _KEYS[index % len(_KEYS)]
+        if key in map and kind < 0.3:
+            map.delete(key)
+        else:
+            map.insert(key, value)
+    elif container == "tree":
+        _edit_tree(doc, kind, index, value)
+    elif container == "counter":
+        # Small integers, so the sums are exact in any order the replicas apply them
+        exact = type(value) is int and abs(value) < 2**31
+        doc.get_counter("counter").increment(value if exact else 1)
+
+
+def _edit_tree(doc: LoroDoc, kind: float, index: int, value: typing.Any) -> None:
+    tree = doc.get_tree("tree")
+    nodes = [node.id for node in tree.get_nodes(False)]
+    if not nodes or kind < 0.4:
+        parent = nodes[index % len(nodes)] if nodes and kind < 0.3 else None
+        node = tree.create(parent)
+        tree.get_meta(node).insert("value", value)
+    elif kind < 0.6:
+        tree.delete(nodes[index % len(nodes)])
+    else:
+        target = nodes[index % len(nodes)]
+        parents = [n for n in nodes if not _is_ancestor(tree, target, n)]
+        parent = parents[index // len(nodes) % len(parents)] if parents else None
+        tree.mov(target, parent)
+
+
+def _is_ancestor(tree: typing.Any, ancestor: TreeID, node: TreeID) -> bool:
+    """Whether `ancestor` is `node` or one of its ancestors."""
+    current: typing.Optional[TreeID] = node
+    while current is not None:
+        if current == ancestor:
+            return True
+        current = tree.parent(current)
+    return False
+
+
+def _sync(a: LoroDoc, b: LoroDoc) -> None:
+    a.import_(b.export(ExportMode.Updates(a.oplog_vv)))
+    b.import_(a.export(ExportMode.Updates(b.oplog_vv)))
+
+
+def sync_all(docs: typing.Sequence[LoroDoc]) -> None:
+    """Exchange updates until every document has all the changes of the others."""
+    if not docs:
+        return
+    hub = docs[0]
+    for doc in docs[1:]:
+        hub.import_(doc.export(ExportMode.Updates(hub.oplog_vv)))
+    for doc in docs[1:]:
+        doc.import_(hub.export(ExportMode.Updates(doc.oplog_vv)))
+
+
+def assert_converged(docs: typing.Sequence[LoroDoc], *, sync: bool = True) -> None:
+    """Sync `docs` with `sync_all` and assert they have the same history and state.
+
+    Pass ``sync=False`` to check documents synced by other means, e.g. the sync code
+    under test.
+    """
+    if sync:
+        sync_all(docs)
+    for doc in docs[1:]:
+        if not docs[0].deep_equal(doc, history=True):
+            raise AssertionError(
+                f"peer {doc.peer_id} diverged from peer {docs[0].peer_id}:\n"
+                f"  {doc.get_deep_value()!r}\n"
+                f"  != {docs[0].get_deep_value()!r}"
+            )
+
+
+Script = typing.Sequence[typing.Dict[str, typing.Any]]
+
+
+def apply_script(doc: LoroDoc, script: typing.Union[str, Script]) -> None:
+    """Replay a list of edits, e.g. loaded from a JSON fixture that reproduces a bug::
+
+        apply_script(doc, [
+            {"path": "text", "type": "text", "method": "insert", "args": [0, "hi"]},
+            {"commit": {"message": "greet", "origin": "fixture"}},
+            {"path": "tree", "type": "tree", "method": "create", "args": [None]},
+            {"path": "tree", "method": "create", "args": ["2@1"]},
+            {"commit": True},
+        ])
+
+    `script` is the list or its JSON encoding. An edit calls `method` with `args` on
+    the container at `path` (see `LoroDoc.get_by_str_path`), like `LoroDoc.apply_ops`.
+    A root container that may not exist yet needs its `type`, one of `CONTAINER_TYPES`.
+    Tree nodes are given in the ``"2@1"`` form of `TreeID.parse` (counter@peer). A
+    commit step commits the edits since the previous one, with an optional message and
+    origin. The edits after the last commit step are committed at the end.
+    """
+    if isinstance(script, str):
+        script = json.loads(script)
+    pending: typing.List[typing.Tuple[typing.Any, str, typing.List[typing.Any]]] = []
+    for i, step in enumerate(script):
+        if "commit" in step:
+            options = step["commit"] if isinstance(step["commit"], dict) else {}
+            doc.apply_ops(
+                pending,
+                origin=options.get("origin"),
+                commit_msg=options.get("message"),
+            )
+            pending = []
+            continue
+        try:
+            path, method = step["path"], step["method"]
+        except KeyError as e:
+            raise ValueError(f"step #{i} has no {e.args[0]!r}") from None
+        target: typing.Any = path
+        if "type" in step:
+            if step["type"] not in CONTAINER_TYPES:
+                raise ValueError(f"step #{i} has an unknown type {step['type']!r}")
+            target = getattr(doc, f"get_{step['type']}")(path)
+        pending.append((target, method, list(step.get("args", []))))
+    if pending:
+        doc.apply_ops(pending)
@@ -0,0 +1,103 @@
+"""Hypothesis strategies to property-test code built on loro::
+
+    from hypothesis import given
+    from loro.testing import assert_converged, make_docs
+    from loro.testing.strategies import interleavings, replay
+
+    @given(interleavings(n_peers=3))
+    def test_converges(steps):
+        docs = make_docs(3)
+        replay(docs, steps)
+        assert_converged(docs)
+
+It needs the ``hypothesis`` package (``pip install loro[testing]``).
+
+A step is a tuple:
+
+- ``("edit", peer, container, kind, index, value)``: edit the root container named
+  after its type ``container`` of the document ``peer``. ``kind`` picks the method,
+  ``index`` the positions and ``value`` the inserted value.
+- ``("commit", peer)``: commit the document ``peer``.
+- ``("sync", a, b)``: exchange the updates of the documents ``a`` and ``b``.
+"""
+
+from __future__ import annotations
+
+import typing
+
+from hypothesis import strategies as st
+
+from ..loro import LoroDoc
+from . import CONTAINER_TYPES, _edit, _sync
+
+__all__ = ["loro_values", "edits", "interleavings", "replay"]
+
+Step = typing.Tuple[typing.Any, ...]
+
+
+def loro_values(max_leaves: int = 10) -> st.SearchStrategy[typing.Any]:
+    """Values that can be stored in a container: ``None``, bools, 64-bit ints, floats
+    but NaN, strings, bytes, and lists and string-keyed dicts of them."""
+    leaves = (
+        st.none()
+        | st.booleans()
+        | st.integers(min_value=-(2**63), max_value=2**63 - 1)
+        | st.floats(allow_nan=False)
+        | st.text()
+        | st.binary()
+    )
+    return st.recursive(
+        leaves,
+        lambda children: st.lists(children) | st.dictionaries(st.text(), children),
+        max_leaves=max_leaves,
+    )
+
+
+def edits(
+    containers: typing.Iterable[str] = CONTAINER_TYPES,
+) -> st.SearchStrategy[Step]:
+    """Edits of one peer, as ``("edit", container, kind, index, value)`` tuples."""
+    containers = list(containers)
+    for name in containers:
+        if name not in CONTAINER_TYPES:
+            raise ValueError(f"unknown container type {name!r}")
+    return st.tuples(
+        st.just("edit"),
+        st.sampled_from(containers),
+        st.floats(min_value=0, max_value=1, exclude_max=True),
+        st.integers(min_value=0, max_value=2**32),
+        loro_values(max_leaves=3),
+    )
+
+
+def interleavings(
+    n_peers: int = 2,
+    *,
+    containers: typing.Iterable[str] = CONTAINER_TYPES,
+    max_steps: int = 50,
+) -> st.SearchStrategy[typing.List[Step]]:
+    """Lists of steps interleaving the edits, commits and syncs of `n_peers` peers."""
+    peers = st.integers(min_value=0, max_value=n_peers - 1)
+    edit = st.tuples(peers, edits(containers)).map(
+        lambda pair: (pair[1][0], pair[0], *pair[1][1:])
+    )
+    steps = edit | st.tuples(st.just("commit"), peers)
+    if n_peers > 1:
+        steps |= st.tuples(st.just("sync"), peers, peers).filter(lambda s: s[1] != s[2])
+    return st.lists(steps, max_size=max_steps)
+
+
+def replay(docs: typing.Sequence[LoroDoc], steps: typing.Iterable[Step]) -> None:
+    """Apply `steps` to `docs`, the documents of the peers, then commit them all."""
+    for step in steps:
+        if step[0] == "edit":
+            _, peer, container, kind, index, value = step
+            _edit(docs[peer], container, kind, index, value)
+        elif step[0] == "commit":
+            docs[step[1]].commit()
+        elif step[0] == "sync":
+            _sync(docs[step[1]], docs[step[2]])
+        else:
+            raise ValueError(f"unknown step {step!r}")
+    for doc in docs:
+        doc.commit()
@@ -4,7 +4,7 @@ use std::{borrow::Cow, collections::HashMap};
 use loro::{awareness::EphemeralEventTrigger, LoroError, PeerID};
 use pyo3::{prelude::*, types::PyBytes};
 
-use crate::{err::PyLoroResult, event::Subscription, value::LoroValue};
+use crate::{convert::loro_value_repr, err::PyLoroResult, event::Subscription, value::LoroValue};
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Awareness>()?;
@@ -70,6 +70,33 @@ impl Awareness {
     pub fn peer(&self) -> PeerID {
         self.0.peer()
     }
+
+    /// The peers with a known state, including this one once its local state is set.
+    pub fn peers(&self) -> Vec<PeerID> {
+        let mut peers = self.0.get_all_states().keys().copied().collect::<Vec<_>>();
+        peers.sort_unstable();
+        peers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.get_all_states().is_empty()
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.get_all_states().len()
+    }
+
+    pub fn __contains__(&self, peer: PeerID) -> bool {
+        self.0.get_all_states().contains_key(&peer)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Awareness(peer={}, peers={:?})",
+            self.0.peer(),
+            self.peers()
+        )
+    }
 }
 
 #[pyclass(get_all)]
@@ -87,6 +114,18 @@ pub struct PeerInfo {
     pub timestamp: i64,
 }
 
+#[pymethods]
+impl PeerInfo {
+    pub fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "PeerInfo(state={}, counter={}, timestamp={})",
+            loro_value_repr(py, self.state.0.clone())?,
+            self.counter,
+            self.timestamp
+        ))
+    }
+}
+
 #[pyclass]
 pub struct EphemeralStore(loro::awareness::EphemeralStore);
 
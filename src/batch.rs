@@ -0,0 +1,282 @@
+use loro::LoroDoc as LoroDocInner;
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyList, PyString},
+};
+
+use crate::{
+    container::Container,
+    convert::{tree_id_from_object, tree_parent_from_object},
+    err::{ErrorContextExt, PyLoroResult},
+    value::{ContainerID, LoroValue},
+};
+
+/// A single edit accepted by `LoroDoc.apply_ops`.
+///
+/// It's a `(target, op, args)` tuple:
+///
+/// - `target` is a container, a `ContainerID` or a path string accepted by `get_by_str_path`.
+/// - `op` is the name of the method to apply, e.g. `"insert"`.
+/// - `args` is the sequence of arguments passed to that method.
+#[derive(FromPyObject)]
+pub struct OpDescriptor<'py>(
+    pub Bound<'py, PyAny>,
+    pub String,
+    pub Vec<Bound<'py, PyAny>>,
+);
+
+/// Resolve the target of an op into a container of `doc`.
+pub(crate) fn resolve_target(doc: &LoroDocInner, target: &Bound<'_, PyAny>) -> PyResult<Container> {
+    if let Ok(container) = target.extract::<Container>() {
+        return Ok(container);
+    }
+    if let Ok(id) = target.downcast::<ContainerID>() {
+        return doc
+            .get_container(id.get().into())
+            .map(Container::from)
+            .ok_or_else(|| PyValueError::new_err(format!("container {} not found", id.get())));
+    }
+    if let Ok(path) = target.downcast::<PyString>() {
+        let path = path.to_str()?;
+        return match doc.get_by_str_path(path) {
+            Some(loro::ValueOrContainer::Container(c)) => Ok(c.into()),
+            Some(loro::ValueOrContainer::Value(_)) => Err(PyTypeError::new_err(format!(
+                "path '{path}' points to a value, not a container"
+            ))),
+            None => Err(PyValueError::new_err(format!("path '{path}' not found"))),
+        };
+    }
+    Err(PyTypeError::new_err(
+        "op target must be a container, a ContainerID or a path string",
+    ))
+}
+
+/// Add `note` to the notes of the exception, as `BaseException.add_note` does.
+///
+/// `add_note` is only available from Python 3.11, older versions get `__notes__` set directly.
+pub(crate) fn add_note(py: Python<'_>, err: &PyErr, note: &str) -> PyResult<()> {
+    let value = err.value(py);
+    if value.hasattr("add_note")? {
+        value.call_method1("add_note", (note,))?;
+        return Ok(());
+    }
+    let notes = match value.getattr("__notes__") {
+        Ok(notes) => notes.downcast_into::<PyList>()?,
+        Err(_) => PyList::empty(py),
+    };
+    notes.append(note)?;
+    value.setattr("__notes__", notes)
+}
+
+fn check_arity(op: &str, args: &[Bound<'_, PyAny>], expected: usize) -> PyResult<()> {
+    if args.len() != expected {
+        return Err(PyTypeError::new_err(format!(
+            "'{op}' expects {expected} argument(s), got {}",
+            args.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Apply a single op on the given container.
+pub(crate) fn apply_op(
+    container: &Container,
+    op: &str,
+    args: &[Bound<'_, PyAny>],
+) -> PyLoroResult<()> {
+    match (container, op) {
+        (Container::Text(text), "insert") => {
+            check_arity(op, args, 2)?;
+            let pos: usize = args[0].extract()?;
+            text.0.insert(pos, args[1].extract::<&str>()?).context_at(
+                &text.0,
+                "LoroText.insert",
+                pos,
+            )?;
+        }
+        (Container::Text(text), "delete") => {
+            check_arity(op, args, 2)?;
+            let pos: usize = args[0].extract()?;
+            text.0
+                .delete(pos, args[1].extract()?)
+                .context_at(&text.0, "LoroText.delete", pos)?;
+        }
+        (Container::Text(text), "push_str") => {
+            check_arity(op, args, 1)?;
+            text.0
+                .push_str(args[0].extract::<&str>()?)
+                .context(&text.0, "LoroText.push_str")?;
+        }
+        (Container::Text(text), "mark") => {
+            check_arity(op, args, 4)?;
+            let start: usize = args[0].extract()?;
+            let end: usize = args[1].extract()?;
+            let value: LoroValue = args[3].extract()?;
+            text.0
+                .mark(start..end, args[2].extract::<&str>()?, value.0)
+                .context_at(&text.0, "LoroText.mark", start)?;
+        }
+        (Container::Text(text), "unmark") => {
+            check_arity(op, args, 3)?;
+            let start: usize = args[0].extract()?;
+            let end: usize = args[1].extract()?;
+            text.0
+                .unmark(start..end, args[2].extract::<&str>()?)
+                .context_at(&text.0, "LoroText.unmark", start)?;
+        }
+        (Container::Map(map), "insert") => {
+            check_arity(op, args, 2)?;
+            let value: LoroValue = args[1].extract()?;
+            let key: &str = args[0].extract()?;
+            map.0
+                .insert(key, value.0)
+                .context_at(&map.0, "LoroMap.insert", key)?;
+        }
+        (Container::Map(map), "delete") => {
+            check_arity(op, args, 1)?;
+            let key: &str = args[0].extract()?;
+            map.0
+                .delete(key)
+                .context_at(&map.0, "LoroMap.delete", key)?;
+        }
+        (Container::Map(map), "clear") => {
+            check_arity(op, args, 0)?;
+            map.0.clear()?;
+        }
+        (Container::List(list), "insert") => {
+            check_arity(op, args, 2)?;
+            let value: LoroValue = args[1].extract()?;
+            let pos: usize = args[0].extract()?;
+            list.0
+                .insert(pos, value.0)
+                .context_at(&list.0, "LoroList.insert", pos)?;
+        }
+        (Container::List(list), "delete") => {
+            check_arity(op, args, 2)?;
+            let pos: usize = args[0].extract()?;
+            list.0
+                .delete(pos, args[1].extract()?)
+                .context_at(&list.0, "LoroList.delete", pos)?;
+        }
+        (Container::List(list), "push") => {
+            check_arity(op, args, 1)?;
+            let value: LoroValue = args[0].extract()?;
+            list.0.push(value.0).context(&list.0, "LoroList.push")?;
+        }
+        (Container::List(list), "clear") => {
+            check_arity(op, args, 0)?;
+            list.0.clear()?;
+        }
+        (Container::MovableList(list), "insert") => {
+            check_arity(op, args, 2)?;
+            let value: LoroValue = args[1].extract()?;
+            let pos: usize = args[0].extract()?;
+            list.0
+                .insert(pos, value.0)
+                .context_at(&list.0, "LoroMovableList.insert", pos)?;
+        }
+        (Container::MovableList(list), "delete") => {
+            check_arity(op, args, 2)?;
+            let pos: usize = args[0].extract()?;
+            list.0.delete(pos, args[1].extract()?).context_at(
+                &list.0,
+                "LoroMovableList.delete",
+                pos,
+            )?;
+        }
+        (Container::MovableList(list), "push") => {
+            check_arity(op, args, 1)?;
+            let value: LoroValue = args[0].extract()?;
+            list.0
+                .push(value.0)
+                .context(&list.0, "LoroMovableList.push")?;
+        }
+        (Container::MovableList(list), "set") => {
+            check_arity(op, args, 2)?;
+            let value: LoroValue = args[1].extract()?;
+            let pos: usize = args[0].extract()?;
+            list.0
+                .set(pos, value.0)
+                .context_at(&list.0, "LoroMovableList.set", pos)?;
+        }
+        (Container::MovableList(list), "mov") => {
+            check_arity(op, args, 2)?;
+            let from: usize = args[0].extract()?;
+            list.0.mov(from, args[1].extract()?).context_at(
+                &list.0,
+                "LoroMovableList.mov",
+                from,
+            )?;
+        }
+        (Container::MovableList(list), "clear") => {
+            check_arity(op, args, 0)?;
+            list.0.clear()?;
+        }
+        (Container::Counter(counter), "increment") => {
+            check_arity(op, args, 1)?;
+            counter
+                .0
+                .increment(args[0].extract()?)
+                .context(&counter.0, "LoroCounter.increment")?;
+        }
+        (Container::Counter(counter), "decrement") => {
+            check_arity(op, args, 1)?;
+            counter
+                .0
+                .decrement(args[0].extract()?)
+                .context(&counter.0, "LoroCounter.decrement")?;
+        }
+        (Container::Tree(tree), "create") => {
+            let parent = match args {
+                [] => None,
+                [parent] => tree_parent_from_object(parent)?,
+                _ => {
+                    return Err(PyTypeError::new_err(format!(
+                        "'{op}' expects at most 1 argument, got {}",
+                        args.len()
+                    ))
+                    .into())
+                }
+            };
+            tree.0
+                .create(parent.map(loro::TreeID::from))
+                .context(&tree.0, "LoroTree.create")?;
+        }
+        (Container::Tree(tree), "mov") => {
+            check_arity(op, args, 2)?;
+            let target = tree_id_from_object(&args[0])?;
+            let parent = tree_parent_from_object(&args[1])?;
+            tree.0
+                .mov(target.into(), parent.map(loro::TreeID::from))
+                .context_at(&tree.0, "LoroTree.mov", target)?;
+        }
+        (Container::Tree(tree), "delete") => {
+            check_arity(op, args, 1)?;
+            let target = tree_id_from_object(&args[0])?;
+            tree.0
+                .delete(target.into())
+                .context_at(&tree.0, "LoroTree.delete", target)?;
+        }
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unsupported op '{op}' for {}",
+                container_kind(container)
+            ))
+            .into())
+        }
+    }
+    Ok(())
+}
+
+fn container_kind(container: &Container) -> &'static str {
+    match container {
+        Container::List(_) => "LoroList",
+        Container::Map(_) => "LoroMap",
+        Container::MovableList(_) => "LoroMovableList",
+        Container::Text(_) => "LoroText",
+        Container::Tree(_) => "LoroTree",
+        Container::Counter(_) => "LoroCounter",
+        Container::Unknown(_) => "LoroUnknown",
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+
+use loro::LoroDoc as LoroDocInner;
+use pyo3::{prelude::*, types::PyBytes};
+
+use crate::{
+    compress,
+    doc::{ImportBlobMetadata, LoroDoc},
+    err::PyLoroResult,
+    event::DiffBatch,
+};
+
+pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<BlobInspection>()?;
+    Ok(())
+}
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(inspect_blob, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_blobs, m)?)?;
+    Ok(())
+}
+
+/// The result of `inspect_blob`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct BlobInspection {
+    /// Whether the blob can be imported.
+    pub valid: bool,
+    /// Why the blob can't be imported: a bad checksum, a truncated or corrupt blob, or a blob
+    /// the document can't accept, such as updates depending on history trimmed by a shallow
+    /// snapshot.
+    pub error: Option<String>,
+    /// The metadata of the blob, unless its header can't be decoded.
+    pub meta: Option<ImportBlobMetadata>,
+    /// Whether some ops of the blob depend on ops that are missing, so importing it would
+    /// leave them pending.
+    pub pending: bool,
+}
+
+impl fmt::Display for BlobInspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BlobInspection(valid={}, error={:?}, pending={})",
+            if self.valid { "True" } else { "False" },
+            self.error,
+            if self.pending { "True" } else { "False" }
+        )
+    }
+}
+
+#[pymethods]
+impl BlobInspection {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl BlobInspection {
+    fn invalid(error: String, meta: Option<ImportBlobMetadata>) -> Self {
+        Self {
+            valid: false,
+            error: Some(error),
+            meta,
+            pending: false,
+        }
+    }
+}
+
+/// Check a blob without importing it, e.g. to reject a corrupt upload before it reaches
+/// the document.
+///
+/// The checksum and the header of the blob are verified, then the blob is imported into a
+/// fork of `doc`, or into an empty document if `doc` is `None`, so a truncated or corrupt
+/// body and a blob the document can't accept are caught as well. The document itself is
+/// never modified, but forking it is O(n). Encrypted blobs are decrypted with the hook set
+/// by `LoroDoc.set_encryption` on `doc`.
+#[pyfunction]
+#[pyo3(signature = (bytes, doc=None))]
+pub fn inspect_blob(bytes: Bound<'_, PyBytes>, doc: Option<PyRef<'_, LoroDoc>>) -> BlobInspection {
+    let bytes = match &doc {
+        Some(doc) => match doc.hooks.decrypt(bytes.as_bytes()) {
+            Ok(bytes) => bytes,
+            Err(e) => return BlobInspection::invalid(PyErr::from(e).to_string(), None),
+        },
+        None => match compress::decompress(bytes.as_bytes().into()) {
+            Ok(bytes) => bytes,
+            Err(e) => return BlobInspection::invalid(PyErr::from(e).to_string(), None),
+        },
+    };
+    let meta = match LoroDocInner::decode_import_blob_meta(&bytes, true) {
+        Ok(meta) => ImportBlobMetadata::from(meta),
+        Err(e) => return BlobInspection::invalid(e.to_string(), None),
+    };
+    let target = match &doc {
+        Some(doc) => doc.doc.fork(),
+        None => LoroDocInner::new(),
+    };
+    match target.import(&bytes) {
+        Ok(status) => BlobInspection {
+            valid: true,
+            error: None,
+            meta: Some(meta),
+            pending: status.pending.is_some(),
+        },
+        Err(e) => BlobInspection::invalid(e.to_string(), Some(meta)),
+    }
+}
+
+/// The diff turning the document saved in blob `a` into the one saved in blob `b`, e.g. to
+/// verify a backup or to see how two replicas diverged.
+///
+/// Both blobs are loaded into one document and diffed between their versions, so they may
+/// come from unrelated documents. The diff is empty if the documents have the same state.
+#[pyfunction]
+pub fn diff_blobs(a: Bound<'_, PyBytes>, b: Bound<'_, PyBytes>) -> PyLoroResult<DiffBatch> {
+    let a = compress::decompress(a.as_bytes().into())?;
+    let b = compress::decompress(b.as_bytes().into())?;
+    let other = LoroDocInner::new();
+    other.import(&b)?;
+    let doc = LoroDocInner::new();
+    doc.import(&a)?;
+    let from = doc.oplog_frontiers();
+    doc.import(&b)?;
+    Ok(doc.diff(&from, &other.oplog_frontiers())?.into())
+}
@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+
+use loro::{Counter, LoroDoc as LoroDocInner};
+use pyo3::exceptions::PyValueError;
+
+use crate::{
+    doc::{ExportMode, LoroDoc},
+    err::{PyLoroError, PyLoroResult},
+    history,
+};
+
+/// Export the updates of `mode` as blobs of at most `max_bytes` each, in causal order.
+///
+/// The blobs are made of whole changes when possible. A change too large for a blob is split
+/// between its ops.
+pub fn export_chunks(
+    doc: &LoroDoc,
+    mode: ExportMode,
+    max_bytes: usize,
+) -> PyLoroResult<Vec<Vec<u8>>> {
+    if max_bytes == 0 {
+        return Err(PyValueError::new_err("max_bytes must be positive").into());
+    }
+    let mut spans = change_spans(&doc.doc, mode)?;
+    let export = |spans: &[loro::IdSpan]| -> PyLoroResult<Option<Vec<u8>>> {
+        let bytes = doc.doc.export(loro::ExportMode::UpdatesInRange {
+            spans: Cow::Borrowed(spans),
+        })?;
+        let bytes = doc.hooks.encrypt(bytes)?;
+        Ok((bytes.len() <= max_bytes).then_some(bytes))
+    };
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < spans.len() {
+        let Some(mut chunk) = export(&spans[start..start + 1])? else {
+            let span = spans[start];
+            let len = span.counter.end - span.counter.start;
+            if len == 1 {
+                return Err(PyLoroError::Error(format!(
+                    "the op {}@{} doesn't fit in {} bytes",
+                    span.counter.start, span.peer, max_bytes
+                )));
+            }
+            let mid = span.counter.start + len / 2;
+            spans.splice(
+                start..start + 1,
+                [
+                    loro::IdSpan::new(span.peer, span.counter.start, mid),
+                    loro::IdSpan::new(span.peer, mid, span.counter.end),
+                ],
+            );
+            continue;
+        };
+        // The largest number of spans that fit, found by doubling then bisecting
+        let mut fits = 1;
+        let mut too_many = spans.len() - start + 1;
+        let mut n = 2;
+        while n < too_many {
+            match export(&spans[start..start + n])? {
+                Some(bytes) => {
+                    fits = n;
+                    chunk = bytes;
+                    n *= 2;
+                }
+                None => too_many = n,
+            }
+        }
+        while too_many - fits > 1 {
+            let mid = (fits + too_many) / 2;
+            match export(&spans[start..start + mid])? {
+                Some(bytes) => {
+                    fits = mid;
+                    chunk = bytes;
+                }
+                None => too_many = mid,
+            }
+        }
+        chunks.push(chunk);
+        start += fits;
+    }
+    Ok(chunks)
+}
+
+/// The parts of the changes exported by `mode`, parents before their children.
+fn change_spans(doc: &LoroDocInner, mode: ExportMode) -> PyLoroResult<Vec<loro::IdSpan>> {
+    // The counter ranges to export, or `None` for the whole history
+    let ranges: Option<Vec<(loro::PeerID, Counter, Counter)>> = match mode.resolve(doc) {
+        loro::ExportMode::Snapshot => None,
+        loro::ExportMode::Updates { from } => {
+            let vv = doc.oplog_vv();
+            let ranges = vv
+                .iter()
+                .map(|(&peer, &end)| (peer, from.get(&peer).copied().unwrap_or(0), end));
+            Some(ranges.collect())
+        }
+        loro::ExportMode::UpdatesInRange { spans } => {
+            let ranges = spans.iter().map(|span| {
+                let (a, b) = (span.counter.start, span.counter.end);
+                (span.peer, a.min(b), a.max(b))
+            });
+            Some(ranges.collect())
+        }
+        _ => {
+            return Err(PyValueError::new_err(
+                "only the Snapshot, Updates, UpdatesInRange and UpdatesInTimeRange modes can be exported in chunks",
+            )
+            .into())
+        }
+    };
+    let mut spans = Vec::new();
+    for change in history::changes(doc, None) {
+        let peer = change.id.peer;
+        let start = change.id.counter;
+        let end = start + change.len as Counter;
+        let Some(ranges) = &ranges else {
+            spans.push(loro::IdSpan::new(peer, start, end));
+            continue;
+        };
+        for &(p, from, to) in ranges {
+            let (from, to) = (from.max(start), to.min(end));
+            if p == peer && from < to {
+                spans.push(loro::IdSpan::new(peer, from, to));
+            }
+        }
+    }
+    Ok(spans)
+}
@@ -0,0 +1,230 @@
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDate, PyDateTime, PyType},
+};
+use rustc_hash::FxHashMap;
+
+/// The key marking a map as a value encoded by a registered codec.
+pub const CODEC_TAG_KEY: &str = "$loro_codec";
+/// The key holding the encoded payload of a tagged map.
+pub const CODEC_VALUE_KEY: &str = "value";
+/// The tag of `datetime.datetime` values, stored as ISO 8601 strings.
+const DATETIME_TAG: &str = "datetime";
+/// The tag of `datetime.date` values, stored as ISO 8601 strings.
+const DATE_TAG: &str = "date";
+
+/// Whether a document has enabled datetime decoding, so reads from the others skip looking up
+/// their flag.
+static DATETIME_DECODING_USED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Whether the value being converted was read from a document with datetime decoding
+    /// enabled, see [decoding_datetime].
+    static DECODE_DATETIME: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(register_codec, m)?)?;
+    m.add_function(wrap_pyfunction!(unregister_codec, m)?)?;
+    Ok(())
+}
+
+struct Codec {
+    ty: Py<PyType>,
+    tag: String,
+    encode: Py<PyAny>,
+    decode: Py<PyAny>,
+}
+
+impl Codec {
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        Self {
+            ty: self.ty.clone_ref(py),
+            tag: self.tag.clone(),
+            encode: self.encode.clone_ref(py),
+            decode: self.decode.clone_ref(py),
+        }
+    }
+}
+
+static CODECS: Mutex<Vec<Codec>> = Mutex::new(Vec::new());
+/// Whether [CODECS] is non-empty, so converting values skips the lock when no codec is
+/// registered. Only written while holding the lock.
+static HAS_CODECS: AtomicBool = AtomicBool::new(false);
+
+/// Snapshot the registry so the codecs can call back into loro without holding the lock.
+fn codecs(py: Python<'_>) -> Vec<Codec> {
+    let codecs = CODECS.lock().unwrap();
+    codecs.iter().map(|c| c.clone_ref(py)).collect()
+}
+
+/// Register a codec to store instances of `python_type` in Loro documents.
+///
+/// `encode` turns an instance into a value Loro supports natively, and `decode` turns that
+/// value back into an instance. Encoded values are stored as a map
+/// `{"$loro_codec": tag, "value": encoded}`. The `tag` defaults to the qualified name of
+/// the type and must stay stable across versions of your app.
+///
+/// Registering a type again replaces its previous codec.
+#[pyfunction]
+#[pyo3(signature = (python_type, encode, decode, tag=None))]
+pub fn register_codec(
+    python_type: Bound<'_, PyType>,
+    encode: Py<PyAny>,
+    decode: Py<PyAny>,
+    tag: Option<String>,
+) -> PyResult<()> {
+    let tag = match tag {
+        Some(tag) => tag,
+        None => format!(
+            "{}.{}",
+            python_type.module()?.to_str()?,
+            python_type.qualname()?.to_str()?
+        ),
+    };
+    let mut codecs = CODECS.lock().unwrap();
+    if codecs
+        .iter()
+        .any(|c| c.tag == tag && !c.ty.bind(python_type.py()).is(&python_type))
+    {
+        return Err(PyValueError::new_err(format!(
+            "codec tag '{tag}' is already registered for another type"
+        )));
+    }
+    codecs.retain(|c| !c.ty.bind(python_type.py()).is(&python_type));
+    codecs.push(Codec {
+        ty: python_type.unbind(),
+        tag,
+        encode,
+        decode,
+    });
+    HAS_CODECS.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// Remove the codec registered for `python_type`.
+///
+/// Return whether a codec was removed.
+#[pyfunction]
+pub fn unregister_codec(python_type: Bound<'_, PyType>) -> bool {
+    let mut codecs = CODECS.lock().unwrap();
+    let len = codecs.len();
+    codecs.retain(|c| !c.ty.bind(python_type.py()).is(&python_type));
+    HAS_CODECS.store(!codecs.is_empty(), Ordering::Release);
+    codecs.len() != len
+}
+
+/// Record that a document has enabled datetime decoding, see [datetime_decoding_used].
+pub fn use_datetime_decoding() {
+    DATETIME_DECODING_USED.store(true, Ordering::Relaxed);
+}
+
+/// Whether any document has enabled datetime decoding. Until one does, reads don't need to
+/// look up the flag of their document.
+pub fn datetime_decoding_used() -> bool {
+    DATETIME_DECODING_USED.load(Ordering::Relaxed)
+}
+
+/// Run `f` with datetime decoding enabled if `enabled` is true, so the values it converts
+/// decode their tagged datetimes. Nested conversions keep the setting of the outer one.
+pub fn decoding_datetime<R>(enabled: bool, f: impl FnOnce() -> R) -> R {
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            DECODE_DATETIME.set(false);
+        }
+    }
+
+    if !enabled || DECODE_DATETIME.get() {
+        return f();
+    }
+    DECODE_DATETIME.set(true);
+    let _reset = Reset;
+    f()
+}
+
+fn tagged(tag: String, value: loro::LoroValue) -> loro::LoroValue {
+    let mut map = FxHashMap::default();
+    map.insert(CODEC_TAG_KEY.to_string(), tag.into());
+    map.insert(CODEC_VALUE_KEY.to_string(), value);
+    loro::LoroValue::Map(map.into())
+}
+
+/// Encode `obj` with the first codec whose type it is an instance of, falling back to the
+/// built-in datetime encoding.
+///
+/// Return `None` if no codec applies.
+pub fn encode(obj: &Bound<'_, PyAny>) -> PyResult<Option<loro::LoroValue>> {
+    if HAS_CODECS.load(Ordering::Acquire) {
+        let py = obj.py();
+        for codec in codecs(py) {
+            if !obj.is_instance(codec.ty.bind(py))? {
+                continue;
+            }
+            let encoded = codec.encode.bind(py).call1((obj,))?;
+            let value = crate::convert::pyobject_to_loro_value(&encoded)?;
+            return Ok(Some(tagged(codec.tag, value)));
+        }
+    }
+    // `datetime` is a subclass of `date`, so it must be checked first.
+    let tag = if obj.is_instance_of::<PyDateTime>() {
+        DATETIME_TAG
+    } else if obj.is_instance_of::<PyDate>() {
+        DATE_TAG
+    } else {
+        return Ok(None);
+    };
+    let iso = obj.call_method0("isoformat")?.extract::<String>()?;
+    Ok(Some(tagged(tag.to_string(), iso.into())))
+}
+
+/// Decode a tagged map produced by [encode].
+///
+/// Return `None` if `map` isn't tagged or its tag has no codec enabled, so it's
+/// converted as a plain dict.
+pub fn decode<'py>(
+    py: Python<'py>,
+    map: &loro::LoroMapValue,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    if map.len() != 2 {
+        return Ok(None);
+    }
+    let (Some(loro::LoroValue::String(tag)), Some(value)) =
+        (map.get(CODEC_TAG_KEY), map.get(CODEC_VALUE_KEY))
+    else {
+        return Ok(None);
+    };
+    let tag = tag.to_string();
+    let codec = if HAS_CODECS.load(Ordering::Acquire) {
+        codecs(py).into_iter().find(|c| c.tag == tag)
+    } else {
+        None
+    };
+    if let Some(codec) = codec {
+        let value = crate::convert::loro_value_to_pyobject(py, value.clone().into())?;
+        let decoded = codec.decode.bind(py).call1((value,))?;
+        return Ok(Some(decoded));
+    }
+    if !DECODE_DATETIME.get() {
+        return Ok(None);
+    }
+    let ty = match tag.as_str() {
+        DATETIME_TAG => py.get_type::<PyDateTime>(),
+        DATE_TAG => py.get_type::<PyDate>(),
+        _ => return Ok(None),
+    };
+    let loro::LoroValue::String(iso) = value else {
+        return Ok(None);
+    };
+    let decoded = ty.call_method1("fromisoformat", (iso.to_string(),))?;
+    Ok(Some(decoded))
+}
@@ -0,0 +1,76 @@
+use std::{
+    borrow::Cow,
+    io::Read,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::err::{PyLoroError, PyLoroResult};
+
+/// The prefix of the blobs compressed by `LoroDoc.export`, which tells them apart from plain
+/// blobs on import.
+const ZSTD_MAGIC: &[u8] = b"lorozst\x01";
+
+/// The largest size a compressed blob may inflate to, set with `set_max_decompressed_size`.
+static MAX_DECOMPRESSED_SIZE: AtomicUsize = AtomicUsize::new(256 * 1024 * 1024);
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(set_max_decompressed_size, m)?)?;
+    Ok(())
+}
+
+/// Set the largest size, in bytes, a blob compressed by `LoroDoc.export` may decompress to.
+///
+/// Compressed blobs are decompressed by every import, whether or not the application
+/// compresses its own exports, so this bounds the memory a crafted blob can take before the
+/// import filter and the quota hook see it. It defaults to 256 MiB and applies to the whole
+/// process.
+#[pyfunction]
+pub fn set_max_decompressed_size(max_bytes: usize) {
+    MAX_DECOMPRESSED_SIZE.store(max_bytes, Ordering::Relaxed);
+}
+
+/// Compress an exported blob with the `compression` passed to `LoroDoc.export`.
+pub fn compress(bytes: Vec<u8>, compression: Option<&str>, level: i32) -> PyLoroResult<Vec<u8>> {
+    match compression {
+        None => Ok(bytes),
+        Some("zstd") => {
+            let compressed = zstd::bulk::compress(&bytes, level)
+                .map_err(|e| PyLoroError::Error(format!("failed to compress the blob: {}", e)))?;
+            let mut ans = ZSTD_MAGIC.to_vec();
+            ans.extend(compressed);
+            Ok(ans)
+        }
+        Some(other) => Err(PyValueError::new_err(format!(
+            "unknown compression '{}', expected 'zstd' or None",
+            other
+        ))
+        .into()),
+    }
+}
+
+/// Decompress a blob compressed by `LoroDoc.export`, or return a plain blob as is.
+///
+/// Fails if the blob decompresses to more than `set_max_decompressed_size` bytes.
+pub fn decompress(bytes: Cow<'_, [u8]>) -> PyLoroResult<Cow<'_, [u8]>> {
+    let Some(payload) = bytes.strip_prefix(ZSTD_MAGIC) else {
+        return Ok(bytes);
+    };
+    let limit = MAX_DECOMPRESSED_SIZE.load(Ordering::Relaxed);
+    let decoder = zstd::stream::read::Decoder::new(payload)
+        .map_err(|e| PyLoroError::Error(format!("failed to decompress the blob: {}", e)))?;
+    let mut ans = Vec::new();
+    // Reading one byte past the limit tells a blob of exactly `limit` bytes from a larger one.
+    decoder
+        .take((limit as u64).saturating_add(1))
+        .read_to_end(&mut ans)
+        .map_err(|e| PyLoroError::Error(format!("failed to decompress the blob: {}", e)))?;
+    if ans.len() > limit {
+        return Err(PyLoroError::Error(format!(
+            "the blob decompresses to more than {} bytes, see set_max_decompressed_size",
+            limit
+        )));
+    }
+    Ok(Cow::Owned(ans))
+}
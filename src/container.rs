@@ -1,5 +1,8 @@
+use loro::ContainerTrait;
 use pyo3::prelude::*;
 
+use crate::hooks::DocHooks;
+
 mod counter;
 mod list;
 mod map;
@@ -27,11 +30,54 @@ pub enum Container {
     Unknown(LoroUnknown),
 }
 
+impl Container {
+    /// Get the deep value of the container, the same shape it has in `LoroDoc.get_deep_value`.
+    pub fn get_deep_value(&self) -> loro::LoroValue {
+        match self {
+            Container::List(list) => list.0.get_deep_value(),
+            Container::Map(map) => map.0.get_deep_value(),
+            Container::MovableList(list) => list.0.get_deep_value(),
+            Container::Text(text) => text.0.to_string().into(),
+            Container::Tree(tree) => tree.0.get_value_with_meta(),
+            Container::Counter(counter) => counter.0.get_value().into(),
+            Container::Unknown(unknown) => loro::LoroValue::Container(unknown.0.id()),
+        }
+    }
+
+    /// Whether the document of the container has datetime decoding enabled, see
+    /// [DocHooks::datetime_decoding].
+    pub fn decodes_datetime(&self) -> bool {
+        match self {
+            Container::List(list) => DocHooks::datetime_decoding(&list.0),
+            Container::Map(map) => DocHooks::datetime_decoding(&map.0),
+            Container::MovableList(list) => DocHooks::datetime_decoding(&list.0),
+            Container::Text(text) => DocHooks::datetime_decoding(&text.0),
+            Container::Tree(tree) => DocHooks::datetime_decoding(&tree.0),
+            Container::Counter(counter) => DocHooks::datetime_decoding(&counter.0),
+            Container::Unknown(unknown) => DocHooks::datetime_decoding(&unknown.0),
+        }
+    }
+
+    /// Get the id of the container.
+    pub fn id(&self) -> loro::ContainerID {
+        match self {
+            Container::List(list) => list.0.id(),
+            Container::Map(map) => map.0.id(),
+            Container::MovableList(list) => list.0.id(),
+            Container::Text(text) => text.0.id(),
+            Container::Tree(tree) => tree.0.id(),
+            Container::Counter(counter) => counter.0.id(),
+            Container::Unknown(unknown) => unknown.0.id(),
+        }
+    }
+}
+
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     text::register_class(m)?;
     map::register_class(m)?;
     tree::register_class(m)?;
     m.add_class::<LoroList>()?;
+    m.add_class::<list::ListIdIter>()?;
     m.add_class::<LoroMovableList>()?;
     m.add_class::<LoroCounter>()?;
     m.add_class::<LoroUnknown>()?;
@@ -2,8 +2,10 @@ use std::sync::Arc;
 
 use crate::{
     doc::LoroDoc,
-    err::PyLoroResult,
-    event::{DiffEvent, Subscription},
+    err::{ErrorContextExt, PyLoroResult},
+    event::Subscription,
+    hash,
+    hooks::DocHooks,
     value::ContainerID,
 };
 use loro::{ContainerTrait, LoroCounter as LoroCounterInner};
@@ -33,6 +35,15 @@ impl LoroCounter {
         Self::default()
     }
 
+    pub fn __repr__(&self) -> String {
+        format!("LoroCounter({:?})", self.0.get_value())
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<f64> {
+        vec![self.0.get_value()]
+    }
+
     /// Return container id of the Counter.
     #[getter]
     pub fn id(&self) -> ContainerID {
@@ -41,13 +52,17 @@ impl LoroCounter {
 
     /// Increment the counter by the given value.
     pub fn increment(&self, py: Python, value: Py<PyAny>) -> PyLoroResult<()> {
-        self.0.increment(value.extract::<f64>(py)?)?;
+        self.0
+            .increment(value.extract::<f64>(py)?)
+            .context(&self.0, "LoroCounter.increment")?;
         Ok(())
     }
 
     /// Decrement the counter by the given value.
     pub fn decrement(&self, py: Python, value: Py<PyAny>) -> PyLoroResult<()> {
-        self.0.decrement(value.extract::<f64>(py)?)?;
+        self.0
+            .decrement(value.extract::<f64>(py)?)
+            .context(&self.0, "LoroCounter.decrement")?;
         Ok(())
     }
 
@@ -58,6 +73,16 @@ impl LoroCounter {
         self.0.get_value()
     }
 
+    /// The value of the counter as a `float`, the same as `get_value`.
+    pub fn to_py(&self) -> f64 {
+        self.0.get_value()
+    }
+
+    /// A stable hash of the current state of the counter, see `LoroDoc.state_hash`.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.0.get_value().into())
+    }
+
     pub fn __float__(&self) -> f64 {
         self.0.get_value()
     }
@@ -116,9 +141,10 @@ impl LoroCounter {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     pub fn subscribe(&self, callback: Py<PyAny>) -> Option<Subscription> {
+        let hooks = DocHooks::of(&self.0.doc()?);
         let subscription = self.0.subscribe(Arc::new(move |e| {
             Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
+                callback.call1(py, (hooks.event(e),)).unwrap();
             });
         }));
         subscription.map(|s| s.into())
@@ -7,11 +7,16 @@ use pyo3::{
     BoundObject,
 };
 
-use crate::container::utils::{py_any_to_loro_values, slice_indices_positions, SliceOrInt};
+use crate::container::utils::{
+    py_any_to_loro_values, read_value, slice_indices_positions, value_or_deep_value, SliceOrInt,
+};
 use crate::{
+    convert::loro_value_repr,
     doc::LoroDoc,
-    err::{PyLoroError, PyLoroResult},
-    event::{DiffEvent, Subscription},
+    err::{ErrorContextExt, PyLoroError, PyLoroResult},
+    event::Subscription,
+    hash,
+    hooks::DocHooks,
     value::{ContainerID, LoroValue, ValueOrContainer, ID},
 };
 
@@ -32,6 +37,18 @@ impl LoroList {
         Self::default()
     }
 
+    pub fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "LoroList({})",
+            loro_value_repr(py, self.0.get_deep_value())?
+        ))
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<LoroValue> {
+        vec![self.0.get_deep_value().into()]
+    }
+
     /// Whether the container is attached to a document
     ///
     /// The edits on a detached container will not be persisted.
@@ -43,27 +60,58 @@ impl LoroList {
 
     /// Insert a value at the given position.
     pub fn insert(&self, pos: usize, v: LoroValue) -> PyLoroResult<()> {
-        self.0.insert(pos, &v)?;
+        self.0
+            .insert(pos, &v)
+            .context_at(&self.0, "LoroList.insert", pos)?;
         Ok(())
     }
 
     /// Delete values at the given position.
     #[inline]
     pub fn delete(&self, pos: usize, len: usize) -> PyLoroResult<()> {
-        self.0.delete(pos, len)?;
+        self.0
+            .delete(pos, len)
+            .context_at(&self.0, "LoroList.delete", pos)?;
         Ok(())
     }
 
     /// Get the value at the given position.
+    ///
+    /// With `resolve=True`, a child container is returned as its deep value, so the result is
+    /// a plain Python value.
+    ///
+    /// Returns `default` if the index is out of range. A stored null is returned as a value,
+    /// so the length of the list tells the two apart.
     #[inline]
-    pub fn get(&self, index: usize) -> Option<ValueOrContainer> {
-        self.0.get(index).map(ValueOrContainer::from)
+    #[pyo3(signature = (index, default=None, *, resolve=false))]
+    pub fn get<'py>(
+        &self,
+        py: Python<'py>,
+        index: usize,
+        default: Option<Bound<'py, PyAny>>,
+        resolve: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match self.0.get(index) {
+            Some(v) => value_or_deep_value(py, &self.0, v, resolve).map(Some),
+            None => Ok(default),
+        }
     }
 
     /// Get the deep value of the container.
     #[inline]
     pub fn get_deep_value(&self) -> LoroValue {
-        self.0.get_deep_value().into()
+        read_value(&self.0, self.0.get_deep_value())
+    }
+
+    /// The items of the list as a Python `list`, with child containers converted to
+    /// plain values recursively, the same as in `LoroDoc.get_deep_value`.
+    pub fn to_py(&self) -> LoroValue {
+        read_value(&self.0, self.0.get_deep_value())
+    }
+
+    /// A stable hash of the current state of the list, see `LoroDoc.state_hash`.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.0.get_deep_value())
     }
 
     /// Get the shallow value of the container.
@@ -71,7 +119,7 @@ impl LoroList {
     /// This does not convert the state of sub-containers; instead, it represents them as [LoroValue::Container].
     #[inline]
     pub fn get_value(&self) -> LoroValue {
-        self.0.get_value().into()
+        read_value(&self.0, self.0.get_value())
     }
 
     /// Get the ID of the container.
@@ -84,29 +132,34 @@ impl LoroList {
     /// Pop the last element of the list.
     #[inline]
     pub fn pop(&self) -> PyLoroResult<Option<LoroValue>> {
-        let ans = self.0.pop()?.map(LoroValue::from);
+        let ans = self.0.pop()?.map(|v| read_value(&self.0, v));
         Ok(ans)
     }
 
     /// Push a value to the list.
     #[inline]
     pub fn push(&self, v: LoroValue) -> PyLoroResult<()> {
-        self.0.push(&v)?;
+        self.0.push(&v).context(&self.0, "LoroList.push")?;
         Ok(())
     }
 
     /// Push a container to the list.
     #[inline]
     pub fn push_container(&self, child: Container) -> PyLoroResult<Container> {
-        let container = self.0.push_container(loro::Container::from(child))?;
+        let container = self
+            .0
+            .push_container(loro::Container::from(child))
+            .context(&self.0, "LoroList.push_container")?;
         Ok(container.into())
     }
 
     /// Iterate over the elements of the list.
     pub fn for_each(&self, f: Py<PyAny>) {
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
         Python::attach(|py| {
             self.0.for_each(&mut |v| {
-                f.call1(py, (ValueOrContainer::from(v),)).unwrap();
+                let v = ValueOrContainer::from(v).decoding_datetime(decode_datetime);
+                f.call1(py, (v,)).unwrap();
             });
         })
     }
@@ -121,24 +174,24 @@ impl LoroList {
                 let indices = slice.indices(self.0.len() as isize)?;
                 let mut i = indices.start;
                 let mut list: Vec<ValueOrContainer> = Vec::with_capacity(indices.slicelength);
+                let decode_datetime = DocHooks::datetime_decoding(&self.0);
 
                 for _ in 0..indices.slicelength {
-                    list.push(
-                        self.0
-                            .get(i as usize)
-                            .ok_or(PyIndexError::new_err("index out of range"))?
-                            .into(),
-                    );
+                    let value = self
+                        .0
+                        .get(i as usize)
+                        .ok_or(PyIndexError::new_err("index out of range"))?;
+                    list.push(ValueOrContainer::from(value).decoding_datetime(decode_datetime));
                     i += indices.step;
                 }
                 list.into_pyobject(py)
             }
             SliceOrInt::Int(idx) => {
-                let value: ValueOrContainer = self
+                let value = self
                     .0
                     .get(idx)
-                    .ok_or(PyIndexError::new_err("index out of range"))?
-                    .into();
+                    .ok_or(PyIndexError::new_err("index out of range"))?;
+                let value = ValueOrContainer::read_from(&self.0, value);
                 Ok(value.into_pyobject(py)?.into_any().into_bound())
             }
         }
@@ -247,7 +300,10 @@ impl LoroList {
     /// ```
     #[inline]
     pub fn insert_container(&self, pos: usize, child: Container) -> PyLoroResult<Container> {
-        let container = self.0.insert_container(pos, loro::Container::from(child))?;
+        let container = self
+            .0
+            .insert_container(pos, loro::Container::from(child))
+            .context_at(&self.0, "LoroList.insert_container", pos)?;
         Ok(container.into())
     }
 
@@ -311,7 +367,12 @@ impl LoroList {
     /// let vec = list.to_vec();
     /// ```
     pub fn to_vec(&self) -> Vec<LoroValue> {
-        self.0.to_vec().into_iter().map(LoroValue::from).collect()
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
+        self.0
+            .to_vec()
+            .into_iter()
+            .map(|v| LoroValue::from(v).decoding_datetime(decode_datetime))
+            .collect()
     }
 
     /// Delete all elements in the list.
@@ -325,6 +386,19 @@ impl LoroList {
         self.0.get_id_at(pos).map(ID::from)
     }
 
+    /// Iterate over the `(index, id)` pairs of the list items, e.g. to key UI rows by the
+    /// stable IDs of the elements.
+    ///
+    /// The IDs are read one at a time as the iterator advances, so stopping early skips the
+    /// rest. Edits made while iterating affect the items not reached yet, like for a Python
+    /// `list`.
+    pub fn iter_ids(&self) -> ListIdIter {
+        ListIdIter {
+            list: self.0.clone(),
+            pos: 0,
+        }
+    }
+
     pub fn doc(&self) -> Option<LoroDoc> {
         self.0.doc().map(|doc| doc.into())
     }
@@ -341,11 +415,32 @@ impl LoroList {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     pub fn subscribe(&self, callback: Py<PyAny>) -> Option<Subscription> {
+        let hooks = DocHooks::of(&self.0.doc()?);
         let subscription = self.0.subscribe(Arc::new(move |e| {
             Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
+                callback.call1(py, (hooks.event(e),)).unwrap();
             });
         }));
         subscription.map(|s| s.into())
     }
 }
+
+/// An iterator over the `(index, id)` pairs of a list, returned by `LoroList.iter_ids`.
+#[pyclass]
+pub struct ListIdIter {
+    list: LoroListInner,
+    pos: usize,
+}
+
+#[pymethods]
+impl ListIdIter {
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<(usize, ID)> {
+        let id = self.list.get_id_at(self.pos)?;
+        self.pos += 1;
+        Some((self.pos - 1, id.into()))
+    }
+}
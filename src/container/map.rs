@@ -4,13 +4,19 @@ use loro::{ContainerTrait, LoroMap as LoroMapInner, PeerID};
 use pyo3::{exceptions::PyKeyError, prelude::*, PyErr};
 
 use crate::{
+    convert::loro_value_repr,
     doc::LoroDoc,
-    err::PyLoroResult,
-    event::{DiffEvent, Subscription},
+    err::{ErrorContextExt, PyLoroResult},
+    event::Subscription,
+    hash, history,
+    hooks::DocHooks,
     value::{ContainerID, LoroValue, ValueOrContainer},
 };
 
-use super::Container;
+use super::{
+    utils::{read_value, value_or_deep_value},
+    Container,
+};
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<LoroMap>()?;
@@ -32,6 +38,18 @@ impl LoroMap {
         Self::default()
     }
 
+    pub fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "LoroMap({})",
+            loro_value_repr(py, self.0.get_deep_value())?
+        ))
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<LoroValue> {
+        vec![self.0.get_deep_value().into()]
+    }
+
     /// Whether the container is attached to a document.
     #[getter]
     pub fn is_attached(&self) -> bool {
@@ -40,23 +58,29 @@ impl LoroMap {
 
     /// Delete a key-value pair from the map.
     pub fn delete(&self, key: &str) -> PyLoroResult<()> {
-        self.0.delete(key)?;
+        self.0
+            .delete(key)
+            .context_at(&self.0, "LoroMap.delete", key)?;
         Ok(())
     }
 
     /// Iterate over the key-value pairs of the map.
     // TODO: why valueOrHandler?
     pub fn for_each(&self, f: Py<PyAny>) {
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
         Python::attach(|py| {
             self.0.for_each(move |key, value| {
-                f.call1(py, (key, ValueOrContainer::from(value))).unwrap();
+                let value = ValueOrContainer::from(value).decoding_datetime(decode_datetime);
+                f.call1(py, (key, value)).unwrap();
             })
         });
     }
 
     /// Insert a key-value pair into the map.
     pub fn insert(&self, key: &str, value: LoroValue) -> PyLoroResult<()> {
-        self.0.insert(key, value)?;
+        self.0
+            .insert(key, value)
+            .context_at(&self.0, "LoroMap.insert", key)?;
         Ok(())
     }
 
@@ -70,7 +94,9 @@ impl LoroMap {
     }
 
     pub fn __getitem__(&self, key: &str) -> PyResult<ValueOrContainer> {
-        self.get(key)
+        self.0
+            .get(key)
+            .map(|v| ValueOrContainer::read_from(&self.0, v))
             .ok_or_else(|| PyKeyError::new_err(format!("Key {key} not found")))
     }
 
@@ -87,9 +113,16 @@ impl LoroMap {
     }
 
     pub fn items(&self) -> Vec<(String, ValueOrContainer)> {
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
         self.0
             .keys()
-            .filter_map(|k| self.get(&k).map(|v| (k.to_string(), v)))
+            .filter_map(|k| {
+                let v = self.0.get(&k)?;
+                Some((
+                    k.to_string(),
+                    ValueOrContainer::from(v).decoding_datetime(decode_datetime),
+                ))
+            })
             .collect()
     }
 
@@ -105,8 +138,24 @@ impl LoroMap {
     }
 
     /// Get the value of the map with the given key.
-    pub fn get(&self, key: &str) -> Option<ValueOrContainer> {
-        self.0.get(key).map(|v| v.into())
+    ///
+    /// With `resolve=True`, a child container is returned as its deep value, so the result is
+    /// a plain Python value.
+    ///
+    /// Returns `default` if the key is missing, like `dict.get`. A stored null is returned
+    /// as a value, so `key in map` tells the two apart.
+    #[pyo3(signature = (key, default=None, *, resolve=false))]
+    pub fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: &str,
+        default: Option<Bound<'py, PyAny>>,
+        resolve: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match self.0.get(key) {
+            Some(v) => value_or_deep_value(py, &self.0, v, resolve).map(Some),
+            None => Ok(default),
+        }
     }
 
     /// Insert a container with the given type at the given key.
@@ -124,7 +173,10 @@ impl LoroMap {
     /// assert_eq!(doc.get_deep_value().to_json_value(), json!({"m": {"t": "012"}}));
     /// ```
     pub fn insert_container(&self, key: &str, child: Container) -> PyLoroResult<Container> {
-        let container = self.0.insert_container(key, loro::Container::from(child))?;
+        let container = self
+            .0
+            .insert_container(key, loro::Container::from(child))
+            .context_at(&self.0, "LoroMap.insert_container", key)?;
         Ok(container.into())
     }
 
@@ -132,21 +184,33 @@ impl LoroMap {
     ///
     /// It will not convert the state of sub-containers, but represent them as [LoroValue::Container].
     pub fn get_value(&self) -> LoroValue {
-        self.0.get_value().into()
+        read_value(&self.0, self.0.get_value())
     }
 
     /// Get the deep value of the map.
     ///
     /// It will convert the state of sub-containers into a nested JSON value.
     pub fn get_deep_value(&self) -> LoroValue {
-        self.0.get_deep_value().into()
+        read_value(&self.0, self.0.get_deep_value())
+    }
+
+    /// The entries of the map as a Python `dict`, with child containers converted to
+    /// plain values recursively, the same as in `LoroDoc.get_deep_value`.
+    pub fn to_py(&self) -> LoroValue {
+        read_value(&self.0, self.0.get_deep_value())
+    }
+
+    /// A stable hash of the current state of the map, see `LoroDoc.state_hash`.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.0.get_deep_value())
     }
 
     /// Get or create a container with the given key.
     pub fn get_or_create_container(&self, key: &str, child: Container) -> PyLoroResult<Container> {
         let container = self
             .0
-            .get_or_create_container(key, loro::Container::from(child))?;
+            .get_or_create_container(key, loro::Container::from(child))
+            .context_at(&self.0, "LoroMap.get_or_create_container", key)?;
         Ok(container.into())
     }
 
@@ -164,7 +228,11 @@ impl LoroMap {
 
     /// Get the values of the map.
     pub fn values(&self) -> Vec<ValueOrContainer> {
-        self.0.values().map(ValueOrContainer::from).collect()
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
+        self.0
+            .values()
+            .map(|v| ValueOrContainer::from(v).decoding_datetime(decode_datetime))
+            .collect()
     }
 
     /// Get the peer id of the last editor on the given entry
@@ -172,6 +240,45 @@ impl LoroMap {
         self.0.get_last_editor(key)
     }
 
+    /// The values written concurrently to the key, with the peers that wrote them.
+    ///
+    /// The map keeps the last write, but peers may have set the key at the same time without
+    /// seeing each other's value. This returns all of them, the current value first, so the
+    /// application can tell "someone else also set this". A deletion has the value `None`.
+    ///
+    /// It reads the history up to the current state, so a document checked out at an older
+    /// version gets the writes of that version. Every change is visited, but only the ones
+    /// that touch the map are decoded, so the cost grows with the size of the history.
+    /// Returns an empty list if the map is detached or the key was never written.
+    pub fn get_all(&self, key: &str) -> Vec<(PeerID, Option<LoroValue>)> {
+        let Some(doc) = self.0.doc() else {
+            return Vec::new();
+        };
+        history::concurrent_map_values(&doc, &self.0.id(), key)
+            .into_iter()
+            .map(|(id, _, value)| (id.peer, value.map(|v| read_value(&self.0, v))))
+            .collect()
+    }
+
+    /// The op id, peer and lamport timestamp of the write that set the current value of the
+    /// key, e.g. to explain which of the concurrent writes returned by `get_all` won.
+    ///
+    /// It reads the history up to the current state like `get_all`, so a document checked out
+    /// at an older version gets the write of the value it shows. Returns `None` if the map is
+    /// detached, the key is unset or deleted, or the write was trimmed by a shallow snapshot.
+    pub fn get_entry_meta(&self, key: &str) -> Option<history::MapEntryMeta> {
+        let doc = self.0.doc()?;
+        let (id, lamport, value) = history::concurrent_map_values(&doc, &self.0.id(), key)
+            .into_iter()
+            .next()?;
+        value?;
+        Some(history::MapEntryMeta {
+            id: id.into(),
+            peer: id.peer,
+            lamport,
+        })
+    }
+
     pub fn doc(&self) -> Option<LoroDoc> {
         self.0.doc().map(|doc| doc.into())
     }
@@ -188,9 +295,10 @@ impl LoroMap {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     pub fn subscribe(&self, callback: Py<PyAny>) -> Option<Subscription> {
+        let hooks = DocHooks::of(&self.0.doc()?);
         let subscription = self.0.subscribe(Arc::new(move |e| {
             Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
+                callback.call1(py, (hooks.event(e),)).unwrap();
             });
         }));
         subscription.map(|s| s.into())
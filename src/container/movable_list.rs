@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
 use crate::{
-    container::utils::{py_any_to_loro_values, slice_indices_positions, SliceOrInt},
+    container::utils::{
+        py_any_to_loro_values, read_value, slice_indices_positions, value_or_deep_value, SliceOrInt,
+    },
+    convert::loro_value_repr,
     doc::LoroDoc,
-    err::{PyLoroError, PyLoroResult},
-    event::{DiffEvent, Subscription},
+    err::{ErrorContextExt, PyLoroError, PyLoroResult},
+    event::Subscription,
+    hash,
+    hooks::DocHooks,
     value::{ContainerID, LoroValue, ValueOrContainer},
 };
 use loro::{ContainerTrait, LoroMovableList as LoroMovableListInner, PeerID};
@@ -31,6 +36,18 @@ impl LoroMovableList {
         Self::default()
     }
 
+    pub fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "LoroMovableList({})",
+            loro_value_repr(py, self.0.get_deep_value())?
+        ))
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<LoroValue> {
+        vec![self.0.get_deep_value().into()]
+    }
+
     /// Get the container id.
     #[getter]
     pub fn id(&self) -> ContainerID {
@@ -48,19 +65,39 @@ impl LoroMovableList {
 
     /// Insert a value at the given position.
     pub fn insert(&self, pos: usize, v: LoroValue) -> PyLoroResult<()> {
-        self.0.insert(pos, &v)?;
+        self.0
+            .insert(pos, &v)
+            .context_at(&self.0, "LoroMovableList.insert", pos)?;
         Ok(())
     }
 
     /// Delete the value at the given position.
     pub fn delete(&self, pos: usize, len: usize) -> PyLoroResult<()> {
-        self.0.delete(pos, len)?;
+        self.0
+            .delete(pos, len)
+            .context_at(&self.0, "LoroMovableList.delete", pos)?;
         Ok(())
     }
 
     /// Get the value at the given position.
-    pub fn get(&self, index: usize) -> Option<ValueOrContainer> {
-        self.0.get(index).map(ValueOrContainer::from)
+    ///
+    /// With `resolve=True`, a child container is returned as its deep value, so the result is
+    /// a plain Python value.
+    ///
+    /// Returns `default` if the index is out of range. A stored null is returned as a value,
+    /// so the length of the list tells the two apart.
+    #[pyo3(signature = (index, default=None, *, resolve=false))]
+    pub fn get<'py>(
+        &self,
+        py: Python<'py>,
+        index: usize,
+        default: Option<Bound<'py, PyAny>>,
+        resolve: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match self.0.get(index) {
+            Some(v) => value_or_deep_value(py, &self.0, v, resolve).map(Some),
+            None => Ok(default),
+        }
     }
 
     /// Get the length of the list.
@@ -78,24 +115,24 @@ impl LoroMovableList {
                 let indices = slice.indices(self.0.len() as isize)?;
                 let mut i = indices.start;
                 let mut list: Vec<ValueOrContainer> = Vec::with_capacity(indices.slicelength);
+                let decode_datetime = DocHooks::datetime_decoding(&self.0);
 
                 for _ in 0..indices.slicelength {
-                    list.push(
-                        self.0
-                            .get(i as usize)
-                            .ok_or(PyIndexError::new_err("index out of range"))?
-                            .into(),
-                    );
+                    let value = self
+                        .0
+                        .get(i as usize)
+                        .ok_or(PyIndexError::new_err("index out of range"))?;
+                    list.push(ValueOrContainer::from(value).decoding_datetime(decode_datetime));
                     i += indices.step;
                 }
                 list.into_pyobject(py)
             }
             SliceOrInt::Int(idx) => {
-                let value: ValueOrContainer = self
+                let value = self
                     .0
                     .get(idx)
-                    .ok_or(PyIndexError::new_err("index out of range"))?
-                    .into();
+                    .ok_or(PyIndexError::new_err("index out of range"))?;
+                let value = ValueOrContainer::read_from(&self.0, value);
                 Ok(value.into_pyobject(py)?.into_any().into_bound())
             }
         }
@@ -184,55 +221,82 @@ impl LoroMovableList {
     ///
     /// It will not convert the state of sub-containers, but represent them as [LoroValue::Container].
     pub fn get_value(&self) -> LoroValue {
-        self.0.get_value().into()
+        read_value(&self.0, self.0.get_value())
     }
 
     /// Get the deep value of the list.
     ///
     /// It will convert the state of sub-containers into a nested JSON value.
     pub fn get_deep_value(&self) -> LoroValue {
-        self.0.get_deep_value().into()
+        read_value(&self.0, self.0.get_deep_value())
+    }
+
+    /// The items of the list as a Python `list`, with child containers converted to
+    /// plain values recursively, the same as in `LoroDoc.get_deep_value`.
+    pub fn to_py(&self) -> LoroValue {
+        read_value(&self.0, self.0.get_deep_value())
+    }
+
+    /// A stable hash of the current state of the list, see `LoroDoc.state_hash`.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.0.get_deep_value())
     }
 
     /// Pop the last element of the list.
     pub fn pop(&self) -> PyLoroResult<Option<ValueOrContainer>> {
-        let ans = self.0.pop()?.map(ValueOrContainer::from);
+        let ans = self
+            .0
+            .pop()?
+            .map(|v| ValueOrContainer::read_from(&self.0, v));
         Ok(ans)
     }
 
     /// Push a value to the end of the list.
     pub fn push(&self, v: LoroValue) -> PyLoroResult<()> {
-        self.0.push(&v)?;
+        self.0.push(&v).context(&self.0, "LoroMovableList.push")?;
         Ok(())
     }
 
     /// Push a container to the end of the list.
     pub fn push_container(&self, child: Container) -> PyLoroResult<Container> {
-        let container = self.0.push_container(loro::Container::from(child))?;
+        let container = self
+            .0
+            .push_container(loro::Container::from(child))
+            .context(&self.0, "LoroMovableList.push_container")?;
         Ok(container.into())
     }
 
     /// Set the value at the given position.
     pub fn set(&self, pos: usize, value: LoroValue) -> PyLoroResult<()> {
-        self.0.set(pos, &value)?;
+        self.0
+            .set(pos, &value)
+            .context_at(&self.0, "LoroMovableList.set", pos)?;
         Ok(())
     }
 
     /// Move the value at the given position to the given position.
     pub fn mov(&self, from_: usize, to: usize) -> PyLoroResult<()> {
-        self.0.mov(from_, to)?;
+        self.0
+            .mov(from_, to)
+            .context_at(&self.0, "LoroMovableList.mov", from_)?;
         Ok(())
     }
 
     /// Insert a container at the given position.
     pub fn insert_container(&self, pos: usize, child: Container) -> PyLoroResult<Container> {
-        let container = self.0.insert_container(pos, loro::Container::from(child))?;
+        let container = self
+            .0
+            .insert_container(pos, loro::Container::from(child))
+            .context_at(&self.0, "LoroMovableList.insert_container", pos)?;
         Ok(container.into())
     }
 
     /// Set the container at the given position.
     pub fn set_container(&self, pos: usize, child: Container) -> PyLoroResult<Container> {
-        let container = self.0.set_container(pos, loro::Container::from(child))?;
+        let container = self
+            .0
+            .set_container(pos, loro::Container::from(child))
+            .context_at(&self.0, "LoroMovableList.set_container", pos)?;
         Ok(container.into())
     }
 
@@ -301,7 +365,12 @@ impl LoroMovableList {
     /// assert_eq!(vec[2], true.into());
     /// ```
     pub fn to_vec(&self) -> Vec<LoroValue> {
-        self.0.to_vec().into_iter().map(LoroValue::from).collect()
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
+        self.0
+            .to_vec()
+            .into_iter()
+            .map(|v| LoroValue::from(v).decoding_datetime(decode_datetime))
+            .collect()
     }
 
     /// Delete all elements in the list.
@@ -312,9 +381,11 @@ impl LoroMovableList {
 
     /// Iterate over the elements of the list.
     pub fn for_each(&self, f: Py<PyAny>) {
+        let decode_datetime = DocHooks::datetime_decoding(&self.0);
         Python::attach(|py| {
             self.0.for_each(&mut |v| {
-                f.call1(py, (ValueOrContainer::from(v),)).unwrap();
+                let v = ValueOrContainer::from(v).decoding_datetime(decode_datetime);
+                f.call1(py, (v,)).unwrap();
             });
         })
     }
@@ -350,9 +421,10 @@ impl LoroMovableList {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     pub fn subscribe(&self, callback: Py<PyAny>) -> Option<Subscription> {
+        let hooks = DocHooks::of(&self.0.doc()?);
         let subscription = self.0.subscribe(Arc::new(move |e| {
             Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
+                callback.call1(py, (hooks.event(e),)).unwrap();
             });
         }));
         subscription.map(|s| s.into())
@@ -2,16 +2,20 @@ use loro::{cursor::PosType, ContainerTrait, LoroText as LoroTextInner, PeerID};
 use pyo3::{
     exceptions::{PyIndexError, PyTypeError, PyValueError},
     prelude::*,
-    types::{PyBytes, PySlice, PyString},
+    types::{PyBytes, PySlice, PyString, PyType},
     Bound, PyErr, PyRef,
 };
 use std::{fmt::Display, sync::Arc};
 
 use crate::{
+    display,
     doc::LoroDoc,
-    err::{PyLoroError, PyLoroResult},
-    event::{DiffEvent, Subscription, TextDelta},
+    err::{ErrorContextExt, PyLoroError, PyLoroResult},
+    event::{Subscription, TextDelta},
+    hash, history,
+    hooks::DocHooks,
     value::{ContainerID, LoroValue, ID},
+    version::Frontiers,
 };
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -54,6 +58,26 @@ impl LoroText {
         format!("LoroText({:?})", self.0.to_string())
     }
 
+    /// Render the rich text as HTML for Jupyter.
+    pub fn _repr_html_(&self) -> String {
+        display::text_html(&self.0)
+    }
+
+    /// Render the rich text as Markdown for Jupyter.
+    pub fn _repr_markdown_(&self) -> String {
+        display::text_markdown(&self.0)
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+
+    /// Render the rich text as a `rich.text.Text` with its styles applied.
+    pub fn __rich__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        display::text_rich(py, &self.0)
+    }
+
     /// Get the [ContainerID]  of the text container.
     #[getter]
     pub fn id(&self) -> ContainerID {
@@ -73,37 +97,49 @@ impl LoroText {
 
     /// Insert a string at the given unicode position.
     pub fn insert(&self, pos: usize, s: &str) -> PyLoroResult<()> {
-        self.0.insert(pos, s)?;
+        self.0
+            .insert(pos, s)
+            .context_at(&self.0, "LoroText.insert", pos)?;
         Ok(())
     }
 
     /// Insert a string at the given utf-8 position.
     pub fn insert_utf8(&self, pos: usize, s: &str) -> PyLoroResult<()> {
-        self.0.insert_utf8(pos, s)?;
+        self.0
+            .insert_utf8(pos, s)
+            .context_at(&self.0, "LoroText.insert_utf8", pos)?;
         Ok(())
     }
 
     /// Insert a string at the given utf-16 position.
     pub fn insert_utf16(&self, pos: usize, s: &str) -> PyLoroResult<()> {
-        self.0.insert_utf16(pos, s)?;
+        self.0
+            .insert_utf16(pos, s)
+            .context_at(&self.0, "LoroText.insert_utf16", pos)?;
         Ok(())
     }
 
     /// Delete a range of text at the given unicode position with unicode length.
     pub fn delete(&self, pos: usize, len: usize) -> PyLoroResult<()> {
-        self.0.delete(pos, len)?;
+        self.0
+            .delete(pos, len)
+            .context_at(&self.0, "LoroText.delete", pos)?;
         Ok(())
     }
 
     /// Delete a range of text at the given utf-8 position with utf-8 length.
     pub fn delete_utf8(&self, pos: usize, len: usize) -> PyLoroResult<()> {
-        self.0.delete_utf8(pos, len)?;
+        self.0
+            .delete_utf8(pos, len)
+            .context_at(&self.0, "LoroText.delete_utf8", pos)?;
         Ok(())
     }
 
     /// Delete a range of text at the given utf-16 position with utf-16 length.
     pub fn delete_utf16(&self, pos: usize, len: usize) -> PyLoroResult<()> {
-        self.0.delete_utf16(pos, len)?;
+        self.0
+            .delete_utf16(pos, len)
+            .context_at(&self.0, "LoroText.delete_utf16", pos)?;
         Ok(())
     }
 
@@ -140,13 +176,18 @@ impl LoroText {
 
     /// Delete specified character and insert string at the same position at given unicode position.
     pub fn splice(&self, pos: usize, len: usize, s: &str) -> PyLoroResult<String> {
-        let s = self.0.splice(pos, len, s)?;
+        let s = self
+            .0
+            .splice(pos, len, s)
+            .context_at(&self.0, "LoroText.splice", pos)?;
         Ok(s)
     }
 
     /// Delete specified range and insert a string at the same UTF-16 position.
     pub fn splice_utf16(&self, pos: usize, len: usize, s: &str) -> PyLoroResult<()> {
-        self.0.splice_utf16(pos, len, s)?;
+        self.0
+            .splice_utf16(pos, len, s)
+            .context_at(&self.0, "LoroText.splice_utf16", pos)?;
         Ok(())
     }
 
@@ -250,7 +291,7 @@ impl LoroText {
     /// It uses Myers' diff algorithm to compute the optimal difference.
     ///
     /// This could take a long time for large texts (e.g. > 50_000 characters).
-    /// In that case, you should use `updateByLine` instead.
+    /// In that case, you should use `update_by_line` instead.
     ///
     /// # Example
     /// ```rust
@@ -306,7 +347,8 @@ impl LoroText {
     /// Apply a [delta](https://quilljs.com/docs/delta/) to the text container.
     pub fn apply_delta(&self, delta: Vec<TextDelta>) -> PyLoroResult<()> {
         self.0
-            .apply_delta(&delta.iter().map(|x| x.into()).collect::<Vec<_>>())?;
+            .apply_delta(&delta.iter().map(|x| x.into()).collect::<Vec<_>>())
+            .context(&self.0, "LoroText.apply_delta")?;
         Ok(())
     }
 
@@ -325,7 +367,9 @@ impl LoroText {
     ///
     /// Note: this is not suitable for unmergeable annotations like comments.
     pub fn mark(&self, start: usize, end: usize, key: &str, value: LoroValue) -> PyLoroResult<()> {
-        self.0.mark(start..end, key, value)?;
+        self.0
+            .mark(start..end, key, value)
+            .context_at(&self.0, "LoroText.mark", start)?;
         Ok(())
     }
 
@@ -336,7 +380,11 @@ impl LoroText {
         key: &str,
         value: LoroValue,
     ) -> PyLoroResult<()> {
-        self.0.mark_utf8(start..end, key, value)?;
+        self.0.mark_utf8(start..end, key, value).context_at(
+            &self.0,
+            "LoroText.mark_utf8",
+            start,
+        )?;
         Ok(())
     }
 
@@ -347,7 +395,11 @@ impl LoroText {
         key: &str,
         value: LoroValue,
     ) -> PyLoroResult<()> {
-        self.0.mark_utf16(start..end, key, value)?;
+        self.0.mark_utf16(start..end, key, value).context_at(
+            &self.0,
+            "LoroText.mark_utf16",
+            start,
+        )?;
         Ok(())
     }
 
@@ -368,12 +420,16 @@ impl LoroText {
     ///
     /// Note: you cannot delete unmergeable annotations like comments by this method.
     pub fn unmark(&self, start: usize, end: usize, key: &str) -> PyLoroResult<()> {
-        self.0.unmark(start..end, key)?;
+        self.0
+            .unmark(start..end, key)
+            .context_at(&self.0, "LoroText.unmark", start)?;
         Ok(())
     }
 
     pub fn unmark_utf16(&self, start: usize, end: usize, key: &str) -> PyLoroResult<()> {
-        self.0.unmark_utf16(start..end, key)?;
+        self.0
+            .unmark_utf16(start..end, key)
+            .context_at(&self.0, "LoroText.unmark_utf16", start)?;
         Ok(())
     }
 
@@ -408,6 +464,11 @@ impl LoroText {
         self.0.get_richtext_value().into()
     }
 
+    /// A stable hash of the current state of the text, with its styles, see `LoroDoc.state_hash`.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.0.get_richtext_value())
+    }
+
     /// Get the text in [Delta](https://quilljs.com/docs/delta/) format.
     pub fn to_delta(&self) -> Vec<TextDelta> {
         self.0.to_delta().iter().map(|x| x.into()).collect()
@@ -419,6 +480,36 @@ impl LoroText {
         self.0.to_string()
     }
 
+    /// The content of the text as a `str`, the same as `to_string`. Rich text styles
+    /// are dropped, see `to_delta` to keep them.
+    pub fn to_py(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Get the text content at the given version of the document.
+    ///
+    /// The text is read from a fork of the document, so it's O(n) like `LoroDoc.fork_at`, and
+    /// the document is left at its current version. Raises if `frontiers` isn't a version of
+    /// the document.
+    pub fn to_string_at(&self, frontiers: &Frontiers) -> PyLoroResult<String> {
+        Ok(self.at(frontiers)?.to_string())
+    }
+
+    /// Get the text in [Delta](https://quilljs.com/docs/delta/) format at the given version
+    /// of the document.
+    ///
+    /// The text is read from a fork of the document, so it's O(n) like `LoroDoc.fork_at`, and
+    /// the document is left at its current version. Raises if `frontiers` isn't a version of
+    /// the document.
+    pub fn to_delta_at(&self, frontiers: &Frontiers) -> PyLoroResult<Vec<TextDelta>> {
+        Ok(self
+            .at(frontiers)?
+            .to_delta()
+            .iter()
+            .map(|x| x.into())
+            .collect())
+    }
+
     /// Get the cursor at the given position in the given Unicode position.
     ///
     /// Using "index" to denote cursor positions can be unstable, as positions may
@@ -460,7 +551,7 @@ impl LoroText {
 
     /// Push a string to the end of the text container.
     pub fn push_str(&self, s: &str) -> PyLoroResult<()> {
-        self.0.push_str(s)?;
+        self.0.push_str(s).context(&self.0, "LoroText.push_str")?;
         Ok(())
     }
 
@@ -485,9 +576,10 @@ impl LoroText {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     pub fn subscribe(&self, callback: Py<PyAny>) -> Option<Subscription> {
+        let hooks = DocHooks::of(&self.0.doc()?);
         let subscription = self.0.subscribe(Arc::new(move |e| {
             Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
+                callback.call1(py, (hooks.event(e),)).unwrap();
             });
         }));
         subscription.map(|s| s.into())
@@ -504,6 +596,16 @@ impl LoroText {
     }
 }
 
+impl LoroText {
+    /// This text in a fork of its document at `frontiers`.
+    fn at(&self, frontiers: &Frontiers) -> PyLoroResult<LoroTextInner> {
+        let doc = self.0.doc().ok_or_else(|| {
+            PyLoroError::Error("the text isn't attached to a document".to_string())
+        })?;
+        Ok(history::fork_at(&doc, &frontiers.into())?.get_text(self.0.id()))
+    }
+}
+
 #[pyclass(eq, eq_int)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Side {
@@ -534,7 +636,7 @@ impl From<PositionType> for PosType {
     }
 }
 
-#[pyclass(str, frozen)]
+#[pyclass(eq, hash, str, frozen, module = "loro")]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Cursor(pub loro::cursor::Cursor);
 
@@ -550,6 +652,17 @@ impl Display for Cursor {
 
 #[pymethods]
 impl Cursor {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Cursor(id={}, container={}, side=Side.{:?})",
+            self.0
+                .id
+                .map_or("None".to_string(), |id| ID::from(id).__repr__()),
+            ContainerID::from(&self.0.container).__repr__(),
+            Side::from(self.0.side),
+        )
+    }
+
     #[getter]
     pub fn id(&self) -> Option<ID> {
         self.0.id.map(|x| x.into())
@@ -572,8 +685,23 @@ impl Cursor {
         self.0.encode()
     }
 
-    #[staticmethod]
-    pub fn decode(bytes: Bound<'_, PyBytes>) -> PyLoroResult<Self> {
+    pub fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.encode())
+    }
+
+    /// Pickle the cursor as its encoded bytes, e.g. to keep it in a session store.
+    pub fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        Ok((
+            py.get_type::<Self>().getattr("decode")?,
+            (self.__bytes__(py),),
+        ))
+    }
+
+    #[classmethod]
+    pub fn decode(_cls: &Bound<'_, PyType>, bytes: Bound<'_, PyBytes>) -> PyLoroResult<Self> {
         let cursor = loro::cursor::Cursor::decode(bytes.as_bytes())
             .map_err(|e| PyLoroError::Error(e.to_string()))?;
         Ok(Self(cursor))
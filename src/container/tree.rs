@@ -4,14 +4,21 @@ use loro::{ContainerTrait, LoroTree as LoroTreeInner};
 use pyo3::prelude::*;
 
 use crate::{
-    convert::tree_parent_id_to_option_tree_id,
+    convert::{
+        tree_id_from_object, tree_parent_from_object, tree_parent_id_from_object,
+        tree_parent_id_to_option_tree_id,
+    },
+    display,
     doc::LoroDoc,
-    err::PyLoroResult,
-    event::{DiffEvent, Subscription},
+    err::{ErrorContextExt, PyLoroError, PyLoroResult},
+    event::Subscription,
+    hash, history,
+    hooks::DocHooks,
     value::{ContainerID, LoroValue, TreeID, TreeParentId, ID},
+    version::Frontiers,
 };
 
-use super::LoroMap;
+use super::{utils::read_value, LoroMap};
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<LoroTree>()?;
@@ -34,6 +41,30 @@ impl LoroTree {
         Self::default()
     }
 
+    pub fn __repr__(&self) -> String {
+        format!("LoroTree(nodes={})", self.0.get_nodes(false).len())
+    }
+
+    /// Render the tree as collapsible HTML lists for Jupyter.
+    pub fn _repr_html_(&self) -> String {
+        display::tree_html(&self.0)
+    }
+
+    /// Render the tree as a nested Markdown list for Jupyter.
+    pub fn _repr_markdown_(&self) -> String {
+        display::tree_markdown(&self.0)
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<(&'static str, usize)> {
+        vec![("nodes", self.0.get_nodes(false).len())]
+    }
+
+    /// Render the tree as a `rich.tree.Tree`.
+    pub fn __rich__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        display::tree_rich(py, &self.0)
+    }
+
     /// Whether the container is attached to a document
     ///
     /// The edits on a detached container will not be persisted.
@@ -43,13 +74,13 @@ impl LoroTree {
         self.0.is_attached()
     }
 
-    pub fn __contains__(&self, target: TreeID) -> bool {
+    pub fn __contains__(&self, #[pyo3(from_py_with = tree_id_from_object)] target: TreeID) -> bool {
         self.contains(target)
     }
 
     /// Create a new tree node and return the [`TreeID`].
     ///
-    /// If the `parent` is `None`, the created node is the root of a tree.
+    /// If the `parent` is `None` or `"root"`, the created node is the root of a tree.
     /// Otherwise, the created node is a child of the parent tree node.
     ///
     /// # Example
@@ -65,8 +96,15 @@ impl LoroTree {
     /// let child = tree.create(root).unwrap();
     /// ```
     #[pyo3(signature = (parent=None))]
-    pub fn create(&self, parent: Option<TreeID>) -> PyLoroResult<TreeID> {
-        let ans = self.0.create(parent.map(loro::TreeID::from))?.into();
+    pub fn create(
+        &self,
+        #[pyo3(from_py_with = tree_parent_from_object)] parent: Option<TreeID>,
+    ) -> PyLoroResult<TreeID> {
+        let ans = self
+            .0
+            .create(parent.map(loro::TreeID::from))
+            .context(&self.0, "LoroTree.create")?
+            .into();
         Ok(ans)
     }
 
@@ -78,7 +116,7 @@ impl LoroTree {
 
     /// Create a new tree node at the given index and return the [`TreeID`].
     ///
-    /// If the `parent` is `None`, the created node is the root of a tree.
+    /// If the `parent` is `None` or `"root"`, the created node is the root of a tree.
     /// If the `index` is greater than the number of children of the parent, error will be returned.
     ///
     /// # Example
@@ -96,17 +134,22 @@ impl LoroTree {
     /// let child = tree.create_at(root, 0).unwrap();
     /// ```
     #[pyo3(signature = (index, parent=None))]
-    pub fn create_at(&self, index: usize, parent: Option<TreeID>) -> PyLoroResult<TreeID> {
+    pub fn create_at(
+        &self,
+        index: usize,
+        #[pyo3(from_py_with = tree_parent_from_object)] parent: Option<TreeID>,
+    ) -> PyLoroResult<TreeID> {
         let ans = self
             .0
-            .create_at(parent.map(loro::TreeID::from), index)?
+            .create_at(parent.map(loro::TreeID::from), index)
+            .context_at(&self.0, "LoroTree.create_at", index)?
             .into();
         Ok(ans)
     }
 
     /// Move the `target` node to be a child of the `parent` node.
     ///
-    /// If the `parent` is `None`, the `target` node will be a root.
+    /// If the `parent` is `None` or `"root"`, the `target` node will be a root.
     ///
     /// # Example
     ///
@@ -121,13 +164,19 @@ impl LoroTree {
     /// tree.mov(root2, root).unwrap();
     /// ```
     #[pyo3(signature = (target,parent=None))]
-    pub fn mov(&self, target: TreeID, parent: Option<TreeID>) -> PyLoroResult<()> {
-        self.0.mov(target.into(), parent.map(loro::TreeID::from))?;
+    pub fn mov(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+        #[pyo3(from_py_with = tree_parent_from_object)] parent: Option<TreeID>,
+    ) -> PyLoroResult<()> {
+        self.0
+            .mov(target.into(), parent.map(loro::TreeID::from))
+            .context_at(&self.0, "LoroTree.mov", target)?;
         Ok(())
     }
 
     /// Move the `target` node to be a child of the `parent` node at the given index.
-    /// If the `parent` is `None`, the `target` node will be a root.
+    /// If the `parent` is `None` or `"root"`, the `target` node will be a root.
     ///
     /// # Example
     ///
@@ -144,9 +193,15 @@ impl LoroTree {
     /// tree.mov_to(root2, 0, root).unwrap();
     /// ```
     #[pyo3(signature = (target, to, parent=None))]
-    pub fn mov_to(&self, target: TreeID, to: usize, parent: Option<TreeID>) -> PyLoroResult<()> {
+    pub fn mov_to(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+        to: usize,
+        #[pyo3(from_py_with = tree_parent_from_object)] parent: Option<TreeID>,
+    ) -> PyLoroResult<()> {
         self.0
-            .mov_to(target.into(), parent.map(loro::TreeID::from), to)?;
+            .mov_to(target.into(), parent.map(loro::TreeID::from), to)
+            .context_at(&self.0, "LoroTree.mov_to", target)?;
         Ok(())
     }
 
@@ -166,8 +221,16 @@ impl LoroTree {
     /// // move `root` to be a child after `root2`.
     /// tree.mov_after(root, root2).unwrap();
     /// ```
-    pub fn mov_after(&self, target: TreeID, after: TreeID) -> PyLoroResult<()> {
-        self.0.mov_after(target.into(), after.into())?;
+    pub fn mov_after(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+        #[pyo3(from_py_with = tree_id_from_object)] after: TreeID,
+    ) -> PyLoroResult<()> {
+        self.0.mov_after(target.into(), after.into()).context_at(
+            &self.0,
+            "LoroTree.mov_after",
+            target,
+        )?;
         Ok(())
     }
 
@@ -187,8 +250,16 @@ impl LoroTree {
     /// // move `root` to be a child before `root2`.
     /// tree.mov_before(root, root2).unwrap();
     /// ```
-    pub fn mov_before(&self, target: TreeID, before: TreeID) -> PyLoroResult<()> {
-        self.0.mov_before(target.into(), before.into())?;
+    pub fn mov_before(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+        #[pyo3(from_py_with = tree_id_from_object)] before: TreeID,
+    ) -> PyLoroResult<()> {
+        self.0.mov_before(target.into(), before.into()).context_at(
+            &self.0,
+            "LoroTree.mov_before",
+            target,
+        )?;
         Ok(())
     }
 
@@ -207,8 +278,13 @@ impl LoroTree {
     /// let root = tree.create(None).unwrap();
     /// tree.delete(root).unwrap();
     /// ```
-    pub fn delete(&self, target: TreeID) -> PyLoroResult<()> {
-        self.0.delete(target.into())?;
+    pub fn delete(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+    ) -> PyLoroResult<()> {
+        self.0
+            .delete(target.into())
+            .context_at(&self.0, "LoroTree.delete", target)?;
         Ok(())
     }
 
@@ -224,8 +300,15 @@ impl LoroTree {
     /// let root_meta = tree.get_meta(root).unwrap();
     /// root_meta.insert("color", "red");
     /// ```
-    pub fn get_meta(&self, target: TreeID) -> PyLoroResult<LoroMap> {
-        let ans = self.0.get_meta(target.into()).map(LoroMap)?;
+    pub fn get_meta(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+    ) -> PyLoroResult<LoroMap> {
+        let ans = self.0.get_meta(target.into()).map(LoroMap).context_at(
+            &self.0,
+            "LoroTree.get_meta",
+            target,
+        )?;
         Ok(ans)
     }
 
@@ -233,14 +316,17 @@ impl LoroTree {
     ///
     /// - If the target node does not exist, return `None`.
     /// - If the target node is a root node, return `Some(None)`.
-    pub fn parent(&self, target: TreeID) -> Option<Option<TreeID>> {
+    pub fn parent(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+    ) -> Option<Option<TreeID>> {
         self.0
             .parent(target.into())
             .map(tree_parent_id_to_option_tree_id)
     }
 
     /// Return whether target node exists. including deleted node.
-    pub fn contains(&self, target: TreeID) -> bool {
+    pub fn contains(&self, #[pyo3(from_py_with = tree_id_from_object)] target: TreeID) -> bool {
         self.0.contains(target.into())
     }
 
@@ -249,8 +335,11 @@ impl LoroTree {
     /// # Errors
     ///
     /// - If the target node does not exist, return `LoroTreeError::TreeNodeNotExist`.
-    pub fn is_node_deleted(&self, target: &TreeID) -> PyLoroResult<bool> {
-        let ans = self.0.is_node_deleted(&(*target).into())?;
+    pub fn is_node_deleted(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+    ) -> PyLoroResult<bool> {
+        let ans = self.0.is_node_deleted(&target.into())?;
         Ok(ans)
     }
 
@@ -268,20 +357,58 @@ impl LoroTree {
             .collect()
     }
 
+    /// Return all nodes at the given version of the document, each with the deep value of
+    /// its metadata map. If `with_deleted` is true, the deleted nodes will be included.
+    ///
+    /// The document is left at its current version, see `LoroDoc.get_value_at`. Raises if
+    /// `frontiers` isn't a version of the document.
+    #[pyo3(signature = (frontiers, with_deleted=false))]
+    pub fn get_nodes_at(
+        &self,
+        frontiers: &Frontiers,
+        with_deleted: bool,
+    ) -> PyLoroResult<Vec<(TreeNode, LoroValue)>> {
+        let doc = self.0.doc().ok_or_else(|| {
+            PyLoroError::Error("the tree isn't attached to a document".to_string())
+        })?;
+        let tree = history::fork_at(&doc, &frontiers.into())?.get_tree(self.0.id());
+        Ok(tree
+            .get_nodes(with_deleted)
+            .into_iter()
+            .map(|node| {
+                let meta = tree
+                    .get_meta(node.id)
+                    .map(|meta| meta.get_deep_value())
+                    .unwrap_or_else(|_| loro::LoroValue::Map(Default::default()));
+                (node.into(), meta.into())
+            })
+            .collect())
+    }
+
     /// Return all children of the target node.
     ///
-    /// If the parent node does not exist, return `None`.
-    #[pyo3(signature = (parent=None))]
-    pub fn children(&self, parent: Option<TreeID>) -> Option<Vec<TreeID>> {
+    /// `parent` is a node, `None` or `"root"` for the roots, or `"deleted"` for the roots
+    /// of the deleted subtrees. If the parent node does not exist, return `None`.
+    #[pyo3(signature = (parent=loro::TreeParentId::Root))]
+    pub fn children(
+        &self,
+        #[pyo3(from_py_with = tree_parent_id_from_object)] parent: loro::TreeParentId,
+    ) -> Option<Vec<TreeID>> {
         self.0
-            .children(parent.map(loro::TreeID::from))
+            .children(parent)
             .map(|x| x.into_iter().map(|x| x.into()).collect())
     }
 
     /// Return the number of children of the target node.
-    #[pyo3(signature = (parent=None))]
-    pub fn children_num(&self, parent: Option<TreeID>) -> Option<usize> {
-        self.0.children_num(parent.map(loro::TreeID::from))
+    ///
+    /// `parent` is a node, `None` or `"root"` for the roots, or `"deleted"` for the roots
+    /// of the deleted subtrees.
+    #[pyo3(signature = (parent=loro::TreeParentId::Root))]
+    pub fn children_num(
+        &self,
+        #[pyo3(from_py_with = tree_parent_id_from_object)] parent: loro::TreeParentId,
+    ) -> Option<usize> {
+        self.0.children_num(parent)
     }
 
     /// Return container id of the tree.
@@ -291,7 +418,10 @@ impl LoroTree {
     }
 
     /// Return the fractional index of the target node with hex format.
-    pub fn fractional_index(&self, target: TreeID) -> Option<String> {
+    pub fn fractional_index(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+    ) -> Option<String> {
         self.0.fractional_index(target.into())
     }
 
@@ -305,7 +435,18 @@ impl LoroTree {
 
     /// Return the hierarchy array of the forest, each node is with metadata.
     pub fn get_value_with_meta(&self) -> LoroValue {
-        self.0.get_value_with_meta().into()
+        read_value(&self.0, self.0.get_value_with_meta())
+    }
+
+    /// The roots of the tree as a Python `list` of `dict`s, with their `children` nested and
+    /// their metadata maps converted to plain values, the same as `get_value_with_meta`.
+    pub fn to_py(&self) -> LoroValue {
+        read_value(&self.0, self.0.get_value_with_meta())
+    }
+
+    /// A stable hash of the current state of the tree, with the metadata of its nodes, see `LoroDoc.state_hash`.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.0.get_value_with_meta())
     }
 
     /// Whether the fractional index is enabled.
@@ -343,8 +484,11 @@ impl LoroTree {
     }
 
     /// Get the last move id of the target node.
-    pub fn get_last_move_id(&self, target: &TreeID) -> Option<ID> {
-        self.0.get_last_move_id(&(*target).into()).map(|x| x.into())
+    pub fn get_last_move_id(
+        &self,
+        #[pyo3(from_py_with = tree_id_from_object)] target: TreeID,
+    ) -> Option<ID> {
+        self.0.get_last_move_id(&target.into()).map(|x| x.into())
     }
 
     pub fn doc(&self) -> Option<LoroDoc> {
@@ -363,9 +507,10 @@ impl LoroTree {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     pub fn subscribe(&self, callback: Py<PyAny>) -> Option<Subscription> {
+        let hooks = DocHooks::of(&self.0.doc()?);
         let subscription = self.0.subscribe(Arc::new(move |e| {
             Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
+                callback.call1(py, (hooks.event(e),)).unwrap();
             });
         }));
         subscription.map(|s| s.into())
@@ -9,6 +9,15 @@ pub struct LoroUnknown(pub LoroUnknownInner);
 
 #[pymethods]
 impl LoroUnknown {
+    pub fn __repr__(&self) -> String {
+        format!("LoroUnknown(id={})", self.id().__repr__())
+    }
+
+    /// The arguments shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self) -> Vec<(&'static str, ContainerID)> {
+        vec![("id", self.id())]
+    }
+
     /// Get the container id.
     #[getter]
     pub fn id(&self) -> ContainerID {
@@ -18,4 +27,10 @@ impl LoroUnknown {
     pub fn doc(&self) -> Option<LoroDoc> {
         self.0.doc().map(|doc| doc.into())
     }
+
+    /// Get the container id, which stands for the content of an unknown container in
+    /// `LoroDoc.get_deep_value`.
+    pub fn to_py(&self) -> ContainerID {
+        self.0.id().into()
+    }
 }
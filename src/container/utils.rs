@@ -1,9 +1,12 @@
-use crate::value::LoroValue as PyLoroValue;
-use loro::LoroValue as CoreLoroValue;
+use crate::{
+    hooks::DocHooks,
+    value::{LoroValue as PyLoroValue, ValueOrContainer},
+};
+use loro::{ContainerTrait, LoroValue as CoreLoroValue};
 use pyo3::{
     exceptions::PyTypeError,
     types::{PyAnyMethods, PySequence, PySequenceMethods, PySlice, PySliceIndices},
-    Bound, FromPyObject, PyAny, PyResult,
+    Bound, FromPyObject, IntoPyObject, IntoPyObjectExt, PyAny, PyResult, Python,
 };
 
 #[derive(FromPyObject)]
@@ -36,3 +39,24 @@ pub fn slice_indices_positions(indices: &PySliceIndices) -> Vec<usize> {
     }
     positions
 }
+
+/// Wrap a value read from `container`, converting its tagged datetimes back if the document
+/// of the container has datetime decoding enabled.
+pub fn read_value<C: ContainerTrait>(container: &C, value: CoreLoroValue) -> PyLoroValue {
+    PyLoroValue::from(value).decoding_datetime(DocHooks::datetime_decoding(container))
+}
+
+/// `value`, read from `container`, as a `ValueOrContainer`, or as a plain value with its child
+/// containers resolved to their deep values if `resolve` is set.
+pub fn value_or_deep_value<'py, C: ContainerTrait>(
+    py: Python<'py>,
+    container: &C,
+    value: loro::ValueOrContainer,
+    resolve: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    if resolve {
+        read_value(container, value.get_deep_value()).into_pyobject(py)
+    } else {
+        ValueOrContainer::read_from(container, value).into_bound_py_any(py)
+    }
+}
@@ -1,28 +1,35 @@
-use std::{borrow::Cow, collections::HashMap, sync::Mutex};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use loro::FractionalIndex;
 use pyo3::{
-    exceptions::PyTypeError,
+    buffer::PyBuffer,
+    exceptions::{PyTypeError, PyValueError},
     prelude::*,
-    types::{PyBool, PyBytes, PyDict, PyList, PyMapping, PyString},
+    types::{PyBool, PyBytes, PyDict, PyFrozenSet, PyList, PyMapping, PySet, PyString, PyTuple},
     BoundObject, PyResult,
 };
 use rustc_hash::FxHashMap;
 
 use crate::{
     awareness::PeerInfo,
+    codec,
     container::{
         Container, Cursor, LoroCounter, LoroList, LoroMap, LoroMovableList, LoroText, LoroTree,
         LoroUnknown, Side, TreeNode,
     },
     doc::{
-        AbsolutePosition, ChangeMeta, Configure, CounterSpan, EncodedBlobMode, ExpandType,
-        ExportMode, IdSpan, ImportBlobMetadata, LoroDoc, PosQueryResult,
+        AbsolutePosition, ChangeMeta, CounterSpan, EncodedBlobMode, ExpandType, IdSpan,
+        ImportBlobMetadata, LoroDoc, PosQueryResult,
     },
     event::{
         ContainerDiff, Diff, DiffEvent, EventTriggerKind, Index, ListDiffItem, MapDelta, PathItem,
         Subscription, TextDelta, TreeDiff, TreeDiffItem, TreeExternalDiff,
     },
+    hooks::DocHooks,
     undo::{CursorWithPos, UndoItemMeta, UndoOrRedo},
     value::{ContainerID, ContainerType, LoroValue, TreeID, ValueOrContainer, ID},
 };
@@ -63,6 +70,9 @@ pub fn pyobject_to_loro_value(obj: &Bound<'_, PyAny>) -> PyResult<loro::LoroValu
     if obj.is_none() {
         return Ok(loro::LoroValue::Null);
     }
+    if let Some(value) = codec::encode(obj)? {
+        return Ok(value);
+    }
 
     if let Ok(value) = obj.extract::<bool>() {
         return Ok(loro::LoroValue::Bool(value));
@@ -90,6 +100,19 @@ pub fn pyobject_to_loro_value(obj: &Bound<'_, PyAny>) -> PyResult<loro::LoroValu
         }
         return Ok(loro::LoroValue::List(loro::LoroListValue::from(list)));
     }
+    if let Ok(value) = obj.downcast::<PyTuple>() {
+        let mut list = Vec::with_capacity(value.len());
+        for item in value.iter() {
+            list.push(pyobject_to_loro_value(&item)?);
+        }
+        return Ok(loro::LoroValue::List(loro::LoroListValue::from(list)));
+    }
+    if obj.downcast::<PySet>().is_ok() || obj.downcast::<PyFrozenSet>().is_ok() {
+        return Err(PyTypeError::new_err(
+            "sets are not supported as LoroValue because they have no stable order; \
+             convert it to a list first, e.g. `sorted(value)`",
+        ));
+    }
     if let Ok(value) = obj.downcast::<PyDict>() {
         let mut map = FxHashMap::default();
         for (key, value) in value.iter() {
@@ -122,9 +145,47 @@ pub fn pyobject_to_loro_value(obj: &Bound<'_, PyAny>) -> PyResult<loro::LoroValu
     if let Ok(value) = obj.downcast::<ContainerID>() {
         return Ok(loro::LoroValue::Container(value.get().clone().into()));
     }
+    if let Some(value) = numpy_to_loro_value(obj)? {
+        return Ok(value);
+    }
+    if let Ok(buffer) = PyBuffer::<u8>::get(obj) {
+        if buffer.dimensions() == 1 {
+            return Ok(loro::LoroValue::Binary(loro::LoroBinaryValue::from(
+                buffer.to_vec(obj.py())?,
+            )));
+        }
+    }
     Err(PyTypeError::new_err("Invalid LoroValue"))
 }
 
+/// Convert numpy scalars through `.item()`, without depending on numpy.
+///
+/// Most of them already convert like the builtin types they wrap, this covers the rest.
+/// 1-D uint8 arrays are handled by the buffer protocol as binary.
+fn numpy_to_loro_value(obj: &Bound<'_, PyAny>) -> PyResult<Option<loro::LoroValue>> {
+    let ty = obj.get_type();
+    if ty.module()?.to_str()? != "numpy" {
+        return Ok(None);
+    }
+    if ty.name()?.to_str()? == "ndarray" {
+        if PyBuffer::<u8>::get(obj).is_ok_and(|b| b.dimensions() == 1) {
+            return Ok(None);
+        }
+        return Err(PyTypeError::new_err(
+            "only 1-D uint8 numpy arrays can be stored (as binary); \
+             use `array.tolist()` to store other arrays as lists",
+        ));
+    }
+    if !obj.hasattr("item")? {
+        return Ok(None);
+    }
+    let item = obj.call_method0("item")?;
+    if item.get_type().is(&ty) {
+        return Ok(None);
+    }
+    pyobject_to_loro_value(&item).map(Some)
+}
+
 pub fn loro_value_to_pyobject(py: Python<'_>, value: LoroValue) -> PyResult<Bound<'_, PyAny>> {
     match value.0 {
         loro::LoroValue::Null => Ok(py.None().into_pyobject(py)?.into_any().into_bound()),
@@ -139,14 +200,17 @@ pub fn loro_value_to_pyobject(py: Python<'_>, value: LoroValue) -> PyResult<Boun
         loro::LoroValue::List(l) => {
             let list = l
                 .iter()
-                .map(|v| LoroValue(v.clone()).into_pyobject(py))
+                .map(|v| LoroValue::from(v.clone()).into_pyobject(py))
                 .collect::<Result<Vec<_>, _>>()?;
             Ok(list.into_pyobject(py)?.into_any().into_bound())
         }
         loro::LoroValue::Map(m) => {
+            if let Some(obj) = codec::decode(py, &m)? {
+                return Ok(obj);
+            }
             let dict = m
                 .iter()
-                .map(|(k, v)| Ok((k, LoroValue(v.clone()).into_pyobject(py)?)))
+                .map(|(k, v)| Ok((k, LoroValue::from(v.clone()).into_pyobject(py)?)))
                 .collect::<Result<FxHashMap<_, _>, PyErr>>()?;
             Ok(dict.into_pyobject(py)?.into_any().into_bound())
         }
@@ -157,9 +221,60 @@ pub fn loro_value_to_pyobject(py: Python<'_>, value: LoroValue) -> PyResult<Boun
     }
 }
 
+/// The number of items of a list or map shown by [loro_value_repr], the rest are elided.
+const REPR_ITEMS: usize = 10;
+/// The nesting depth shown by [loro_value_repr].
+const REPR_LEVELS: usize = 3;
+/// The number of characters of a string shown by [loro_value_repr].
+const REPR_CHARS: usize = 80;
+
+/// Format a [loro::LoroValue] the way Python's `repr` would format its converted value, with
+/// long lists, maps and strings elided by `reprlib` so the size of the result is bounded.
+pub fn loro_value_repr(py: Python<'_>, value: loro::LoroValue) -> PyResult<String> {
+    let repr = py.import("reprlib")?.getattr("Repr")?.call0()?;
+    repr.setattr("maxlevel", REPR_LEVELS)?;
+    repr.setattr("maxlist", REPR_ITEMS)?;
+    repr.setattr("maxdict", REPR_ITEMS)?;
+    repr.setattr("maxstring", REPR_CHARS)?;
+    repr.setattr("maxother", REPR_CHARS)?;
+    let value = loro_value_to_pyobject(py, repr_prefix(&value, REPR_LEVELS).into())?;
+    repr.call_method1("repr", (value,))?.extract()
+}
+
+/// The part of `value` that `reprlib` shows, so large values aren't converted in full. One
+/// more item than shown is kept, for `reprlib` to know there are more.
+fn repr_prefix(value: &loro::LoroValue, levels: usize) -> loro::LoroValue {
+    let child = |value: &loro::LoroValue| match levels {
+        0 => loro::LoroValue::Null,
+        _ => repr_prefix(value, levels - 1),
+    };
+    match value {
+        loro::LoroValue::List(list) => list
+            .iter()
+            .take(REPR_ITEMS + 1)
+            .map(child)
+            .collect::<Vec<_>>()
+            .into(),
+        loro::LoroValue::Map(map) => {
+            // reprlib shows the smallest keys
+            let mut keys = map.keys().collect::<Vec<_>>();
+            keys.sort_unstable();
+            keys.truncate(REPR_ITEMS + 1);
+            keys.into_iter()
+                .map(|key| (key.clone(), child(&map[key])))
+                .collect::<FxHashMap<_, _>>()
+                .into()
+        }
+        _ => value.clone(),
+    }
+}
+
 impl From<loro::LoroDoc> for LoroDoc {
     fn from(value: loro::LoroDoc) -> Self {
-        Self { doc: value }
+        Self {
+            hooks: DocHooks::of(&value),
+            doc: value,
+        }
     }
 }
 
@@ -300,12 +415,6 @@ impl From<loro::TreeID> for TreeID {
     }
 }
 
-impl From<loro::Configure> for Configure {
-    fn from(value: loro::Configure) -> Self {
-        Self(value)
-    }
-}
-
 impl From<loro::IdSpan> for IdSpan {
     fn from(value: loro::IdSpan) -> Self {
         Self {
@@ -359,6 +468,53 @@ impl From<Index> for loro::Index {
     }
 }
 
+/// Accept a `TreeID` or its `counter@peer` string form, `None` or `"root"` for the roots, or
+/// `"deleted"` for the parent of the deleted nodes, where a tree parent is expected.
+pub(crate) fn tree_parent_id_from_object(ob: &Bound<'_, PyAny>) -> PyResult<loro::TreeParentId> {
+    if ob.is_none() {
+        return Ok(loro::TreeParentId::Root);
+    }
+    if let Ok(s) = ob.downcast::<PyString>() {
+        return match s.to_str()? {
+            "root" => Ok(loro::TreeParentId::Root),
+            "deleted" => Ok(loro::TreeParentId::Deleted),
+            s => TreeID::parse(s)
+                .map(|id| loro::TreeParentId::Node(id.into()))
+                .map_err(|_| {
+                    PyValueError::new_err(format!(
+                        "invalid tree parent '{}', expected a TreeID, None, 'root' or 'deleted'",
+                        s
+                    ))
+                }),
+        };
+    }
+    let id = ob.extract::<TreeID>().map_err(|_| {
+        PyTypeError::new_err("a tree parent must be a TreeID, None, 'root' or 'deleted'")
+    })?;
+    Ok(loro::TreeParentId::Node(id.into()))
+}
+
+/// Accept a `TreeID` or its `counter@peer` string form.
+pub(crate) fn tree_id_from_object(ob: &Bound<'_, PyAny>) -> PyResult<TreeID> {
+    if let Ok(s) = ob.downcast::<PyString>() {
+        return TreeID::parse(s.to_str()?);
+    }
+    ob.extract::<TreeID>()
+        .map_err(|_| PyTypeError::new_err("a tree id must be a TreeID or a 'counter@peer' str"))
+}
+
+/// Like [tree_parent_id_from_object], for the parent of a created or moved node, which can't
+/// be `"deleted"`.
+pub(crate) fn tree_parent_from_object(ob: &Bound<'_, PyAny>) -> PyResult<Option<TreeID>> {
+    match tree_parent_id_from_object(ob)? {
+        loro::TreeParentId::Node(id) => Ok(Some(id.into())),
+        loro::TreeParentId::Root => Ok(None),
+        _ => Err(PyValueError::new_err(
+            "a node can't be created or moved under 'deleted', use delete() instead",
+        )),
+    }
+}
+
 pub(crate) fn tree_parent_id_to_option_tree_id(value: loro::TreeParentId) -> Option<TreeID> {
     match value {
         loro::TreeParentId::Node(id) => Some(id.into()),
@@ -559,7 +715,7 @@ impl From<loro::EventTriggerKind> for EventTriggerKind {
 
 impl From<loro::LoroValue> for LoroValue {
     fn from(value: loro::LoroValue) -> Self {
-        Self(value)
+        Self(value, false)
     }
 }
 
@@ -721,7 +877,7 @@ impl From<loro::cursor::Cursor> for Cursor {
 
 impl From<loro::Subscription> for Subscription {
     fn from(value: loro::Subscription) -> Self {
-        Subscription(Mutex::new(Some(value)))
+        Subscription(Arc::new(Mutex::new(Some(value))))
     }
 }
 
@@ -764,29 +920,6 @@ impl From<loro::CounterSpan> for CounterSpan {
     }
 }
 
-impl From<ExportMode> for loro::ExportMode<'_> {
-    fn from(value: ExportMode) -> Self {
-        match value {
-            ExportMode::Snapshot {} => loro::ExportMode::Snapshot,
-            ExportMode::Updates { from_ } => loro::ExportMode::Updates {
-                from: Cow::Owned(from_.into()),
-            },
-            ExportMode::UpdatesInRange { spans } => loro::ExportMode::UpdatesInRange {
-                spans: Cow::Owned(spans.into_iter().map(|s| s.into()).collect()),
-            },
-            ExportMode::ShallowSnapshot { frontiers } => {
-                loro::ExportMode::ShallowSnapshot(Cow::Owned(frontiers.into()))
-            }
-            ExportMode::StateOnly { frontiers } => {
-                loro::ExportMode::StateOnly(frontiers.map(|f| Cow::Owned(f.into())))
-            }
-            ExportMode::SnapshotAt { version } => loro::ExportMode::SnapshotAt {
-                version: Cow::Owned(version.into()),
-            },
-        }
-    }
-}
-
 impl From<loro::ChangeMeta> for ChangeMeta {
     fn from(value: loro::ChangeMeta) -> Self {
         ChangeMeta {
@@ -0,0 +1,359 @@
+//! Renderings for the Jupyter `_repr_html_`/`_repr_markdown_` hooks and the `rich` library.
+
+use std::fmt::Write;
+
+use loro::{LoroValue, ToJson, TreeID};
+use pyo3::prelude::*;
+
+use crate::version::Frontiers;
+
+/// Values longer than this are cut in previews.
+const PREVIEW_LEN: usize = 80;
+/// Trees are only rendered up to this many nodes, so huge documents stay responsive.
+const MAX_TREE_NODES: usize = 500;
+/// Nodes nested deeper than this are elided.
+const MAX_TREE_DEPTH: usize = 64;
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn preview(value: &LoroValue) -> String {
+    let json = value.to_json();
+    if json.chars().count() <= PREVIEW_LEN {
+        return json;
+    }
+    let mut cut = json.chars().take(PREVIEW_LEN - 1).collect::<String>();
+    cut.push('…');
+    cut
+}
+
+fn is_set(value: &LoroValue) -> bool {
+    !matches!(value, LoroValue::Null | LoroValue::Bool(false))
+}
+
+/// Only links with these schemes are rendered, so a `javascript:` link can't run in a notebook.
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+pub fn text_html(text: &loro::LoroText) -> String {
+    let mut out = String::from(r#"<div class="loro-text" style="white-space: pre-wrap">"#);
+    for delta in text.to_delta() {
+        let loro::TextDelta::Insert { insert, attributes } = delta else {
+            continue;
+        };
+        let mut attributes = attributes
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, v)| is_set(v))
+            .collect::<Vec<_>>();
+        attributes.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let mut close = Vec::new();
+        for (key, value) in &attributes {
+            let (open, tag) = match (key.as_str(), value) {
+                ("bold", _) => ("<strong>".to_string(), "strong"),
+                ("italic", _) => ("<em>".to_string(), "em"),
+                ("underline", _) => ("<u>".to_string(), "u"),
+                ("strike" | "strikethrough", _) => ("<s>".to_string(), "s"),
+                ("code", _) => ("<code>".to_string(), "code"),
+                ("link", LoroValue::String(url)) if is_safe_url(url) => {
+                    (format!(r#"<a href="{}">"#, escape_html(url)), "a")
+                }
+                ("link", _) => continue,
+                _ => (
+                    format!(
+                        r#"<span title="{}">"#,
+                        escape_html(&format!("{key}: {}", preview(value)))
+                    ),
+                    "span",
+                ),
+            };
+            out.push_str(&open);
+            close.push(tag);
+        }
+        out.push_str(&escape_html(&insert));
+        for tag in close.into_iter().rev() {
+            let _ = write!(out, "</{tag}>");
+        }
+    }
+    out.push_str("</div>");
+    out
+}
+
+pub fn text_markdown(text: &loro::LoroText) -> String {
+    let mut out = String::new();
+    for delta in text.to_delta() {
+        let loro::TextDelta::Insert { insert, attributes } = delta else {
+            continue;
+        };
+        let attributes = attributes.unwrap_or_default();
+        let has = |key: &str| attributes.get(key).is_some_and(is_set);
+        let mut segment = if has("code") {
+            format!("`{}`", insert.replace('`', "'"))
+        } else {
+            escape_markdown(&insert)
+        };
+        if has("strike") || has("strikethrough") {
+            segment = format!("~~{segment}~~");
+        }
+        if has("italic") {
+            segment = format!("*{segment}*");
+        }
+        if has("bold") {
+            segment = format!("**{segment}**");
+        }
+        if let Some(LoroValue::String(url)) = attributes.get("link") {
+            if is_safe_url(url) {
+                segment = format!("[{segment}](<{}>)", url.replace(['<', '>'], ""));
+            }
+        }
+        out.push_str(&segment);
+    }
+    out
+}
+
+/// Build a `rich.text.Text` with the text styles mapped to rich styles.
+pub fn text_rich<'py>(py: Python<'py>, text: &loro::LoroText) -> PyResult<Bound<'py, PyAny>> {
+    let out = py.import("rich.text")?.getattr("Text")?.call0()?;
+    for delta in text.to_delta() {
+        let loro::TextDelta::Insert { insert, attributes } = delta else {
+            continue;
+        };
+        let mut styles = attributes
+            .unwrap_or_default()
+            .iter()
+            .filter(|(_, v)| is_set(v))
+            .filter_map(|(key, value)| match (key.as_str(), value) {
+                ("bold", _) => Some("bold".to_string()),
+                ("italic", _) => Some("italic".to_string()),
+                ("underline", _) => Some("underline".to_string()),
+                ("strike" | "strikethrough", _) => Some("strike".to_string()),
+                ("code", _) => Some("reverse".to_string()),
+                ("link", LoroValue::String(url))
+                    if is_safe_url(url) && !url.contains(char::is_whitespace) =>
+                {
+                    Some(format!("link {}", url.as_str()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        styles.sort_unstable();
+        let style = (!styles.is_empty()).then(|| styles.join(" "));
+        out.call_method1("append", (insert, style))?;
+    }
+    Ok(out)
+}
+
+struct TreeRenderer<'a> {
+    tree: &'a loro::LoroTree,
+    budget: usize,
+    out: String,
+}
+
+impl TreeRenderer<'_> {
+    fn label(&self, node: TreeID) -> String {
+        let meta = self
+            .tree
+            .get_meta(node)
+            .map(|m| m.get_deep_value())
+            .ok()
+            .filter(|v| !matches!(v, LoroValue::Map(m) if m.is_empty()));
+        match meta {
+            Some(meta) => format!("{node} {}", preview(&meta)),
+            None => node.to_string(),
+        }
+    }
+
+    fn children(&self, node: TreeID) -> Vec<TreeID> {
+        self.tree.children(Some(node)).unwrap_or_default()
+    }
+
+    fn html(&mut self, nodes: Vec<TreeID>, depth: usize) {
+        self.out.push_str("<ul>");
+        let total = nodes.len();
+        for (i, node) in nodes.into_iter().enumerate() {
+            if self.budget == 0 || depth >= MAX_TREE_DEPTH {
+                let _ = write!(self.out, "<li>… {} more</li>", total - i);
+                break;
+            }
+            self.budget -= 1;
+            let label = escape_html(&self.label(node));
+            let children = self.children(node);
+            if children.is_empty() {
+                let _ = write!(self.out, "<li>{label}</li>");
+            } else {
+                let _ = write!(self.out, "<li><details open><summary>{label}</summary>");
+                self.html(children, depth + 1);
+                self.out.push_str("</details></li>");
+            }
+        }
+        self.out.push_str("</ul>");
+    }
+
+    fn markdown(&mut self, nodes: Vec<TreeID>, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let total = nodes.len();
+        for (i, node) in nodes.into_iter().enumerate() {
+            if self.budget == 0 || depth >= MAX_TREE_DEPTH {
+                let _ = writeln!(self.out, "{indent}- … {} more", total - i);
+                break;
+            }
+            self.budget -= 1;
+            let label = escape_markdown(&self.label(node));
+            let _ = writeln!(self.out, "{indent}- {label}");
+            let children = self.children(node);
+            self.markdown(children, depth + 1);
+        }
+    }
+
+    fn rich(
+        &mut self,
+        parent: &Bound<'_, PyAny>,
+        text: &Bound<'_, PyAny>,
+        nodes: Vec<TreeID>,
+        depth: usize,
+    ) -> PyResult<()> {
+        let total = nodes.len();
+        for (i, node) in nodes.into_iter().enumerate() {
+            if self.budget == 0 || depth >= MAX_TREE_DEPTH {
+                parent.call_method1("add", (text.call1((format!("… {} more", total - i),))?,))?;
+                break;
+            }
+            self.budget -= 1;
+            let branch = parent.call_method1("add", (text.call1((self.label(node),))?,))?;
+            let children = self.children(node);
+            self.rich(&branch, text, children, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn tree_html(tree: &loro::LoroTree) -> String {
+    let mut renderer = TreeRenderer {
+        tree,
+        budget: MAX_TREE_NODES,
+        out: String::from(r#"<div class="loro-tree">"#),
+    };
+    renderer.html(tree.roots(), 0);
+    renderer.out.push_str("</div>");
+    renderer.out
+}
+
+/// Build a `rich.tree.Tree` of the nodes, labelled with their id and metadata.
+pub fn tree_rich<'py>(py: Python<'py>, tree: &loro::LoroTree) -> PyResult<Bound<'py, PyAny>> {
+    let text = py.import("rich.text")?.getattr("Text")?;
+    let root = py
+        .import("rich.tree")?
+        .getattr("Tree")?
+        .call1((text.call1(("LoroTree",))?,))?;
+    let mut renderer = TreeRenderer {
+        tree,
+        budget: MAX_TREE_NODES,
+        out: String::new(),
+    };
+    renderer.rich(&root, &text, tree.roots(), 0)?;
+    Ok(root)
+}
+
+pub fn tree_markdown(tree: &loro::LoroTree) -> String {
+    let mut renderer = TreeRenderer {
+        tree,
+        budget: MAX_TREE_NODES,
+        out: String::new(),
+    };
+    renderer.markdown(tree.roots(), 0);
+    if renderer.out.is_empty() {
+        return "*empty tree*".to_string();
+    }
+    renderer.out
+}
+
+/// The root containers of `doc` as `(name, type, preview)`, sorted by name.
+fn doc_roots(doc: &loro::LoroDoc) -> Vec<(String, String, String)> {
+    let LoroValue::Map(shallow) = doc.get_value() else {
+        return Vec::new();
+    };
+    let LoroValue::Map(deep) = doc.get_deep_value() else {
+        return Vec::new();
+    };
+    let mut roots = shallow
+        .iter()
+        .map(|(name, value)| {
+            let ty = match value {
+                LoroValue::Container(id) => id.container_type().to_string(),
+                _ => "Value".to_string(),
+            };
+            let value = deep.get(name).map(preview).unwrap_or_default();
+            (name.to_string(), ty, value)
+        })
+        .collect::<Vec<_>>();
+    roots.sort_unstable();
+    roots
+}
+
+pub fn doc_html(doc: &loro::LoroDoc) -> String {
+    let mut out = String::from(r#"<div class="loro-doc">"#);
+    let _ = write!(
+        out,
+        "<p><strong>LoroDoc</strong> peer_id={}, frontiers={}</p>",
+        doc.peer_id(),
+        escape_html(&Frontiers::from(doc.state_frontiers()).to_string())
+    );
+    out.push_str("<table><thead><tr><th>Root</th><th>Type</th><th>Value</th></tr></thead><tbody>");
+    for (name, ty, value) in doc_roots(doc) {
+        let _ = write!(
+            out,
+            "<tr><td>{}</td><td>{ty}</td><td><code>{}</code></td></tr>",
+            escape_html(&name),
+            escape_html(&value)
+        );
+    }
+    out.push_str("</tbody></table></div>");
+    out
+}
+
+pub fn doc_markdown(doc: &loro::LoroDoc) -> String {
+    let mut out = format!(
+        "**LoroDoc** peer_id={}, frontiers={}\n\n| Root | Type | Value |\n| --- | --- | --- |\n",
+        doc.peer_id(),
+        escape_markdown(&Frontiers::from(doc.state_frontiers()).to_string())
+    );
+    for (name, ty, value) in doc_roots(doc) {
+        let _ = writeln!(
+            out,
+            "| {} | {ty} | `{}` |",
+            escape_markdown(&name),
+            value.replace('`', "'").replace('|', "\\|")
+        );
+    }
+    out
+}
@@ -1,28 +1,50 @@
-use loro::{Counter, Lamport, LoroDoc as LoroDocInner, PeerID, Timestamp};
+use loro::{ContainerTrait, Counter, Lamport, LoroDoc as LoroDocInner, PeerID, Timestamp, ToJson};
 use pyo3::{
-    exceptions::PyValueError,
+    exceptions::{PyTypeError, PyValueError},
     prelude::*,
-    types::{PyBytes, PyType},
+    types::{PyBytes, PyCFunction, PyDict, PyIterator, PyList, PyString, PyTuple, PyType},
+};
+use rustc_hash::FxHashMap;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fmt::Display,
+    fs,
+    ops::ControlFlow,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc, Mutex, OnceLock, Weak,
+    },
 };
-use std::{borrow::Cow, collections::HashSet, fmt::Display, ops::ControlFlow, sync::Arc};
 
 use crate::{
+    batch::{self, OpDescriptor},
+    chunk, compress,
     container::{
         Container, Cursor, LoroCounter, LoroList, LoroMap, LoroMovableList, LoroText, LoroTree,
         Side,
     },
     convert::pyobject_to_container_id,
+    display,
     err::{PyLoroError, PyLoroResult},
-    event::{DiffBatch, DiffEvent, Index, Subscription},
+    event::{DiffBatch, EventTriggerKind, Index, Subscription, SubscriptionInfo},
+    hash, history,
+    hooks::{DocHooks, ReadOnly, TrialImport, Updates},
+    msgpack,
+    peer::{self, peer_id_from_object, peer_id_from_optional_object},
+    stats::{DocAnalysis, GcResult, StorageStats},
     value::{ContainerID, ContainerType, LoroValue, Ordering, ValueOrContainer, ID},
-    version::{Frontiers, VersionRange, VersionVector, VersionVectorDiff},
+    version::{Frontiers, SyncGap, VersionInfo, VersionRange, VersionVector, VersionVectorDiff},
 };
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<LoroDoc>()?;
     m.add_class::<Configure>()?;
     m.add_class::<ImportStatus>()?;
+    m.add_class::<CommitInfo>()?;
     m.add_class::<PosQueryResult>()?;
+    m.add_class::<AbsolutePosition>()?;
     m.add_class::<EncodedBlobMode>()?;
     m.add_class::<ImportBlobMetadata>()?;
     m.add_class::<StyleConfigMap>()?;
@@ -41,24 +63,217 @@ pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
 /// **Important:** Loro is a pure library and does not handle network protocols.
 /// It is the responsibility of the user to manage the storage, loading, and synchronization
 /// of the bytes exported by Loro in a manner suitable for their specific environment.
+///
+/// The hooks and settings of `set_metrics_hook`, `set_import_filter`, `set_encryption`,
+/// `set_quota_hook`, `set_pending_hook`, `set_timestamp_provider`, `set_dispatcher`,
+/// `set_readonly` and `set_datetime_decoding` belong to the document, not to this object.
+/// Every `LoroDoc` object of the document, e.g. `container.doc()`, sees them, and they last
+/// as long as the document. `fork` only copies the text style config and the datetime decoding.
 #[pyclass(frozen)]
 pub struct LoroDoc {
+    // Declared first so the hooks are released before the document they're keyed by.
+    pub(crate) hooks: Arc<DocHooks>,
     pub(crate) doc: LoroDocInner,
 }
 
 impl Default for LoroDoc {
     fn default() -> Self {
-        let doc = LoroDocInner::new();
-        Self { doc }
+        Self::seeded(LoroDocInner::new())
     }
 }
 
 #[pymethods]
 impl LoroDoc {
     /// Create a new `LoroDoc` instance.
+    ///
+    /// The keyword arguments configure the document before any op is created, like the
+    /// matching setters: `peer_id` (an int, or a string passed to
+    /// `peer_id_from_string`), `record_timestamp`, `merge_interval` in seconds
+    /// (`set_change_merge_interval`), `detached_editing` and `hide_empty_root_containers`.
     #[new]
-    pub fn new() -> Self {
-        Self::default()
+    #[pyo3(signature = (
+        *,
+        peer_id=None,
+        record_timestamp=None,
+        merge_interval=None,
+        detached_editing=None,
+        hide_empty_root_containers=None,
+    ))]
+    pub fn new(
+        #[pyo3(from_py_with = peer_id_from_optional_object)] peer_id: Option<PeerID>,
+        record_timestamp: Option<bool>,
+        merge_interval: Option<i64>,
+        detached_editing: Option<bool>,
+        hide_empty_root_containers: Option<bool>,
+    ) -> PyLoroResult<Self> {
+        let doc = Self::default();
+        if let Some(peer_id) = peer_id {
+            doc.doc.set_peer_id(peer_id)?;
+        }
+        if let Some(record) = record_timestamp {
+            doc.doc.set_record_timestamp(record);
+        }
+        if let Some(interval) = merge_interval {
+            doc.doc.set_change_merge_interval(interval);
+        }
+        if let Some(enable) = detached_editing {
+            doc.doc.set_detached_editing(enable);
+        }
+        if let Some(hide) = hide_empty_root_containers {
+            doc.doc.set_hide_empty_root_containers(hide);
+        }
+        Ok(doc)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "LoroDoc(peer_id={}, frontiers={}, detached={})",
+            self.doc.peer_id(),
+            Frontiers::from(self.doc.state_frontiers()).__repr__(),
+            if self.hooks.is_detached(&self.doc) {
+                "True"
+            } else {
+                "False"
+            }
+        )
+    }
+
+    /// Copy the document with `fork`, so the copy gets its own PeerID and history.
+    ///
+    /// A shallow copy sharing the document would let two objects commit with the same
+    /// PeerID, so `copy.copy` forks as well.
+    pub fn __copy__(&self) -> Self {
+        self.fork()
+    }
+
+    /// Copy the document with `fork` for `copy.deepcopy`.
+    pub fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> Self {
+        self.fork()
+    }
+
+    /// Set a callback invoked after every import, export and commit of this document.
+    ///
+    /// The callback receives an [OperationMetrics] with the operation name, its duration,
+    /// the bytes read or written and the number of ops it added, which makes it easy to feed
+    /// a metrics system without wrapping every call site. Exceptions raised by the callback
+    /// are reported with `sys.unraisablehook` instead of failing the operation.
+    ///
+    /// Pass `None` to remove it.
+    #[pyo3(signature = (callback))]
+    pub fn set_metrics_hook(&self, callback: Option<Py<PyAny>>) {
+        self.hooks.set_metrics(callback);
+    }
+
+    /// Set a callback that decides whether a blob may be imported.
+    ///
+    /// Before `import_`, `import_with`, `import_batch` and `import_json_updates` apply their
+    /// updates, the callback receives an [ImportRequest] describing them: the decoded blob
+    /// metadata, the peers and spans of their changes and, on demand, the containers they
+    /// touch. The blobs of `import_batch` are described together by a single request. If it
+    /// returns a falsy value the import fails without changing the document; exceptions it
+    /// raises are propagated. Servers can use it to reject updates from unauthorized peers or
+    /// to read-only containers.
+    ///
+    /// The updates are imported into a fork of the document first, so the request describes
+    /// the changes they really contain whatever the blob headers claim. This costs about as
+    /// much as the import itself.
+    ///
+    /// Pass `None` to remove it.
+    #[pyo3(signature = (callback))]
+    pub fn set_import_filter(&self, callback: Option<Py<PyAny>>) {
+        self.hooks.set_import_filter(callback);
+    }
+
+    /// Set the callbacks that encrypt the blobs returned by `export`, `export_chunks` and
+    /// `gc`, and decrypt the blobs passed to `import_`, `import_with` and `import_batch`.
+    ///
+    /// Both take and return `bytes`. Encrypted blobs are tagged, so importing a plain blob while
+    /// a `decrypt` callback is set fails, and so does importing an encrypted blob without one;
+    /// plain and encrypted snapshots can't be mixed up silently. Import filters see the
+    /// decrypted blob. Pass `None` to remove a callback.
+    ///
+    /// JSON can't carry an encrypted payload, so `export_json_updates` and
+    /// `export_json_in_id_span` raise while an `encrypt` callback is set instead of leaking the
+    /// plain history.
+    #[pyo3(signature = (encrypt, decrypt))]
+    pub fn set_encryption(&self, encrypt: Option<Py<PyAny>>, decrypt: Option<Py<PyAny>>) {
+        self.hooks.set_encryption(encrypt, decrypt);
+    }
+
+    /// Set a callback that can veto commits and imports that would make the document too
+    /// large, to enforce per-document quotas.
+    ///
+    /// Before `commit`, `commit_with`, `import_`, `import_with` and `import_batch`, the callback
+    /// receives a [QuotaRequest] with the number of ops the operation would add, the size of
+    /// the imported blobs and the current number of ops. If it returns a falsy value the
+    /// operation fails; exceptions it raises are propagated. A rejected import leaves the
+    /// document unchanged. Loro can't discard the ops of a transaction, so the ops of a
+    /// rejected commit stay pending: the next `commit` asks the hook again, while the implicit
+    /// commits done by other methods, e.g. `export`, commit them without asking. Check the
+    /// quota before making large edits when that matters.
+    ///
+    /// Pass `None` to remove it.
+    #[pyo3(signature = (callback))]
+    pub fn set_quota_hook(&self, callback: Option<Py<PyAny>>) {
+        self.hooks.set_quota(callback);
+    }
+
+    /// Set a callback invoked when imported changes that were waiting for their dependencies
+    /// get applied.
+    ///
+    /// The callback receives the list of `IdSpan`s applied, out of those reported by
+    /// `pending_changes`. Exceptions raised by the callback are reported with
+    /// `sys.unraisablehook` instead of failing the import.
+    ///
+    /// Pass `None` to remove it.
+    #[pyo3(signature = (callback))]
+    pub fn set_pending_hook(&self, callback: Option<Py<PyAny>>) {
+        self.hooks.set_pending_hook(callback);
+    }
+
+    /// Get the spans of the imported changes waiting for missing dependencies, by peer.
+    ///
+    /// They are applied once the changes they depend on are imported; `missing_spans` with
+    /// the version of the peer that sent them tells what to request. The imports made
+    /// through any `LoroDoc` object of the document are tracked.
+    pub fn pending_changes(&self) -> Vec<IdSpan> {
+        self.hooks.pending_changes()
+    }
+
+    /// Make the local edits of the document fail, e.g. in viewer processes or to guard
+    /// against accidental writes in request handlers.
+    ///
+    /// The document is detached with detached editing disabled, so every mutation raises.
+    /// Imports are still applied and the state follows them, unless `allow_import` is false:
+    /// then imports raise too. Their events are reported as `EventTriggerKind.Import` with the
+    /// origin of the import, as on a writable document, and `is_detached` leaves out the
+    /// detaching done by this mode. `checkout` still works for viewing older versions, and
+    /// `attach` or `checkout_to_latest` go back to the latest version without allowing
+    /// edits. `set_readonly(False)` restores the detached state and the detached editing
+    /// setting the document had before, attaching it again unless it was detached.
+    #[pyo3(signature = (readonly, allow_import=true))]
+    pub fn set_readonly(&self, readonly: bool, allow_import: bool) {
+        let mode = match (readonly, allow_import) {
+            (false, _) => ReadOnly::Writable,
+            (true, true) => ReadOnly::AllowImport,
+            (true, false) => ReadOnly::Frozen,
+        };
+        self.hooks.set_readonly(&self.doc, mode);
+    }
+
+    /// Whether the document was made read-only with `set_readonly`.
+    pub fn is_readonly(&self) -> bool {
+        self.hooks.readonly() != ReadOnly::Writable
+    }
+
+    /// Render the root containers as an HTML table for Jupyter.
+    pub fn _repr_html_(&self) -> String {
+        display::doc_html(&self.doc)
+    }
+
+    /// Render the root containers as a Markdown table for Jupyter.
+    pub fn _repr_markdown_(&self) -> String {
+        display::doc_markdown(&self.doc)
     }
 
     /// Duplicate the document with a different PeerID
@@ -69,23 +284,78 @@ impl LoroDoc {
     /// It will have the same effect as `fork_at(&self.state_frontiers())`.
     #[inline]
     pub fn fork(&self) -> Self {
-        let doc = self.doc.fork();
-        Self { doc }
+        let fork = Self::seeded(self.doc.fork());
+        fork.hooks
+            .add_text_style_keys(&self.hooks.text_style_keys().lock().unwrap());
+        fork.hooks
+            .set_datetime_decoding(self.hooks.decodes_datetime());
+        fork
     }
 
     /// Fork the document at the given frontiers.
     ///
     /// The created doc will only contain the history before the specified frontiers.
     pub fn fork_at(&self, frontiers: &Frontiers) -> Self {
-        let new_doc = self.doc.fork_at(&frontiers.into());
-        Self { doc: new_doc }
+        let fork = Self::seeded(self.doc.fork_at(&frontiers.into()));
+        fork.hooks
+            .add_text_style_keys(&self.hooks.text_style_keys().lock().unwrap());
+        fork.hooks
+            .set_datetime_decoding(self.hooks.decodes_datetime());
+        fork
+    }
+
+    /// Create a document whose PeerID is derived from `seed`, so a simulation or a doctest
+    /// gets the same PeerIDs on every run. See `set_rng_seed` to seed every new document.
+    #[classmethod]
+    pub fn new_with_seed(_cls: &Bound<'_, PyType>, seed: u64) -> PyLoroResult<Self> {
+        let mut state = seed;
+        let doc = LoroDocInner::new();
+        doc.set_peer_id(peer::seeded_peer_id(&mut state))?;
+        Ok(doc.into())
+    }
+
+    /// Get the deep value of the document, or of the value at `path`, at the given version.
+    ///
+    /// `path` is a path accepted by `get_by_str_path`, e.g. `"map/key"`. Returns `None` if
+    /// nothing is at the path in that version. The document is left at its current version:
+    /// the value is read from a fork, so it's O(n) like `fork_at`.
+    ///
+    /// Raises if `frontiers` isn't a version of the document, e.g. it has ops this document
+    /// hasn't imported or is before the start of a shallow snapshot.
+    #[pyo3(signature = (frontiers, path=None))]
+    pub fn get_value_at(
+        &self,
+        frontiers: &Frontiers,
+        path: Option<&str>,
+    ) -> PyLoroResult<Option<LoroValue>> {
+        let doc = history::fork_at(&self.doc, &frontiers.into())?;
+        let decode_datetime = self.hooks.decodes_datetime();
+        let Some(path) = path else {
+            let value = LoroValue::from(doc.get_deep_value());
+            return Ok(Some(value.decoding_datetime(decode_datetime)));
+        };
+        let Some(value) = doc.get_by_str_path(path) else {
+            return Ok(None);
+        };
+        let value = match value {
+            loro::ValueOrContainer::Value(value) => value,
+            loro::ValueOrContainer::Container(container) => {
+                Container::from(container).get_deep_value()
+            }
+        };
+        Ok(Some(
+            LoroValue::from(value).decoding_datetime(decode_datetime),
+        ))
     }
 
     /// Get the configurations of the document.
     #[inline]
     #[getter]
     pub fn config(&self) -> Configure {
-        self.doc.config().clone().into()
+        Configure {
+            inner: self.doc.config().clone(),
+            text_style_keys: self.hooks.text_style_keys(),
+        }
     }
 
     /// Get `Change` at the given id.
@@ -105,6 +375,98 @@ impl LoroDoc {
         self.doc.get_change(id.into()).map(|meta| meta.into())
     }
 
+    /// Get the ops in the given id span, described with their container, kind and content.
+    ///
+    /// Together with `get_change`, it can show which change an edit belongs to and what
+    /// else that change did.
+    pub fn get_changed_ops_in(&self, id_span: IdSpan) -> Vec<history::OpInfo> {
+        history::decode_ops(&self.doc, id_span.into())
+    }
+
+    /// Render the change DAG for debugging concurrent history.
+    ///
+    /// Each change is a node labelled with its id, peer, lamport and commit message, with an
+    /// edge from every dependency to the change depending on it. `format` is `"mermaid"` or
+    /// `"dot"` (Graphviz).
+    #[pyo3(signature = (format="mermaid"))]
+    pub fn export_change_graph(&self, format: &str) -> PyResult<String> {
+        let format = match format {
+            "mermaid" => history::GraphFormat::Mermaid,
+            "dot" => history::GraphFormat::Dot,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown graph format '{format}', expected 'mermaid' or 'dot'"
+                )))
+            }
+        };
+        Ok(history::change_graph(&self.doc, format))
+    }
+
+    /// Iterate the changes with ops in the range `(from_, to]`.
+    ///
+    /// Each item has the `ChangeMeta` of a change and its `ops`, described with their
+    /// container, kind and content. The ops are only decoded when accessed. Changes
+    /// partially in the range only list the ops inside it.
+    ///
+    /// By default, it iterates the whole history, parents before their children. Pass `peer`
+    /// to only get the changes of that peer, in counter order.
+    #[pyo3(signature = (from_=None, to=None, peer=None))]
+    pub fn iter_changes(
+        &self,
+        from_: Option<VersionVector>,
+        to: Option<VersionVector>,
+        peer: Option<PeerID>,
+    ) -> history::ChangeIter {
+        history::ChangeIter::new(&self.doc, from_.map(Into::into), to.map(Into::into), peer)
+    }
+
+    /// Get the latest version made only of changes with a timestamp up to `timestamp`.
+    ///
+    /// Timestamps are in seconds and only recorded when `set_record_timestamp(True)` is
+    /// enabled. Each peer's changes are taken in counter order up to its first change made
+    /// after `timestamp`; a peer whose clock went backwards has its later changes left out
+    /// even if their timestamps are earlier.
+    /// Changes depending on later ones, which happen when clocks are skewed, are left out
+    /// too, so the result can always be checked out.
+    pub fn frontiers_at_timestamp(&self, timestamp: Timestamp) -> Frontiers {
+        history::frontiers_at_timestamp(&self.doc, timestamp).into()
+    }
+
+    /// Get up to `points` versions spread evenly over the history, e.g. for the checkpoints of
+    /// a history scrubber. The last one is the latest version.
+    ///
+    /// With `by="changes"`, the versions are made of the first `i * n / points` changes,
+    /// ordered by lamport. With `by="timestamp"`, they are the versions at evenly spaced
+    /// timestamps, see `frontiers_at_timestamp`. Versions with no change or equal to the
+    /// previous one are skipped.
+    #[pyo3(signature = (points, by="changes"))]
+    pub fn timeline(&self, points: usize, by: &str) -> PyResult<Vec<history::TimelinePoint>> {
+        let spacing = match by {
+            "changes" => history::TimelineSpacing::Changes,
+            "timestamp" => history::TimelineSpacing::Timestamp,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown spacing '{by}', expected 'changes' or 'timestamp'"
+                )))
+            }
+        };
+        if points == 0 {
+            return Err(PyValueError::new_err("points must be positive"));
+        }
+        Ok(history::timeline(&self.doc, points, spacing))
+    }
+
+    /// Get the changes with `start <= timestamp < end`, parents before their children.
+    ///
+    /// Each peer's changes are binary searched by timestamp until its clock first goes
+    /// backwards and scanned after that, so a peer's timestamps don't need to be sorted.
+    pub fn changes_between(&self, start: Timestamp, end: Timestamp) -> Vec<ChangeMeta> {
+        history::changes_between(&self.doc, start, end)
+            .into_iter()
+            .map(ChangeMeta::from)
+            .collect()
+    }
+
     /// Decodes the metadata for an imported blob from the provided bytes.
     #[classmethod]
     pub fn decode_import_blob_meta(
@@ -112,7 +474,8 @@ impl LoroDoc {
         bytes: Bound<'_, PyBytes>,
         check_checksum: bool,
     ) -> PyLoroResult<ImportBlobMetadata> {
-        let meta = LoroDocInner::decode_import_blob_meta(bytes.as_bytes(), check_checksum)?;
+        let bytes = compress::decompress(bytes.as_bytes().into())?;
+        let meta = LoroDocInner::decode_import_blob_meta(&bytes, check_checksum)?;
         Ok(meta.into())
     }
 
@@ -129,6 +492,38 @@ impl LoroDoc {
         self.doc.set_record_timestamp(record);
     }
 
+    /// Set a callback returning the Unix timestamp, in seconds, of each new change.
+    ///
+    /// It's called on every commit, including the implicit ones, instead of reading the local
+    /// wall clock: tests can produce deterministic histories and servers can use a trusted
+    /// clock. It takes precedence over the `timestamp` passed to `commit_with`, and stamps the
+    /// changes even if `set_record_timestamp` wasn't enabled. Exceptions it raises are reported
+    /// with `sys.unraisablehook` and the change keeps its default timestamp.
+    ///
+    /// Pass `None` to remove it.
+    #[pyo3(signature = (callback))]
+    pub fn set_timestamp_provider(&self, callback: Option<Py<PyAny>>) {
+        self.hooks.set_clock(&self.doc, callback);
+    }
+
+    /// Set where the callbacks of `subscribe`, `subscribe_root` and `subscribe_jsonpath` run.
+    ///
+    /// - `"inline"`, the default, calls them during the commit, import or checkout that
+    ///   emits the event.
+    /// - `"thread"` queues them to a thread dedicated to this document, so committing never
+    ///   waits for the GIL on their behalf, e.g. while another thread holds it.
+    /// - A callable, such as `ThreadPoolExecutor.submit` or `queue.Queue.put`, is given each
+    ///   invocation as a function without arguments to run whenever it sees fit. It's called
+    ///   from a thread dedicated to this document too, so committing doesn't wait for the GIL
+    ///   to submit them either.
+    ///
+    /// Exceptions raised on the dispatch thread, including by the callable, are reported with
+    /// `sys.unraisablehook`. The setting applies to existing subscriptions as well. Container
+    /// subscriptions always run inline.
+    pub fn set_dispatcher(&self, dispatcher: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.hooks.dispatcher().set(dispatcher)
+    }
+
     /// Enables editing in detached mode, which is disabled by default.
     ///
     /// The doc enter detached mode after calling `detach` or checking out a non-latest version.
@@ -139,9 +534,16 @@ impl LoroDoc {
     /// - Ensure no concurrent operations share the same PeerID if set manually.
     /// - Importing does not affect the document's state or version; changes are
     ///   recorded in the [OpLog] only. Call `checkout` to apply changes.
+    /// - It can't be enabled on a document made read-only with `set_readonly`.
     #[inline]
-    pub fn set_detached_editing(&self, enable: bool) {
+    pub fn set_detached_editing(&self, enable: bool) -> PyResult<()> {
+        if enable && self.hooks.readonly() != ReadOnly::Writable {
+            return Err(PyValueError::new_err(
+                "cannot enable detached editing on a read-only document",
+            ));
+        }
         self.doc.set_detached_editing(enable);
+        Ok(())
     }
 
     /// Whether editing the doc in detached mode is allowed, which is disabled by
@@ -182,7 +584,22 @@ impl LoroDoc {
     /// beginning or end of the style.
     #[inline]
     pub fn config_text_style(&self, text_style: StyleConfigMap) {
-        self.doc.config_text_style(text_style.0)
+        self.hooks.add_text_style_keys(&text_style.keys);
+        self.doc.config_text_style(text_style.inner)
+    }
+
+    /// Set whether the `datetime.datetime` and `datetime.date` values read from the document
+    /// are converted back.
+    ///
+    /// They are always stored as tagged maps with an ISO 8601 string, e.g.
+    /// `{"$loro_codec": "datetime", "value": "2025-01-01T12:00:00+00:00"}`, so the timezone
+    /// survives the round trip. When decoding is disabled (the default), the tagged map is
+    /// returned as a dict, which keeps reads identical to other Loro implementations.
+    ///
+    /// It applies to the values read through the document and its containers. Values of
+    /// events and of detached containers aren't converted.
+    pub fn set_datetime_decoding(&self, enabled: bool) {
+        self.hooks.set_datetime_decoding(enabled);
     }
 
     /// Configures the default text style for the document.
@@ -194,7 +611,10 @@ impl LoroDoc {
     ///
     /// - `text_style`: The style configuration to set as the default. `None` to reset.
     #[pyo3(signature = (text_style=None))]
-    pub fn config_default_text_style(&self, text_style: Option<ExpandType>) {
+    pub fn config_default_text_style(
+        &self,
+        #[pyo3(from_py_with = ExpandType::from_optional_object)] text_style: Option<ExpandType>,
+    ) {
         self.doc
             .config_default_text_style(text_style.map(|c| loro::StyleConfig { expand: c.into() }));
     }
@@ -207,7 +627,8 @@ impl LoroDoc {
     /// > recorded in the `OpLog` without being applied to the `DocState`.
     #[inline]
     pub fn attach(&self) {
-        self.doc.attach()
+        self.doc.attach();
+        self.hooks.keep_readonly(&self.doc, false);
     }
 
     /// Checkout the `DocState` to a specific version.
@@ -221,6 +642,7 @@ impl LoroDoc {
     #[inline]
     pub fn checkout(&self, frontiers: &Frontiers) -> PyLoroResult<()> {
         self.doc.checkout(&frontiers.into())?;
+        self.hooks.keep_readonly(&self.doc, true);
         Ok(())
     }
 
@@ -234,7 +656,8 @@ impl LoroDoc {
     /// This has the same effect as `attach`.
     #[inline]
     pub fn checkout_to_latest(&self) {
-        self.doc.checkout_to_latest()
+        self.doc.checkout_to_latest();
+        self.hooks.keep_readonly(&self.doc, false);
     }
 
     /// Compare the frontiers with the current OpLog's version.
@@ -245,16 +668,18 @@ impl LoroDoc {
         self.doc.cmp_with_frontiers(&other.into()).into()
     }
 
-    /// Compare two frontiers.
+    /// Compare two frontiers by causal order, [Ordering::Concurrent] if neither includes the
+    /// other.
     ///
-    /// If the frontiers are not included in the document, return [`FrontiersNotIncluded`].
+    /// Both frontiers must be included in the document's history, otherwise a `ValueError`
+    /// is raised.
     #[inline]
-    pub fn cmp_frontiers(&self, a: &Frontiers, b: &Frontiers) -> PyResult<Option<Ordering>> {
+    pub fn cmp_frontiers(&self, a: &Frontiers, b: &Frontiers) -> PyResult<Ordering> {
         let ans = self
             .doc
             .cmp_frontiers(&a.into(), &b.into())
             .map_err(|e| PyValueError::new_err(e.to_string()))?
-            .map(|o| o.into());
+            .map_or(Ordering::Concurrent, Ordering::from);
         Ok(ans)
     }
 
@@ -265,7 +690,8 @@ impl LoroDoc {
     /// Learn more at https://loro.dev/docs/advanced/doc_state_and_oplog#attacheddetached-status
     #[inline]
     pub fn detach(&self) {
-        self.doc.detach()
+        self.doc.detach();
+        self.hooks.keep_readonly(&self.doc, true);
     }
 
     // /// Import a batch of updates/snapshot.
@@ -273,9 +699,36 @@ impl LoroDoc {
     // /// The data can be in arbitrary order. The import result will be the same.
     #[inline]
     pub fn import_batch(&self, bytes: Vec<Bound<'_, PyBytes>>) -> PyLoroResult<ImportStatus> {
-        let vec_bytes: Vec<Vec<u8>> = bytes.into_iter().map(|b| b.as_bytes().to_vec()).collect();
-        let status = self.doc.import_batch(&vec_bytes)?;
-        Ok(ImportStatus::from(status))
+        let vec_bytes = bytes
+            .into_iter()
+            .map(|b| Ok(self.hooks.decrypt(b.as_bytes())?.into_owned()))
+            .collect::<PyLoroResult<Vec<Vec<u8>>>>()?;
+        self.import_decrypted_batch(&vec_bytes)
+    }
+
+    /// Import the files of the directory `path` whose names match `pattern`, e.g. the update
+    /// blobs written by an append-only persistence scheme.
+    ///
+    /// Identical files are imported once. The blobs are imported like `import_batch`: in
+    /// causal order whatever their names, with a single event flush. `pattern` matches the
+    /// whole file name and supports the `*` and `?` wildcards.
+    #[pyo3(signature = (path, pattern="*.bin"))]
+    pub fn import_dir(&self, path: PathBuf, pattern: &str) -> PyLoroResult<ImportStatus> {
+        let mut vec_bytes = Vec::new();
+        for entry in fs::read_dir(&path).map_err(PyErr::from)? {
+            let entry = entry.map_err(PyErr::from)?;
+            let name = entry.file_name();
+            if !entry.file_type().map_err(PyErr::from)?.is_file()
+                || !wildcard_match(pattern, &name.to_string_lossy())
+            {
+                continue;
+            }
+            let bytes = fs::read(entry.path()).map_err(PyErr::from)?;
+            vec_bytes.push(self.hooks.decrypt(&bytes)?.into_owned());
+        }
+        vec_bytes.sort_unstable();
+        vec_bytes.dedup();
+        self.import_decrypted_batch(&vec_bytes)
     }
 
     /// Get a [LoroMovableList] by container id.
@@ -341,9 +794,16 @@ impl LoroDoc {
     /// - `doc.export(mode)` is called.
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
+    ///
+    /// Returns what was committed, or `None` if there were no pending ops. Raises if the
+    /// quota hook set with `set_quota_hook` rejects the commit, leaving the ops pending.
     #[inline]
-    pub fn commit(&self) {
-        self.doc.commit()
+    pub fn commit(&self) -> PyLoroResult<Option<CommitInfo>> {
+        self.hooks.check_commit_quota(&self.doc)?;
+        let measure = self.hooks.measure(&self.doc, "commit");
+        let info = self.committed(|doc| doc.commit());
+        measure.finish(0);
+        Ok(info)
     }
 
     /// Commit the cumulative auto commit transaction with custom configure.
@@ -351,6 +811,9 @@ impl LoroDoc {
     /// There is a transaction behind every operation.
     /// It will automatically commit when users invoke export or import.
     /// The event will be sent after a transaction is committed
+    ///
+    /// Returns what was committed, or `None` if there were no pending ops. Raises if the
+    /// quota hook set with `set_quota_hook` rejects the commit, leaving the ops pending.
     #[pyo3(signature = (origin=None, timestamp=None, immediate_renew=true, commit_msg=None))]
     #[inline]
     pub fn commit_with(
@@ -359,13 +822,19 @@ impl LoroDoc {
         timestamp: Option<i64>,
         immediate_renew: Option<bool>,
         commit_msg: Option<&str>,
-    ) {
-        self.doc.commit_with(loro::CommitOptions {
-            origin: origin.map(|s| s.into()),
-            immediate_renew: immediate_renew.unwrap_or(true),
-            timestamp,
-            commit_msg: commit_msg.map(|s| s.into()),
-        })
+    ) -> PyLoroResult<Option<CommitInfo>> {
+        self.hooks.check_commit_quota(&self.doc)?;
+        let measure = self.hooks.measure(&self.doc, "commit");
+        let info = self.committed(|doc| {
+            doc.commit_with(loro::CommitOptions {
+                origin: origin.map(|s| s.into()),
+                immediate_renew: immediate_renew.unwrap_or(true),
+                timestamp,
+                commit_msg: commit_msg.map(|s| s.into()),
+            })
+        });
+        measure.finish(0);
+        Ok(info)
     }
 
     /// Set commit message for the current uncommitted changes
@@ -418,29 +887,42 @@ impl LoroDoc {
     /// synchronized with the latest version of the [loro_internal::OpLog].
     #[inline]
     pub fn is_detached(&self) -> bool {
-        self.doc.is_detached()
+        self.hooks.is_detached(&self.doc)
     }
 
     /// Import updates/snapshot exported by [`LoroDoc::export_snapshot`] or [`LoroDoc::export_from`].
-    #[pyo3(name = "import_")]
+    ///
+    /// The checksum of the blob is always validated, and a corrupted blob raises.
+    ///
+    /// `verify` sets what happens to a blob depending on changes the document lacks:
+    /// - `"partial"`, the default, applies it partially, with the rest reported in `pending`.
+    /// - `"strict"` rejects it, leaving the document untouched. Finding out imports the blob
+    ///   into a fork of the document, which is O(n). The import filter reuses that fork.
+    ///
+    /// The returned status tells which of them ran and whether anything was left pending.
+    #[pyo3(name = "import_", signature = (bytes, verify=ImportVerification::Partial))]
     #[inline]
-    pub fn import(&self, bytes: Bound<'_, PyBytes>) -> PyLoroResult<ImportStatus> {
-        let status = self.doc.import(bytes.as_bytes())?;
-        Ok(ImportStatus::from(status))
+    pub fn import(
+        &self,
+        bytes: Bound<'_, PyBytes>,
+        #[pyo3(from_py_with = ImportVerification::from_object)] verify: ImportVerification,
+    ) -> PyLoroResult<ImportStatus> {
+        self.import_verified(bytes.as_bytes(), None, verify)
     }
 
     /// Import updates/snapshot exported by [`LoroDoc::export_snapshot`] or [`LoroDoc::export_from`].
     ///
     /// It marks the import with a custom `origin` string. It can be used to track the import source
-    /// in the generated events.
+    /// in the generated events. `verify` works as in `import_`.
+    #[pyo3(signature = (bytes, origin, verify=ImportVerification::Partial))]
     #[inline]
     pub fn import_with(
         &self,
         bytes: Bound<'_, PyBytes>,
         origin: &str,
+        #[pyo3(from_py_with = ImportVerification::from_object)] verify: ImportVerification,
     ) -> PyLoroResult<ImportStatus> {
-        let status = self.doc.import_with(bytes.as_bytes(), origin)?;
-        Ok(ImportStatus::from(status))
+        self.import_verified(bytes.as_bytes(), Some(origin), verify)
     }
 
     /// Import the json schema updates.
@@ -448,18 +930,36 @@ impl LoroDoc {
     /// only supports backward compatibility but not forward compatibility.
     #[inline]
     pub fn import_json_updates(&self, json: String) -> PyLoroResult<ImportStatus> {
+        self.hooks
+            .check_import(&self.doc, &[Updates::Json(&json)])?;
+        let follow = self.hooks.start_import(&self.doc)?;
+        let measure = self.hooks.measure(&self.doc, "import_json_updates");
+        let len = json.len();
         let status = self.doc.import_json_updates(json)?;
+        self.hooks.follow_import(&self.doc, follow, None);
+        self.hooks.track_pending(&self.doc, &status);
+        measure.finish(len);
         Ok(ImportStatus::from(status))
     }
 
     // TODO: return an object
     /// Export the current state with json-string format of the document.
+    ///
+    /// Raise if an encryption callback is set with `set_encryption`.
     #[inline]
-    pub fn export_json_updates(&self, start_vv: VersionVector, end_vv: VersionVector) -> String {
+    pub fn export_json_updates(
+        &self,
+        start_vv: VersionVector,
+        end_vv: VersionVector,
+    ) -> PyLoroResult<String> {
+        self.hooks.check_plain_export()?;
+        let measure = self.hooks.measure(&self.doc, "export_json_updates");
         let json = self
             .doc
             .export_json_updates(&start_vv.into(), &end_vv.into());
-        serde_json::to_string(&json).unwrap()
+        let json = serde_json::to_string(&json).unwrap();
+        measure.finish(json.len());
+        Ok(json)
     }
 
     /// Exports changes within the specified ID span to JSON schema format.
@@ -470,9 +970,12 @@ impl LoroDoc {
     /// This method can also export pending changes from the uncommitted transaction that have not yet been applied to the OpLog.
     ///
     /// This method will NOT trigger a new commit implicitly.
-    pub fn export_json_in_id_span(&self, id_span: IdSpan) -> String {
+    ///
+    /// Raise if an encryption callback is set with `set_encryption`.
+    pub fn export_json_in_id_span(&self, id_span: IdSpan) -> PyLoroResult<String> {
+        self.hooks.check_plain_export()?;
         let json = self.doc.export_json_in_id_span(id_span.into());
-        serde_json::to_string(&json).unwrap()
+        Ok(serde_json::to_string(&json).unwrap())
     }
 
     /// Convert `Frontiers` into `VersionVector`
@@ -565,19 +1068,62 @@ impl LoroDoc {
     /// Get the shallow value of the document.
     #[inline]
     pub fn get_value(&self) -> LoroValue {
-        self.doc.get_value().into()
+        self.read(self.doc.get_value())
     }
 
     /// Get the entire state of the current DocState
     #[inline]
     pub fn get_deep_value(&self) -> LoroValue {
-        self.doc.get_deep_value().into()
+        self.read(self.doc.get_deep_value())
+    }
+
+    /// Whether `other` has the same deep value, compared in Rust without converting the
+    /// states to Python.
+    ///
+    /// If `history` is true, the documents must also have the same history, i.e. the same
+    /// `oplog_vv`. Replicas that imported each other's updates converge to equal documents.
+    #[pyo3(signature = (other, history=false))]
+    pub fn deep_equal(&self, other: &LoroDoc, history: bool) -> bool {
+        (!history || self.doc.oplog_vv() == other.doc.oplog_vv())
+            && self.doc.get_deep_value() == other.doc.get_deep_value()
+    }
+
+    /// A stable hash of the current state, so replicas can check they converged without
+    /// exchanging snapshots.
+    ///
+    /// It's the hash of the deep value, the same across processes, platforms and versions of
+    /// loro-py. Text styles aren't part of the deep value: use `LoroText.state_hash` to
+    /// include them.
+    pub fn state_hash(&self) -> u64 {
+        hash::value_hash(&self.doc.get_deep_value())
+    }
+
+    /// Get the entire state of the current DocState, serialized as msgpack.
+    ///
+    /// It's the same as `loro.to_msgpack(doc.get_deep_value())` without building the
+    /// intermediate Python objects.
+    pub fn get_deep_value_msgpack(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(msgpack::encode(&self.doc.get_deep_value()))
+    }
+
+    /// Get the entire state of the current DocState as a JSON string.
+    ///
+    /// It's serialized in Rust, which is much faster than `json.dumps(doc.get_deep_value())`
+    /// for large documents.
+    #[pyo3(signature = (pretty=false))]
+    pub fn get_deep_value_json(&self, pretty: bool) -> String {
+        let value = self.doc.get_deep_value();
+        if pretty {
+            value.to_json_pretty()
+        } else {
+            value.to_json()
+        }
     }
 
     /// Get the entire state of the current DocState with container id
     #[inline]
     pub fn get_deep_value_with_id(&self) -> LoroValue {
-        self.doc.get_deep_value_with_id().into()
+        self.read(self.doc.get_deep_value_with_id())
     }
 
     /// Get the `Frontiers` version of `OpLog`
@@ -596,6 +1142,30 @@ impl LoroDoc {
         self.doc.state_frontiers().into()
     }
 
+    /// Get the version vectors and frontiers of both the `DocState` and the `OpLog` at once.
+    pub fn version_info(&self) -> VersionInfo {
+        VersionInfo {
+            state_vv: self.doc.state_vv().into(),
+            oplog_vv: self.doc.oplog_vv().into(),
+            state_frontiers: self.doc.state_frontiers().into(),
+            oplog_frontiers: self.doc.oplog_frontiers().into(),
+        }
+    }
+
+    /// Compare the history with the version vector of a remote peer, e.g. to request and
+    /// send the changes needed to catch up.
+    ///
+    /// `missing` are the spans to request from the peer, `ahead` the spans to send to it, which
+    /// can be exported with `ExportMode.UpdatesInRange`.
+    pub fn missing_spans(&self, remote_vv: VersionVector) -> SyncGap {
+        let local = self.doc.oplog_vv();
+        let remote = loro::VersionVector::from(remote_vv);
+        SyncGap {
+            missing: remote.sub_iter(&local).map(IdSpan::from).collect(),
+            ahead: local.sub_iter(&remote).map(IdSpan::from).collect(),
+        }
+    }
+
     /// Get the PeerID
     #[getter]
     #[inline]
@@ -605,13 +1175,15 @@ impl LoroDoc {
 
     /// Change the PeerID
     ///
+    /// A string is turned into a stable PeerID with `peer_id_from_string`.
+    ///
     /// NOTE: You need to make sure there is no chance two peer have the same PeerID.
     /// If it happens, the document will be corrupted.
     #[setter]
     #[inline]
     #[pyo3(name = "peer_id")]
-    pub fn set_peer_id(&self, peer: PeerID) -> PyLoroResult<()> {
-        self.doc.set_peer_id(peer)?;
+    pub fn set_peer_id(&self, peer: &Bound<'_, PyAny>) -> PyLoroResult<()> {
+        self.doc.set_peer_id(peer_id_from_object(peer)?)?;
         Ok(())
     }
 
@@ -627,6 +1199,9 @@ impl LoroDoc {
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
     ///
+    /// Pass a set of `EventTriggerKind`s as `kinds` to receive only the events they trigger,
+    /// e.g. `{EventTriggerKind.Import}` for the remote changes.
+    ///
     /// # Example
     ///
     /// ```
@@ -660,16 +1235,31 @@ impl LoroDoc {
     /// sub.unsubscribe();
     /// ```
     #[inline]
-    pub fn subscribe(&self, container_id: &ContainerID, callback: Py<PyAny>) -> Subscription {
+    #[pyo3(signature = (container_id, callback, kinds=None))]
+    pub fn subscribe(
+        &self,
+        container_id: &ContainerID,
+        callback: Py<PyAny>,
+        #[pyo3(from_py_with = trigger_kinds)] kinds: Option<HashSet<EventTriggerKind>>,
+    ) -> Subscription {
+        let dispatcher = self.hooks.dispatcher();
+        let hooks = self.hooks.clone();
+        let callback = Arc::new(callback);
         let subscription = self.doc.subscribe(
             &container_id.into(),
             Arc::new(move |e| {
-                Python::attach(|py| {
-                    callback.call1(py, (DiffEvent::from(e),)).unwrap();
-                });
+                if !is_wanted(&kinds, hooks.triggered_by(&e)) {
+                    return;
+                }
+                let (callback, event) = (callback.clone(), hooks.event(e));
+                dispatcher.dispatch(Box::new(move |py| {
+                    callback.call1(py, (event,))?;
+                    Ok(())
+                }));
             }),
         );
-        subscription.into()
+        let target = loro::ContainerID::from(container_id).to_string();
+        self.hooks.track(subscription, "container", Some(target))
     }
 
     /// Subscribe all the events.
@@ -683,14 +1273,121 @@ impl LoroDoc {
     /// - `doc.export(mode)` is called.
     /// - `doc.import(data)` is called.
     /// - `doc.checkout(version)` is called.
+    ///
+    /// `kinds` filters the events like in `subscribe`.
     #[inline]
-    pub fn subscribe_root(&self, callback: Py<PyAny>) -> Subscription {
+    #[pyo3(signature = (callback, kinds=None))]
+    pub fn subscribe_root(
+        &self,
+        callback: Py<PyAny>,
+        #[pyo3(from_py_with = trigger_kinds)] kinds: Option<HashSet<EventTriggerKind>>,
+    ) -> Subscription {
+        let dispatcher = self.hooks.dispatcher();
+        let hooks = self.hooks.clone();
+        let callback = Arc::new(callback);
         let subscription = self.doc.subscribe_root(Arc::new(move |e| {
-            Python::attach(|py| {
-                callback.call1(py, (DiffEvent::from(e),)).unwrap();
-            });
+            if !is_wanted(&kinds, hooks.triggered_by(&e)) {
+                return;
+            }
+            let (callback, event) = (callback.clone(), hooks.event(e));
+            dispatcher.dispatch(Box::new(move |py| {
+                callback.call1(py, (event,))?;
+                Ok(())
+            }));
         }));
-        subscription.into()
+        self.hooks.track(subscription, "root", None)
+    }
+
+    /// Subscribe to the next event of the container `container_id`, or of the whole document
+    /// if it's `None`.
+    ///
+    /// The subscription ends once `callback` has received one event.
+    #[pyo3(signature = (container_id, callback))]
+    pub fn subscribe_once(
+        &self,
+        container_id: Option<&ContainerID>,
+        callback: Py<PyAny>,
+    ) -> Subscription {
+        let dispatcher = self.hooks.dispatcher();
+        let hooks = self.hooks.clone();
+        let callback = Arc::new(callback);
+        let fired = AtomicBool::new(false);
+        let slot = Arc::new(OnceLock::<Weak<Mutex<Option<loro::Subscription>>>>::new());
+        let handler: loro::event::Subscriber = {
+            let slot = slot.clone();
+            Arc::new(move |e| {
+                if fired.swap(true, AtomicOrdering::Relaxed) {
+                    return;
+                }
+                // Loro allows unsubscribing while it emits the event.
+                if let Some(subscription) = slot.get().and_then(Weak::upgrade) {
+                    drop(subscription.lock().unwrap().take());
+                }
+                let (callback, event) = (callback.clone(), hooks.event(e));
+                dispatcher.dispatch(Box::new(move |py| {
+                    callback.call1(py, (event,))?;
+                    Ok(())
+                }));
+            })
+        };
+        let (subscription, target) = match container_id {
+            Some(id) => {
+                let id = loro::ContainerID::from(id);
+                (self.doc.subscribe(&id, handler), Some(id.to_string()))
+            }
+            None => (self.doc.subscribe_root(handler), None),
+        };
+        let subscription = self.hooks.track(subscription, "once", target);
+        let _ = slot.set(Arc::downgrade(&subscription.0));
+        subscription
+    }
+
+    /// Wait for the next event of the container `container_id`, or of the whole document if
+    /// it's `None`, e.g. until an import made by another task lands.
+    ///
+    /// Returns an awaitable of the `DiffEvent`, which raises `asyncio.TimeoutError` after
+    /// `timeout` seconds. Must be called from a running event loop; the event may come from
+    /// any thread.
+    #[pyo3(signature = (container_id=None, timeout=None))]
+    pub fn wait_for_change<'py>(
+        &self,
+        py: Python<'py>,
+        container_id: Option<&ContainerID>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let asyncio = py.import("asyncio")?;
+        let event_loop = asyncio.call_method0("get_running_loop")?;
+        let future = event_loop.call_method0("create_future")?;
+        let waiting = future.clone().unbind();
+        let settle = move |args: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| {
+            let future = waiting.bind(args.py());
+            if !future.call_method0("done")?.is_truthy()? {
+                future.call_method1("set_result", (args.get_item(0)?,))?;
+            }
+            PyResult::Ok(())
+        };
+        let settle = PyCFunction::new_closure(py, None, None, settle)?.unbind();
+        let notified_loop = event_loop.clone().unbind();
+        let notify = move |args: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| {
+            let py = args.py();
+            // The loop may be closed once nobody waits for the event anymore.
+            let _ = notified_loop
+                .bind(py)
+                .call_method1("call_soon_threadsafe", (settle.bind(py), args.get_item(0)?));
+            PyResult::Ok(())
+        };
+        let notify = PyCFunction::new_closure(py, None, None, notify)?;
+        let subscription = Py::new(
+            py,
+            self.subscribe_once(container_id, notify.into_any().unbind()),
+        )?;
+        // Keeps the subscription alive until the wait ends, and ends it on a timeout.
+        let done = move |_: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| {
+            subscription.get().unsubscribe();
+        };
+        let done = PyCFunction::new_closure(py, None, None, done)?;
+        future.call_method1("add_done_callback", (done,))?;
+        asyncio.call_method1("wait_for", (future, timeout))
     }
 
     /// Subscribe the local update of the document.
@@ -701,7 +1398,7 @@ impl LoroDoc {
                 b.extract::<bool>(py).unwrap()
             })
         }));
-        subscription.into()
+        self.hooks.track(subscription, "local_update", None)
     }
 
     /// Subscribe the peer id change of the document.
@@ -712,14 +1409,58 @@ impl LoroDoc {
                 b.extract::<bool>(py).unwrap()
             })
         }));
-        subscription.into()
+        self.hooks.track(subscription, "peer_id_change", None)
     }
 
-    // /// Estimate the size of the document states in memory.
-    // #[inline]
-    // pub fn log_estimate_size(&self) {
-    //     self.doc.log_estimated_size();
-    // }
+    /// The subscriptions made through this document that are still active, e.g. to find the
+    /// observers left behind before unloading it.
+    ///
+    /// Subscriptions made on containers or detached with `Subscription.detach` aren't listed.
+    pub fn active_subscriptions(&self) -> Vec<SubscriptionInfo> {
+        self.hooks.active_subscriptions()
+    }
+
+    /// Unsubscribe all the subscriptions listed by `active_subscriptions` and return how many
+    /// there were.
+    pub fn unsubscribe_all(&self) -> usize {
+        self.hooks.unsubscribe_all()
+    }
+
+    /// Get the storage statistics of the document: the size of its state and history, and
+    /// the number of changes, ops and containers.
+    ///
+    /// It exports the document to measure it, so it's O(n). Use it to monitor how documents
+    /// grow and to decide when to trim the history with a shallow snapshot.
+    pub fn storage_stats(&self) -> PyLoroResult<StorageStats> {
+        StorageStats::new(&self.doc)
+    }
+
+    /// Export a shallow snapshot trimming the history, and measure what it saves.
+    ///
+    /// Pass exactly one policy: `keep_last` keeps the last N changes, `keep_since` keeps the
+    /// history since the given `Frontiers`. The document itself is left untouched; load the
+    /// returned `snapshot` in its place to drop the trimmed history. Time travel before
+    /// `shallow_since` isn't possible in the loaded document.
+    #[pyo3(signature = (*, keep_last=None, keep_since=None))]
+    pub fn gc(
+        &self,
+        keep_last: Option<usize>,
+        keep_since: Option<Frontiers>,
+    ) -> PyLoroResult<GcResult> {
+        let frontiers = match (keep_last, keep_since) {
+            (Some(n), None) => history::frontiers_before_last(&self.doc, n),
+            (None, Some(frontiers)) => frontiers.into(),
+            _ => {
+                return Err(PyValueError::new_err(
+                    "pass exactly one of `keep_last` and `keep_since`",
+                )
+                .into())
+            }
+        };
+        let mut result = GcResult::new(&self.doc, frontiers)?;
+        result.snapshot = self.hooks.encrypt(result.snapshot)?;
+        Ok(result)
+    }
 
     // /// Check the correctness of the document state by comparing it with the state
     // /// calculated by applying all the history.
@@ -729,22 +1470,36 @@ impl LoroDoc {
     // }
 
     /// Get the handler by the path.
+    ///
+    /// The steps of the path are `Index`es, or str keys, int positions and `TreeID`s.
     #[inline]
-    pub fn get_by_path(&self, path: Vec<Index>) -> Option<ValueOrContainer> {
+    pub fn get_by_path(
+        &self,
+        #[pyo3(from_py_with = Index::path_from_object)] path: Vec<Index>,
+    ) -> Option<ValueOrContainer> {
         self.doc
             .get_by_path(&path.iter().map(|x| x.into()).collect::<Vec<_>>())
-            .map(ValueOrContainer::from)
+            .map(|v| ValueOrContainer::from(v).decoding_datetime(self.hooks.decodes_datetime()))
     }
 
     /// Get the handler by the string path.
     #[inline]
     pub fn get_by_str_path(&self, path: &str) -> Option<ValueOrContainer> {
-        self.doc.get_by_str_path(path).map(ValueOrContainer::from)
+        self.doc
+            .get_by_str_path(path)
+            .map(|v| ValueOrContainer::from(v).decoding_datetime(self.hooks.decodes_datetime()))
     }
 
     /// Get a container by its ContainerID.
-    pub fn get_container(&self, id: &ContainerID) -> Option<Container> {
-        self.doc.get_container(id.into()).map(|c| c.into())
+    ///
+    /// `id` is either a `ContainerID` or its serialized `"cid:..."` form, as found in
+    /// JSON exports and persisted references. The container is returned with its
+    /// concrete type, or `None` if it doesn't exist in the document.
+    pub fn get_container(
+        &self,
+        #[pyo3(from_py_with = container_id_from_object)] id: loro::ContainerID,
+    ) -> Option<Container> {
+        self.doc.get_container(id).map(|c| c.into())
     }
 
     /// Get the absolute position of the given cursor.
@@ -808,18 +1563,61 @@ impl LoroDoc {
     }
 
     /// Export the document in the given mode.
-    pub fn export(&self, mode: ExportMode) -> PyLoroResult<Cow<'_, [u8]>> {
-        let ans = self.doc.export(mode.into())?;
+    ///
+    /// `mode` is an `ExportMode`, or the name of one with its fields as keywords:
+    /// `"snapshot"`, `"updates"` with an optional `from_` version, `"updates-in-range"`
+    /// with `spans`, `"updates-in-time-range"` with `start_ts` and `end_ts`,
+    /// `"shallow-snapshot"` with `frontiers`, `"state-only"` with optional `frontiers`, or
+    /// `"snapshot-at"` with `version`.
+    ///
+    /// With `compression="zstd"`, the blob is compressed at the zstd `level`, which pays off
+    /// for snapshots of text-heavy documents. Compressed blobs are detected and decompressed
+    /// on import, up to the size set with `set_max_decompressed_size`. They start with a
+    /// `lorozst` header that only these bindings understand: loro-js and the Rust crate can't
+    /// import them.
+    #[pyo3(signature = (mode, compression=None, level=3, **options))]
+    pub fn export(
+        &self,
+        mode: &Bound<'_, PyAny>,
+        compression: Option<&str>,
+        level: i32,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyLoroResult<Cow<'_, [u8]>> {
+        let mode = ExportMode::from_args(mode, options)?;
+        let measure = self.hooks.measure(&self.doc, "export");
+        let ans = compress::compress(
+            self.doc.export(mode.resolve(&self.doc))?,
+            compression,
+            level,
+        )?;
+        let ans = self.hooks.encrypt(ans)?;
+        measure.finish(ans.len());
         Ok(Cow::Owned(ans))
     }
 
-    // /// Analyze the container info of the doc
-    // ///
-    // /// This is used for development and debugging. It can be slow.
-    // // TODO:
-    // // pub fn analyze(&self) -> DocAnalysis {
-    // //     self.doc.analyze()
-    // // }
+    /// Export the updates of `mode` as blobs of at most `max_bytes` each, e.g. to push them
+    /// through a transport that limits the size of its messages.
+    ///
+    /// `mode` is `ExportMode.Snapshot()` for the whole history, `ExportMode.Updates`,
+    /// `ExportMode.UpdatesInRange` or `ExportMode.UpdatesInTimeRange`. The blobs are in causal
+    /// order, so importing them one by one leaves nothing pending. A change too large for a
+    /// blob is split between its ops; an error is raised if a single op doesn't fit.
+    pub fn export_chunks(&self, mode: ExportMode, max_bytes: usize) -> PyLoroResult<Vec<Vec<u8>>> {
+        let measure = self.hooks.measure(&self.doc, "export");
+        let chunks = chunk::export_chunks(self, mode, max_bytes)?;
+        measure.finish(chunks.iter().map(|c| c.len()).sum());
+        Ok(chunks)
+    }
+
+    /// Analyze the container info of the doc
+    ///
+    /// Return the op count, state size, deep size and child count of every container, which
+    /// helps to find the container that makes a document large or slow.
+    ///
+    /// This is used for development and debugging. It can be slow.
+    pub fn analyze(&self) -> DocAnalysis {
+        DocAnalysis::new(&self.doc)
+    }
 
     /// Get the path from the root to the container
     pub fn get_path_to_container(&self, id: &ContainerID) -> Option<Vec<(ContainerID, Index)>> {
@@ -864,7 +1662,12 @@ impl LoroDoc {
     pub fn jsonpath(&self, path: &str) -> PyResult<Vec<ValueOrContainer>> {
         self.doc
             .jsonpath(path)
-            .map(|vec| vec.into_iter().map(|v| v.into()).collect())
+            .map(|vec| {
+                let decode_datetime = self.hooks.decodes_datetime();
+                vec.into_iter()
+                    .map(|v| ValueOrContainer::from(v).decoding_datetime(decode_datetime))
+                    .collect()
+            })
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
@@ -873,14 +1676,19 @@ impl LoroDoc {
     /// The callback may fire false positives; it is intended as a lightweight notification so
     /// callers can debounce or throttle before running an expensive JSONPath query themselves.
     pub fn subscribe_jsonpath(&self, path: &str, callback: Py<PyAny>) -> PyLoroResult<Subscription> {
+        let dispatcher = self.hooks.dispatcher();
+        let callback = Arc::new(callback);
         let subscription = self
             .doc
             .subscribe_jsonpath(path, Arc::new(move || {
-                Python::attach(|py| {
-                    callback.call0(py).unwrap();
-                });
+                let callback = callback.clone();
+                dispatcher.dispatch(Box::new(move |py| {
+                    callback.call0(py)?;
+                    Ok(())
+                }));
             }))?;
-        Ok(subscription.into())
+        let target = Some(path.to_string());
+        Ok(self.hooks.track(subscription, "jsonpath", target))
     }
 
     /// Get the number of operations in the pending transaction.
@@ -979,6 +1787,16 @@ impl LoroDoc {
         self.doc.has_container(&id.into())
     }
 
+    /// Check if the target container was deleted, e.g. because its parent container or the
+    /// map entry holding it was removed.
+    ///
+    /// Returns `False` if the doc doesn't contain the container, see `has_container`.
+    pub fn is_container_deleted(&self, id: &ContainerID) -> bool {
+        self.doc
+            .get_container(id.into())
+            .is_some_and(|container| container.is_deleted())
+    }
+
     /// Find the operation id spans that between the `from` version and the `to` version.
     pub fn find_id_spans_between(&self, from: &Frontiers, to: &Frontiers) -> VersionVectorDiff {
         self.doc
@@ -1002,7 +1820,8 @@ impl LoroDoc {
                     b.extract::<bool>(py).unwrap()
                 })
             }));
-        subscription.into()
+        self.hooks
+            .track(subscription, "first_commit_from_peer", None)
     }
 
     /// Subscribe to the pre-commit event.
@@ -1025,11 +1844,14 @@ impl LoroDoc {
                 b.extract::<bool>(py).unwrap()
             })
         }));
-        subscription.into()
+        self.hooks.track(subscription, "pre_commit", None)
     }
 
     /// Set whether to hide empty root containers.
     ///
+    /// Root containers are created on first access, so reading a root that was never
+    /// written leaves an empty container in `get_deep_value()`. Hidden, they don't show up.
+    ///
     /// # Example
     /// ```
     /// use loro::LoroDoc;
@@ -1050,9 +1872,29 @@ impl LoroDoc {
     /// - It won't show up in `get_deep_value()` results
     /// - It won't be included in document snapshots
     ///
-    /// Only works on root containers (containers without parents).
-    pub fn delete_root_container(&self, cid: ContainerID) {
-        self.doc.delete_root_container(cid.into());
+    /// Only works on root containers (containers without parents). `cid` is the ContainerID
+    /// of the container, or its name if `container_type` is given, e.g.
+    /// `doc.delete_root_container("drafts", ContainerType.Map())`.
+    #[pyo3(signature = (cid, container_type=None))]
+    pub fn delete_root_container(
+        &self,
+        cid: &Bound<'_, PyAny>,
+        container_type: Option<ContainerType>,
+    ) -> PyResult<()> {
+        let cid = match container_type {
+            Some(container_type) => {
+                loro::ContainerID::new_root(&cid.extract::<String>()?, container_type.into())
+            }
+            None => cid.extract::<ContainerID>()?.into(),
+        };
+        if !cid.is_root() {
+            return Err(PyValueError::new_err(format!(
+                "{} isn't a root container",
+                cid
+            )));
+        }
+        self.doc.delete_root_container(cid);
+        Ok(())
     }
 
     /// Redacts sensitive content in JSON updates within the specified version range.
@@ -1078,6 +1920,206 @@ impl LoroDoc {
             .map_err(|e| PyLoroError::Error(e.to_string()))?;
         Ok(serde_json::to_string(&schema).unwrap())
     }
+
+    /// Apply a batch of edits across containers in a single call, then commit them.
+    ///
+    /// Each op is a `(target, op, args)` tuple:
+    ///
+    /// - `target`: a container, a `ContainerID` or a path accepted by `get_by_str_path`.
+    /// - `op`: the name of the edit, e.g. `"insert"`, `"delete"`, `"push"`, `"mark"`,
+    ///   `"set"`, `"mov"`, `"increment"` or `"create"`.
+    /// - `args`: the arguments of the edit, in the same order as the container method.
+    ///
+    /// Ops that were already pending are committed first, so the batch's change only holds
+    /// its own ops. The batch is committed like `commit_with`, so the quota hook set with
+    /// `set_quota_hook` can reject it, leaving its ops pending.
+    ///
+    /// If an op fails, the error is raised after committing the ops applied before it. The
+    /// raised exception is the one of the op, with a note giving the index of the op.
+    #[pyo3(signature = (ops, origin=None, commit_msg=None))]
+    pub fn apply_ops(
+        &self,
+        py: Python<'_>,
+        ops: Vec<OpDescriptor<'_>>,
+        origin: Option<&str>,
+        commit_msg: Option<&str>,
+    ) -> PyResult<()> {
+        self.commit()?;
+        let mut result = Ok(());
+        for (i, OpDescriptor(target, op, args)) in ops.iter().enumerate() {
+            let applied = batch::resolve_target(&self.doc, target)
+                .map_err(PyLoroError::from)
+                .and_then(|container| batch::apply_op(&container, op, args));
+            if let Err(e) = applied {
+                let err = PyErr::from(e);
+                batch::add_note(py, &err, &format!("op #{i} ('{op}') failed"))?;
+                result = Err(err);
+                break;
+            }
+        }
+        self.commit_with(origin, None, Some(true), commit_msg)?;
+        result
+    }
+}
+
+impl LoroDoc {
+    /// Wrap a value read from the document, see `set_datetime_decoding`.
+    fn read(&self, value: loro::LoroValue) -> LoroValue {
+        LoroValue::from(value).decoding_datetime(self.hooks.decodes_datetime())
+    }
+
+    /// Wrap a new document, with the next PeerID of `set_rng_seed` if it's seeded.
+    fn seeded(doc: LoroDocInner) -> Self {
+        if let Some(peer) = peer::next_peer_id() {
+            // Only `PeerID::MAX` is rejected, which the generator skips.
+            doc.set_peer_id(peer).unwrap();
+        }
+        doc.into()
+    }
+
+    /// Run `commit` and describe the change it produced, if any.
+    fn committed(&self, commit: impl FnOnce(&LoroDocInner)) -> Option<CommitInfo> {
+        // The pending ops are already counted by the oplog version, they're the last ones of
+        // the peer.
+        let ops = self.doc.get_pending_txn_len();
+        commit(&self.doc);
+        if ops == 0 || self.doc.get_pending_txn_len() != 0 {
+            return None;
+        }
+        let peer = self.doc.peer_id();
+        let end = self.doc.oplog_vv().get(&peer).copied().unwrap_or(0);
+        Some(CommitInfo {
+            span: loro::IdSpan::new(peer, end - ops as Counter, end).into(),
+            frontiers: self.doc.oplog_frontiers().into(),
+            ops,
+        })
+    }
+
+    fn import_verified(
+        &self,
+        bytes: &[u8],
+        origin: Option<&str>,
+        verify: ImportVerification,
+    ) -> PyLoroResult<ImportStatus> {
+        let bytes = self.hooks.decrypt(bytes)?;
+        let updates = [Updates::Blob(&bytes)];
+        match verify {
+            ImportVerification::Partial => self.hooks.check_import(&self.doc, &updates)?,
+            ImportVerification::Strict => {
+                let trial = TrialImport::new(&self.doc, &updates)?;
+                if !trial.is_complete() {
+                    return Err(PyLoroError::Error(
+                        "the blob depends on changes missing from the document".to_string(),
+                    ));
+                }
+                self.hooks.check_trial_import(&self.doc, &updates, trial)?;
+            }
+        }
+        let follow = self.hooks.start_import(&self.doc)?;
+        let measure = self.hooks.measure(&self.doc, "import");
+        let status = match origin {
+            Some(origin) => self.doc.import_with(&bytes, origin)?,
+            None => self.doc.import(&bytes)?,
+        };
+        self.hooks.follow_import(&self.doc, follow, origin);
+        self.hooks.track_pending(&self.doc, &status);
+        measure.finish(bytes.len());
+        Ok(ImportStatus {
+            verify: Some(verify.as_str().to_string()),
+            ..ImportStatus::from(status)
+        })
+    }
+
+    fn import_decrypted_batch(&self, vec_bytes: &[Vec<u8>]) -> PyLoroResult<ImportStatus> {
+        let updates = vec_bytes
+            .iter()
+            .map(|bytes| Updates::Blob(bytes))
+            .collect::<Vec<_>>();
+        self.hooks.check_import(&self.doc, &updates)?;
+        let follow = self.hooks.start_import(&self.doc)?;
+        let measure = self.hooks.measure(&self.doc, "import_batch");
+        let mut status = self.doc.import_batch(vec_bytes)?;
+        settle_pending(&self.doc, &mut status);
+        self.hooks.follow_import(&self.doc, follow, None);
+        self.hooks.track_pending(&self.doc, &status);
+        measure.finish(vec_bytes.iter().map(|b| b.len()).sum());
+        Ok(ImportStatus {
+            verify: Some(ImportVerification::Partial.as_str().to_string()),
+            ..ImportStatus::from(status)
+        })
+    }
+}
+
+/// `import_batch` reports the changes left pending by each blob, including the ones a later
+/// blob of the batch provided the dependencies of. Keep only those that are still pending.
+fn settle_pending(doc: &LoroDocInner, status: &mut loro::ImportStatus) {
+    let Some(pending) = &status.pending else {
+        return;
+    };
+    let vv = doc.oplog_vv();
+    let pending: FxHashMap<_, _> = pending
+        .iter()
+        .filter_map(|(&peer, &(start, end))| {
+            let start = start.max(vv.get(&peer).copied().unwrap_or(0));
+            (start < end).then_some((peer, (start, end)))
+        })
+        .collect();
+    status.pending = (!pending.is_empty()).then(|| loro::VersionRange::from_map(pending));
+}
+
+/// Accept any iterable of `EventTriggerKind`s for the `kinds` of the subscribe methods.
+fn trigger_kinds(ob: &Bound<'_, PyAny>) -> PyResult<Option<HashSet<EventTriggerKind>>> {
+    if ob.is_none() {
+        return Ok(None);
+    }
+    let kinds = ob.try_iter()?.map(|kind| kind?.extract());
+    kinds.collect::<PyResult<_>>().map(Some)
+}
+
+/// Accept either a `ContainerID` or its `"cid:..."` string form.
+fn container_id_from_object(ob: &Bound<'_, PyAny>) -> PyResult<loro::ContainerID> {
+    if let Ok(id) = ob.downcast::<ContainerID>() {
+        return Ok(id.get().into());
+    }
+    let Ok(id) = ob.extract::<String>() else {
+        return Err(PyTypeError::new_err("expected a ContainerID or 'cid:' str"));
+    };
+    loro::ContainerID::try_from(id.as_str())
+        .map_err(|_| PyValueError::new_err(format!("invalid container id '{id}'")))
+}
+
+/// Whether an event triggered by `kind` passes the `kinds` filter of a subscription.
+fn is_wanted(kinds: &Option<HashSet<EventTriggerKind>>, kind: loro::EventTriggerKind) -> bool {
+    match kinds {
+        Some(kinds) => kinds.contains(&kind.into()),
+        None => true,
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and `?` any
+/// single character.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The position after the last `*` and the name position it's matched up to
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some((after_star, matched)) = star {
+            p = after_star;
+            n = matched + 1;
+            star = Some((after_star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 #[derive(Debug, IntoPyObject)]
@@ -1114,41 +2156,49 @@ impl Display for PreCommitCallbackPayload {
 }
 
 #[pyclass(frozen)]
-pub struct Configure(pub loro::Configure);
+pub struct Configure {
+    inner: loro::Configure,
+    /// The keys set with `LoroDoc.config_text_style`, shared with the hooks of the document.
+    text_style_keys: Arc<Mutex<Vec<String>>>,
+}
 
 #[pymethods]
 impl Configure {
     #[new]
     pub fn default() -> Self {
-        Self(loro::Configure::default())
+        Self {
+            inner: loro::Configure::default(),
+            text_style_keys: Default::default(),
+        }
     }
 
     pub fn text_style_config(&self) -> StyleConfigMap {
-        StyleConfigMap(self.0.text_style_config().read().unwrap().clone())
+        let inner = self.inner.text_style_config().read().unwrap().clone();
+        StyleConfigMap::with_keys(inner, &self.text_style_keys.lock().unwrap())
     }
 
     pub fn record_timestamp(&self) -> bool {
-        self.0.record_timestamp()
+        self.inner.record_timestamp()
     }
 
     pub fn set_record_timestamp(&self, record: bool) {
-        self.0.set_record_timestamp(record);
+        self.inner.set_record_timestamp(record);
     }
 
     pub fn detached_editing(&self) -> bool {
-        self.0.detached_editing()
+        self.inner.detached_editing()
     }
 
     pub fn set_detached_editing(&self, mode: bool) {
-        self.0.set_detached_editing(mode);
+        self.inner.set_detached_editing(mode);
     }
 
     pub fn merge_interval(&self) -> i64 {
-        self.0.merge_interval()
+        self.inner.merge_interval()
     }
 
     pub fn set_merge_interval(&self, interval: i64) {
-        self.0.set_merge_interval(interval);
+        self.inner.set_merge_interval(interval);
     }
 }
 
@@ -1157,6 +2207,9 @@ impl Configure {
 pub struct ImportStatus {
     pub success: VersionRange,
     pub pending: Option<VersionRange>,
+    /// The `verify` mode the blobs were imported with, `"partial"` or `"strict"`, or `None`
+    /// for JSON updates. The checksum of a blob is validated in both modes.
+    pub verify: Option<String>,
 }
 
 impl From<loro::ImportStatus> for ImportStatus {
@@ -1165,6 +2218,79 @@ impl From<loro::ImportStatus> for ImportStatus {
         Self {
             success: a.into(),
             pending: value.pending.map(|x| x.into()),
+            verify: None,
+        }
+    }
+}
+
+#[pymethods]
+impl ImportStatus {
+    /// Whether everything was applied, with nothing left pending.
+    #[getter]
+    pub fn complete(&self) -> bool {
+        self.pending.is_none()
+    }
+}
+
+/// The change produced by `LoroDoc.commit` or `LoroDoc.commit_with`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    /// The ids of the committed ops.
+    pub span: IdSpan,
+    /// The frontiers of the history right after the commit.
+    pub frontiers: Frontiers,
+    /// The number of committed ops.
+    pub ops: usize,
+}
+
+impl Display for CommitInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CommitInfo(span={}, frontiers={}, ops={})",
+            self.span,
+            self.frontiers.__repr__(),
+            self.ops
+        )
+    }
+}
+
+#[pymethods]
+impl CommitInfo {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// What `LoroDoc.import_` does with a blob that can't be applied in full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportVerification {
+    Partial,
+    Strict,
+}
+
+impl ImportVerification {
+    /// Accept `"partial"` or `"strict"`.
+    pub fn from_object(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let verify = ob
+            .downcast::<PyString>()
+            .ok()
+            .map(|s| s.to_str())
+            .transpose()?;
+        match verify {
+            Some("partial") => Ok(ImportVerification::Partial),
+            Some("strict") => Ok(ImportVerification::Strict),
+            _ => Err(PyValueError::new_err(
+                "verify must be 'partial' or 'strict', the checksum of a blob is always validated",
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportVerification::Partial => "partial",
+            ImportVerification::Strict => "strict",
         }
     }
 }
@@ -1211,6 +2337,192 @@ pub enum ExportMode {
     ShallowSnapshot { frontiers: Frontiers },
     StateOnly { frontiers: Option<Frontiers> },
     SnapshotAt { version: Frontiers },
+    UpdatesInTimeRange { start_ts: i64, end_ts: i64 },
+}
+
+#[pymethods]
+impl ExportMode {
+    /// Export the whole document.
+    #[classmethod]
+    pub fn snapshot(_cls: &Bound<'_, PyType>) -> Self {
+        ExportMode::Snapshot {}
+    }
+
+    /// Export the changes missing from `from_`, all of them by default.
+    #[classmethod]
+    #[pyo3(signature = (from_=None))]
+    pub fn updates(_cls: &Bound<'_, PyType>, from_: Option<VersionVector>) -> Self {
+        ExportMode::Updates {
+            from_: from_.unwrap_or_else(|| loro::VersionVector::default().into()),
+        }
+    }
+
+    /// Export the changes within `spans`.
+    #[classmethod]
+    pub fn updates_in_range(_cls: &Bound<'_, PyType>, spans: Vec<IdSpan>) -> Self {
+        ExportMode::UpdatesInRange { spans }
+    }
+
+    /// Export the changes with `start_ts <= timestamp < end_ts`, in Unix seconds.
+    ///
+    /// Raises `ValueError` if `start_ts` is after `end_ts`.
+    #[classmethod]
+    pub fn updates_in_time_range(
+        _cls: &Bound<'_, PyType>,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> PyResult<Self> {
+        Self::time_range(start_ts, end_ts)
+    }
+
+    /// Export a snapshot without the history before `frontiers`.
+    ///
+    /// Raises `ValueError` if `frontiers` is empty.
+    #[classmethod]
+    pub fn shallow_snapshot(_cls: &Bound<'_, PyType>, frontiers: Frontiers) -> PyResult<Self> {
+        Self::shallow(frontiers)
+    }
+
+    /// Export the state at `frontiers`, the latest by default, without history.
+    #[classmethod]
+    #[pyo3(signature = (frontiers=None))]
+    pub fn state_only(_cls: &Bound<'_, PyType>, frontiers: Option<Frontiers>) -> Self {
+        ExportMode::StateOnly { frontiers }
+    }
+
+    /// Export a snapshot of the document at `version`.
+    #[classmethod]
+    pub fn snapshot_at(_cls: &Bound<'_, PyType>, version: Frontiers) -> Self {
+        ExportMode::SnapshotAt { version }
+    }
+}
+
+impl ExportMode {
+    fn time_range(start_ts: i64, end_ts: i64) -> PyResult<Self> {
+        if start_ts > end_ts {
+            return Err(PyValueError::new_err(format!(
+                "start_ts {start_ts} is after end_ts {end_ts}"
+            )));
+        }
+        Ok(ExportMode::UpdatesInTimeRange { start_ts, end_ts })
+    }
+
+    fn shallow(frontiers: Frontiers) -> PyResult<Self> {
+        if loro::Frontiers::from(&frontiers).is_empty() {
+            return Err(PyValueError::new_err(
+                "a shallow snapshot needs non-empty frontiers to start from",
+            ));
+        }
+        Ok(ExportMode::ShallowSnapshot { frontiers })
+    }
+
+    /// Convert to the mode of `loro`, replacing `UpdatesInTimeRange` with the spans of the
+    /// changes with `start_ts <= timestamp < end_ts`.
+    pub(crate) fn resolve(self, doc: &LoroDocInner) -> loro::ExportMode<'static> {
+        match self {
+            ExportMode::Snapshot {} => loro::ExportMode::Snapshot,
+            ExportMode::Updates { from_ } => loro::ExportMode::Updates {
+                from: Cow::Owned(from_.into()),
+            },
+            ExportMode::UpdatesInRange { spans } => loro::ExportMode::UpdatesInRange {
+                spans: Cow::Owned(spans.into_iter().map(|s| s.into()).collect()),
+            },
+            ExportMode::ShallowSnapshot { frontiers } => {
+                loro::ExportMode::ShallowSnapshot(Cow::Owned(frontiers.into()))
+            }
+            ExportMode::StateOnly { frontiers } => {
+                loro::ExportMode::StateOnly(frontiers.map(|f| Cow::Owned(f.into())))
+            }
+            ExportMode::SnapshotAt { version } => loro::ExportMode::SnapshotAt {
+                version: Cow::Owned(version.into()),
+            },
+            ExportMode::UpdatesInTimeRange { start_ts, end_ts } => {
+                let spans = history::changes_between(doc, start_ts, end_ts)
+                    .into_iter()
+                    .map(|c| {
+                        loro::IdSpan::new(c.id.peer, c.id.counter, c.id.counter + c.len as Counter)
+                    })
+                    .collect();
+                loro::ExportMode::UpdatesInRange {
+                    spans: Cow::Owned(spans),
+                }
+            }
+        }
+    }
+
+    /// Get the mode from an `ExportMode`, or from its name and its fields as keywords, e.g.
+    /// `"updates", from_=vv`.
+    pub(crate) fn from_args<'py>(
+        mode: &Bound<'py, PyAny>,
+        options: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Self> {
+        let Ok(name) = mode.downcast::<PyString>() else {
+            if options.is_some_and(|o| !o.is_empty()) {
+                return Err(PyTypeError::new_err(
+                    "export options are only accepted with a mode name, e.g. 'snapshot'",
+                ));
+            }
+            return mode.extract();
+        };
+        let name = name.to_str()?;
+        let fields: &[&str] = match name {
+            "snapshot" => &[],
+            "updates" => &["from_"],
+            "updates-in-range" => &["spans"],
+            "updates-in-time-range" => &["start_ts", "end_ts"],
+            "shallow-snapshot" | "state-only" => &["frontiers"],
+            "snapshot-at" => &["version"],
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown export mode '{name}', expected 'snapshot', 'updates', \
+                     'updates-in-range', 'updates-in-time-range', 'shallow-snapshot', \
+                     'state-only' or 'snapshot-at'"
+                )))
+            }
+        };
+        if let Some(options) = options {
+            for key in options.keys() {
+                let key = key.extract::<String>()?;
+                if !fields.contains(&key.as_str()) {
+                    return Err(PyTypeError::new_err(format!(
+                        "unexpected option '{key}' for export mode '{name}'"
+                    )));
+                }
+            }
+        }
+        let option = |key: &str| -> PyResult<Option<Bound<'py, PyAny>>> {
+            let value = options.map(|o| o.get_item(key)).transpose()?.flatten();
+            Ok(value.filter(|v| !v.is_none()))
+        };
+        let required = |key: &str| {
+            option(key)?.ok_or_else(|| {
+                PyTypeError::new_err(format!("export mode '{name}' needs the option '{key}'"))
+            })
+        };
+        Ok(match name {
+            "snapshot" => ExportMode::Snapshot {},
+            "updates" => ExportMode::Updates {
+                from_: match option("from_")? {
+                    Some(from) => from.extract()?,
+                    None => loro::VersionVector::default().into(),
+                },
+            },
+            "updates-in-range" => ExportMode::UpdatesInRange {
+                spans: required("spans")?.extract()?,
+            },
+            "updates-in-time-range" => Self::time_range(
+                required("start_ts")?.extract()?,
+                required("end_ts")?.extract()?,
+            )?,
+            "shallow-snapshot" => Self::shallow(required("frontiers")?.extract()?)?,
+            "state-only" => ExportMode::StateOnly {
+                frontiers: option("frontiers")?.map(|f| f.extract()).transpose()?,
+            },
+            _ => ExportMode::SnapshotAt {
+                version: required("version")?.extract()?,
+            },
+        })
+    }
 }
 
 /// This struct supports reverse repr: [CounterSpan]'s from can be less than to. But we should use it conservatively.
@@ -1280,6 +2592,32 @@ pub struct ChangeMeta {
     pub len: usize,
 }
 
+#[pymethods]
+impl ChangeMeta {
+    /// The timestamp of the change as a UTC `datetime.datetime`.
+    #[getter]
+    pub fn datetime<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let datetime = py.import("datetime")?;
+        let utc = datetime.getattr("timezone")?.getattr("utc")?;
+        datetime
+            .getattr("datetime")?
+            .call_method1("fromtimestamp", (self.timestamp, utc))
+    }
+
+    /// The commit message parsed as JSON, or `None` if the change has no message.
+    ///
+    /// Raises `ValueError` if the message isn't valid JSON.
+    #[getter]
+    pub fn message_json<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some(message) = &self.message else {
+            return Ok(None);
+        };
+        py.import("json")?
+            .call_method1("loads", (message,))
+            .map(Some)
+    }
+}
+
 impl Display for ChangeMeta {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -1324,43 +2662,158 @@ pub enum EncodedBlobMode {
     Updates,
 }
 
+/// The keys looked up when wrapping a map that comes from loro, which can't list its keys,
+/// on top of the ones set with `LoroDoc.config_text_style`. They cover the default rich text
+/// config.
+const KNOWN_STYLE_KEYS: &[&str] = &[
+    "bold",
+    "italic",
+    "underline",
+    "strike",
+    "strikethrough",
+    "link",
+    "code",
+    "highlight",
+    "comment",
+];
+
 #[pyclass(str)]
 #[derive(Debug, Clone, Default)]
-pub struct StyleConfigMap(loro::StyleConfigMap);
+pub struct StyleConfigMap {
+    inner: loro::StyleConfigMap,
+    /// The keys in insertion order, as loro's map can't be iterated.
+    keys: Vec<String>,
+}
+
+impl From<loro::StyleConfigMap> for StyleConfigMap {
+    fn from(inner: loro::StyleConfigMap) -> Self {
+        Self::with_keys(inner, &[])
+    }
+}
+
+impl StyleConfigMap {
+    /// Wrap a map that comes from loro, listing the `keys` it was configured with and the
+    /// known style keys it has.
+    fn with_keys(inner: loro::StyleConfigMap, keys: &[String]) -> Self {
+        let mut listed: Vec<String> = Vec::new();
+        let known = KNOWN_STYLE_KEYS.iter().map(|key| key.to_string());
+        for key in keys.iter().cloned().chain(known) {
+            if inner.get(&key.as_str().into()).is_some() && !listed.contains(&key) {
+                listed.push(key);
+            }
+        }
+        Self {
+            inner,
+            keys: listed,
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, ExpandType)> {
+        self.keys
+            .iter()
+            .filter_map(|key| Some((key.clone(), self.get(key)?)))
+            .collect()
+    }
+}
 
 #[pymethods]
 impl StyleConfigMap {
+    /// Create a config, optionally from a dict such as `{"bold": ExpandType.After}`.
+    ///
+    /// The values can also be the names of the expand types: `"before"`, `"after"`, `"both"`
+    /// or `"none"`.
     #[new]
-    pub fn new() -> Self {
-        Self(loro::StyleConfigMap::new())
+    #[pyo3(signature = (styles=None))]
+    pub fn new(styles: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut ans = Self::default();
+        for (key, value) in styles.into_iter().flatten() {
+            ans.insert(key.extract()?, ExpandType::from_object(&value)?)?;
+        }
+        Ok(ans)
     }
 
-    pub fn insert(&mut self, key: String, value: ExpandType) {
+    /// Set how the style `key` expands. Raises `ValueError` if the key contains `':'`.
+    pub fn insert(
+        &mut self,
+        key: String,
+        #[pyo3(from_py_with = ExpandType::from_object)] value: ExpandType,
+    ) -> PyResult<()> {
         if key.contains(':') {
-            panic!("style key should not contain ':'");
+            return Err(PyValueError::new_err(format!(
+                "style key '{key}' should not contain ':'"
+            )));
         }
 
-        self.0.insert(
-            key.into(),
+        self.inner.insert(
+            key.as_str().into(),
             loro::StyleConfig {
                 expand: value.into(),
             },
         );
+        if !self.keys.contains(&key) {
+            self.keys.push(key);
+        }
+        Ok(())
     }
 
     pub fn get(&self, key: &str) -> Option<ExpandType> {
-        self.0.get(&key.into()).map(|x| x.expand.into())
+        self.inner.get(&key.into()).map(|x| x.expand.into())
+    }
+
+    /// The configured style keys, in insertion order.
+    ///
+    /// For a config read from a document, these are the keys set with `config_text_style`
+    /// and the keys of the default rich text config.
+    pub fn keys(&self) -> Vec<String> {
+        self.entries().into_iter().map(|(key, _)| key).collect()
+    }
+
+    /// The config as a dict from style key to [ExpandType].
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, value) in self.entries() {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict)
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.entries().len()
+    }
+
+    pub fn __contains__(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, self.keys())?.try_iter()
+    }
+
+    pub fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        let Ok(other) = other.extract::<PyRef<StyleConfigMap>>() else {
+            return false;
+        };
+        let mut a = self.entries();
+        let mut b = other.entries();
+        a.sort_unstable_by(|x, y| x.0.cmp(&y.0));
+        b.sort_unstable_by(|x, y| x.0.cmp(&y.0));
+        a == b
     }
 
     #[classmethod]
     pub fn default_rich_text_config(_cls: &Bound<'_, PyType>) -> Self {
-        Self(loro::StyleConfigMap::default_rich_text_config())
+        loro::StyleConfigMap::default_rich_text_config().into()
     }
 }
 
 impl Display for StyleConfigMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        let entries = self
+            .entries()
+            .into_iter()
+            .map(|(key, value)| format!("'{key}': {value:?}"))
+            .collect::<Vec<_>>();
+        write!(f, "StyleConfigMap({{{}}})", entries.join(", "))
     }
 }
 
@@ -1378,3 +2831,55 @@ pub enum ExpandType {
     Both,
     Null,
 }
+
+#[pymethods]
+impl ExpandType {
+    /// Parse the name of an expand type: `"before"`, `"after"`, `"both"` or `"none"`
+    /// (case-insensitive), `"none"` standing for [ExpandType::Null].
+    #[staticmethod]
+    pub fn from_str(s: &str) -> PyResult<Self> {
+        Self::parse(s)
+    }
+
+    /// The name of the expand type, as accepted by `from_str`.
+    pub fn __str__(&self) -> &'static str {
+        match self {
+            Self::Before => "before",
+            Self::After => "after",
+            Self::Both => "both",
+            Self::Null => "none",
+        }
+    }
+}
+
+impl ExpandType {
+    /// Extract an [ExpandType] or its name, for the arguments that accept both.
+    pub fn from_object(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match obj.extract::<Self>() {
+            Ok(value) => Ok(value),
+            Err(e) => match obj.extract::<&str>() {
+                Ok(s) => Self::parse(s),
+                Err(_) => Err(e),
+            },
+        }
+    }
+
+    pub fn from_optional_object(obj: &Bound<'_, PyAny>) -> PyResult<Option<Self>> {
+        if obj.is_none() {
+            return Ok(None);
+        }
+        Self::from_object(obj).map(Some)
+    }
+
+    pub fn parse(s: &str) -> PyResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "before" => Ok(Self::Before),
+            "after" => Ok(Self::After),
+            "both" => Ok(Self::Both),
+            "none" | "null" => Ok(Self::Null),
+            _ => Err(PyValueError::new_err(format!(
+                "invalid expand type '{s}', expected 'before', 'after', 'both' or 'none'"
+            ))),
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use loro::{CannotFindRelativePosition, ChangeTravelError, LoroEncodeError};
-use pyo3::{exceptions::PyBaseException, PyErr};
+use loro::{CannotFindRelativePosition, ChangeTravelError, ContainerTrait, LoroEncodeError};
+use pyo3::{exceptions::PyBaseException, prelude::*, IntoPyObjectExt, PyErr};
+
+use crate::value::{ContainerID, TreeID};
 
 pub enum PyLoroError {
     LoroError(loro::LoroError),
@@ -8,6 +10,7 @@ pub enum PyLoroError {
     ChangeTravelError(ChangeTravelError),
     PyError(PyErr),
     Error(String),
+    WithContext(Box<PyLoroError>, Box<ErrorContext>),
 }
 
 pub type PyLoroResult<T> = Result<T, PyLoroError>;
@@ -45,6 +48,11 @@ impl From<PyLoroError> for PyErr {
             PyLoroError::ChangeTravelError(e) => PyBaseException::new_err(e.to_string()),
             PyLoroError::PyError(e) => e,
             PyLoroError::Error(e) => PyBaseException::new_err(e),
+            PyLoroError::WithContext(e, context) => {
+                let err = PyErr::from(*e);
+                Python::attach(|py| context.attach(py, &err));
+                err
+            }
         }
     }
 }
@@ -54,3 +62,144 @@ impl From<PyErr> for PyLoroError {
         Self::PyError(value)
     }
 }
+
+/// The index or key a failed operation was targeting.
+pub enum ErrorIndex {
+    Seq(usize),
+    Key(String),
+    Node(loro::TreeID),
+}
+
+impl From<usize> for ErrorIndex {
+    fn from(value: usize) -> Self {
+        Self::Seq(value)
+    }
+}
+
+impl From<&str> for ErrorIndex {
+    fn from(value: &str) -> Self {
+        Self::Key(value.to_string())
+    }
+}
+
+impl From<TreeID> for ErrorIndex {
+    fn from(value: TreeID) -> Self {
+        Self::Node(value.into())
+    }
+}
+
+impl From<&loro::Index> for ErrorIndex {
+    fn from(value: &loro::Index) -> Self {
+        match value {
+            loro::Index::Key(key) => Self::Key(key.to_string()),
+            loro::Index::Seq(index) => Self::Seq(*index),
+            loro::Index::Node(target) => Self::Node(*target),
+        }
+    }
+}
+
+impl ErrorIndex {
+    fn to_py(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self {
+            ErrorIndex::Seq(index) => index.into_py_any(py),
+            ErrorIndex::Key(key) => key.into_py_any(py),
+            ErrorIndex::Node(target) => TreeID::from(*target).into_py_any(py),
+        }
+    }
+}
+
+/// Where a failed operation happened.
+///
+/// It's exposed on the raised exception as the `operation`, `container_id`,
+/// `path` and `index` attributes.
+pub struct ErrorContext {
+    operation: &'static str,
+    container_id: loro::ContainerID,
+    path: Option<Vec<ErrorIndex>>,
+    index: Option<ErrorIndex>,
+}
+
+impl ErrorContext {
+    fn new<C: ContainerTrait>(
+        container: &C,
+        operation: &'static str,
+        index: Option<ErrorIndex>,
+    ) -> Self {
+        let container_id = container.id();
+        let path = container
+            .doc()
+            .and_then(|doc| doc.get_path_to_container(&container_id))
+            .map(|path| path.iter().map(|(_, index)| index.into()).collect());
+        Self {
+            operation,
+            container_id,
+            path,
+            index,
+        }
+    }
+
+    fn attach(&self, py: Python<'_>, err: &PyErr) {
+        // The context is best-effort: never let it shadow the original error.
+        let _ = (|| -> PyResult<()> {
+            let value = err.value(py);
+            value.setattr("operation", self.operation)?;
+            value.setattr(
+                "container_id",
+                ContainerID::from(&self.container_id).into_py_any(py)?,
+            )?;
+            let path = match &self.path {
+                Some(path) => Some(
+                    path.iter()
+                        .map(|index| index.to_py(py))
+                        .collect::<PyResult<Vec<_>>>()?,
+                ),
+                None => None,
+            };
+            value.setattr("path", path)?;
+            let index = match &self.index {
+                Some(index) => Some(index.to_py(py)?),
+                None => None,
+            };
+            value.setattr("index", index)?;
+            Ok(())
+        })();
+    }
+}
+
+pub trait ErrorContextExt<T> {
+    /// Attach the failed operation and its container to the error.
+    fn context<C: ContainerTrait>(self, container: &C, operation: &'static str) -> PyLoroResult<T>;
+
+    /// Attach the failed operation, its container and the targeted index or key to the error.
+    fn context_at<C: ContainerTrait>(
+        self,
+        container: &C,
+        operation: &'static str,
+        index: impl Into<ErrorIndex>,
+    ) -> PyLoroResult<T>;
+}
+
+impl<T, E: Into<PyLoroError>> ErrorContextExt<T> for Result<T, E> {
+    fn context<C: ContainerTrait>(self, container: &C, operation: &'static str) -> PyLoroResult<T> {
+        self.map_err(|e| {
+            PyLoroError::WithContext(
+                Box::new(e.into()),
+                Box::new(ErrorContext::new(container, operation, None)),
+            )
+        })
+    }
+
+    fn context_at<C: ContainerTrait>(
+        self,
+        container: &C,
+        operation: &'static str,
+        index: impl Into<ErrorIndex>,
+    ) -> PyLoroResult<T> {
+        self.map_err(|e| {
+            PyLoroError::WithContext(
+                Box::new(e.into()),
+                Box::new(ErrorContext::new(container, operation, Some(index.into()))),
+            )
+        })
+    }
+}
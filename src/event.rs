@@ -1,12 +1,15 @@
 use crate::value::{ContainerID, LoroValue, TreeID, TreeParentId, ValueOrContainer};
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyTuple};
+use pyo3::types::{PyDict, PyInt, PyString, PyTuple};
+use pyo3::IntoPyObjectExt;
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Subscription>()?;
+    m.add_class::<SubscriptionInfo>()?;
     m.add_class::<EventTriggerKind>()?;
     m.add_class::<ListDiffItem>()?;
     m.add_class::<MapDelta>()?;
@@ -45,7 +48,7 @@ impl fmt::Display for DiffEvent {
             self.origin,
             self.current_target
                 .as_ref()
-                .map_or("None".to_string(), |v| format!("{}", v)),
+                .map_or("None".to_string(), |v| v.__repr__()),
             self.events
                 .iter()
                 .map(|e| format!("{}", e))
@@ -55,8 +58,28 @@ impl fmt::Display for DiffEvent {
     }
 }
 
+#[pymethods]
+impl DiffEvent {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    /// The fields shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self, py: Python<'_>) -> PyResult<Vec<(&'static str, Py<PyAny>)>> {
+        Ok(vec![
+            ("triggered_by", self.triggered_by.into_py_any(py)?),
+            ("origin", self.origin.clone().into_py_any(py)?),
+            (
+                "current_target",
+                self.current_target.clone().into_py_any(py)?,
+            ),
+            ("events", self.events.clone().into_py_any(py)?),
+        ])
+    }
+}
+
 /// The kind of the event trigger.
-#[pyclass(eq, eq_int)]
+#[pyclass(eq, eq_int, hash, frozen)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EventTriggerKind {
     /// The event is triggered by a local transaction.
@@ -89,11 +112,19 @@ impl fmt::Display for PathItem {
         write!(
             f,
             "PathItem(container={}, index={})",
-            self.container, self.index
+            self.container.__repr__(),
+            self.index
         )
     }
 }
 
+#[pymethods]
+impl PathItem {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(get_all, str)]
 #[derive(Debug, Clone)]
 /// A diff of a container.
@@ -113,7 +144,7 @@ impl fmt::Display for ContainerDiff {
         write!(
             f,
             "ContainerDiff(target={}, path=[{}], is_unknown={}, diff={})",
-            self.target,
+            self.target.__repr__(),
             self.path
                 .iter()
                 .map(|p| format!("{}", p))
@@ -125,7 +156,29 @@ impl fmt::Display for ContainerDiff {
     }
 }
 
-#[pyclass(str, get_all)]
+#[pymethods]
+impl ContainerDiff {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    /// The fields shown by the `rich` pretty printer.
+    pub fn __rich_repr__(&self, py: Python<'_>) -> PyResult<Vec<(&'static str, Py<PyAny>)>> {
+        Ok(vec![
+            ("target", self.target.clone().into_py_any(py)?),
+            ("path", self.path.clone().into_py_any(py)?),
+            ("is_unknown", self.is_unknown.into_py_any(py)?),
+            ("diff", self.diff.clone().into_py_any(py)?),
+        ])
+    }
+}
+
+/// A step of a path to a container: a map key, a list position or a tree node.
+///
+/// `str()` gives the key, the position or the node id as `counter@peer`, and a `Seq`
+/// can be used as a list index. The APIs taking an `Index` also accept a `str` key, an
+/// `int` position or a `TreeID` instead.
+#[pyclass(get_all)]
 #[derive(Debug, Clone)]
 pub enum Index {
     Key { key: String },
@@ -138,7 +191,64 @@ impl fmt::Display for Index {
         match self {
             Index::Key { key } => write!(f, "Key(key='{}')", key),
             Index::Seq { index } => write!(f, "Seq(index={})", index),
-            Index::Node { target } => write!(f, "Node(target={})", target),
+            Index::Node { target } => write!(f, "Node(target={})", target.__repr__()),
+        }
+    }
+}
+
+impl Index {
+    /// Accept an `Index`, or a str key, an int position or a `TreeID` in its place.
+    pub fn from_object(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(index) = ob.downcast::<Index>() {
+            return Ok(index.borrow().clone());
+        }
+        if let Ok(key) = ob.downcast::<PyString>() {
+            return Ok(Index::Key {
+                key: key.to_str()?.to_string(),
+            });
+        }
+        if ob.is_instance_of::<PyInt>() {
+            let index = ob
+                .extract()
+                .map_err(|_| PyValueError::new_err("a position must be between 0 and 2**32 - 1"))?;
+            return Ok(Index::Seq { index });
+        }
+        if let Ok(target) = ob.extract::<TreeID>() {
+            return Ok(Index::Node { target });
+        }
+        Err(PyTypeError::new_err(
+            "an index must be an Index, a str, an int or a TreeID",
+        ))
+    }
+
+    pub fn path_from_object(ob: &Bound<'_, PyAny>) -> PyResult<Vec<Self>> {
+        ob.try_iter()?
+            .map(|item| Index::from_object(&item?))
+            .collect()
+    }
+}
+
+#[pymethods]
+impl Index {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn __str__(&self) -> String {
+        match self {
+            Index::Key { key } => key.clone(),
+            Index::Seq { index } => index.to_string(),
+            Index::Node { target } => target.__str__(),
+        }
+    }
+
+    pub fn __index__(&self) -> PyResult<u32> {
+        match self {
+            Index::Seq { index } => Ok(*index),
+            _ => Err(PyTypeError::new_err(format!(
+                "{} can't be used as a position",
+                self
+            ))),
         }
     }
 }
@@ -187,13 +297,22 @@ impl fmt::Display for Diff {
     }
 }
 
-#[pyclass(str, get_all)]
-#[derive(Debug, Clone)]
+#[pymethods]
+impl Diff {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pyclass(eq, str, get_all)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TextDelta {
+    #[pyo3(constructor = (retain, attributes = None))]
     Retain {
         retain: usize,
         attributes: Option<HashMap<String, LoroValue>>,
     },
+    #[pyo3(constructor = (insert, attributes = None))]
     Insert {
         insert: String,
         attributes: Option<HashMap<String, LoroValue>>,
@@ -241,6 +360,41 @@ impl fmt::Display for TextDelta {
     }
 }
 
+#[pymethods]
+impl TextDelta {
+    /// Keep the next `retain` characters, applying `attributes` to them if given.
+    #[staticmethod]
+    #[pyo3(signature = (retain, attributes = None))]
+    pub fn retain(retain: usize, attributes: Option<HashMap<String, LoroValue>>) -> Self {
+        TextDelta::Retain { retain, attributes }
+    }
+
+    /// Insert the text `insert` with the optional `attributes`.
+    #[staticmethod]
+    #[pyo3(signature = (insert, attributes = None))]
+    pub fn insert(insert: String, attributes: Option<HashMap<String, LoroValue>>) -> Self {
+        TextDelta::Insert { insert, attributes }
+    }
+
+    /// Delete the next `delete` characters.
+    #[staticmethod]
+    pub fn delete(delete: usize) -> Self {
+        TextDelta::Delete { delete }
+    }
+
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// An item of a list diff. Branch on `kind` or match the variants:
+///
+/// ```python
+/// match item:
+///     case ListDiffItem.Insert(values, is_move): ...
+///     case ListDiffItem.Delete(n): ...
+///     case ListDiffItem.Retain(n): ...
+/// ```
 #[pyclass(str, get_all)]
 #[derive(Debug, Clone)]
 pub enum ListDiffItem {
@@ -290,6 +444,33 @@ impl fmt::Display for ListDiffItem {
     }
 }
 
+#[pymethods]
+impl ListDiffItem {
+    /// The variant of the item: `"insert"`, `"delete"` or `"retain"`.
+    #[getter]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ListDiffItem::Insert { .. } => "insert",
+            ListDiffItem::Delete { .. } => "delete",
+            ListDiffItem::Retain { .. } => "retain",
+        }
+    }
+
+    /// The number of elements the item inserts, deletes or retains.
+    #[getter]
+    pub fn get_len(&self) -> usize {
+        match self {
+            ListDiffItem::Insert { insert, .. } => insert.len(),
+            ListDiffItem::Delete { delete } => *delete as usize,
+            ListDiffItem::Retain { retain } => *retain as usize,
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(str, get_all)]
 #[derive(Debug, Clone)]
 pub struct MapDelta {
@@ -315,6 +496,13 @@ impl fmt::Display for MapDelta {
     }
 }
 
+#[pymethods]
+impl MapDelta {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(str, get_all)]
 #[derive(Debug, Clone)]
 pub struct TreeDiff {
@@ -335,6 +523,13 @@ impl fmt::Display for TreeDiff {
     }
 }
 
+#[pymethods]
+impl TreeDiff {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(str, get_all)]
 #[derive(Debug, Clone)]
 pub struct TreeDiffItem {
@@ -347,11 +542,19 @@ impl fmt::Display for TreeDiffItem {
         write!(
             f,
             "TreeDiffItem(target={}, action={})",
-            self.target, self.action
+            self.target.__repr__(),
+            self.action
         )
     }
 }
 
+#[pymethods]
+impl TreeDiffItem {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(str, get_all)]
 #[derive(Debug, Clone)]
 pub enum TreeExternalDiff {
@@ -414,8 +617,15 @@ impl fmt::Display for TreeExternalDiff {
     }
 }
 
+#[pymethods]
+impl TreeExternalDiff {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(frozen)]
-pub struct Subscription(pub(crate) Mutex<Option<loro::Subscription>>);
+pub struct Subscription(pub(crate) Arc<Mutex<Option<loro::Subscription>>>);
 
 #[pymethods]
 impl Subscription {
@@ -433,6 +643,14 @@ impl Subscription {
         }
     }
 
+    pub fn __repr__(&self) -> String {
+        let active = self.0.lock().map(|s| s.is_some()).unwrap_or(false);
+        format!(
+            "Subscription(active={})",
+            if active { "True" } else { "False" }
+        )
+    }
+
     #[pyo3(signature = (*_args, **_kwargs))]
     pub fn __call__(
         &self,
@@ -449,6 +667,39 @@ impl Subscription {
     }
 }
 
+/// A subscription made through a `LoroDoc`, listed by `LoroDoc.active_subscriptions`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct SubscriptionInfo {
+    /// The subscribe method that made it, e.g. "root" for `subscribe_root`.
+    pub kind: String,
+    /// The container id or JSONPath it watches, or `None` if it watches the whole document.
+    pub target: Option<String>,
+    /// The "file:line" of the Python code that made it, if known.
+    pub created_at: Option<String>,
+}
+
+impl fmt::Display for SubscriptionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quoted =
+            |s: &Option<String>| s.as_ref().map_or("None".to_string(), |s| format!("'{s}'"));
+        write!(
+            f,
+            "SubscriptionInfo(kind='{}', target={}, created_at={})",
+            self.kind,
+            quoted(&self.target),
+            quoted(&self.created_at)
+        )
+    }
+}
+
+#[pymethods]
+impl SubscriptionInfo {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[pyclass(str)]
 #[derive(Debug, Clone, Default)]
 pub struct DiffBatch(loro::event::DiffBatch);
@@ -466,6 +717,10 @@ impl DiffBatch {
         Self::default()
     }
 
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
     /// Push a new event to the batch.
     ///
     /// If the cid already exists in the batch, return Err
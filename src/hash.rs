@@ -0,0 +1,21 @@
+use crate::msgpack;
+
+/// FNV-1a, finalized with splitmix64 to spread the bits.
+///
+/// Unlike `std::hash`, it's the same across processes and versions, so the hashes can be
+/// stored or sent to other replicas.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// The stable hash of a value, through its deterministic msgpack encoding.
+pub fn value_hash(value: &loro::LoroValue) -> u64 {
+    stable_hash(&msgpack::encode(value))
+}
@@ -0,0 +1,695 @@
+use std::{
+    cmp::Reverse,
+    fmt::{self, Write},
+    sync::OnceLock,
+};
+
+use loro::{
+    json::{JsonOpContent, MapOp},
+    Counter, Lamport, LoroDoc as LoroDocInner, LoroError, PeerID, Timestamp,
+};
+use pyo3::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    doc::ChangeMeta,
+    err::PyLoroResult,
+    value::{ContainerID, ID},
+    version::Frontiers,
+};
+
+pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ChangeIter>()?;
+    m.add_class::<ChangeInfo>()?;
+    m.add_class::<OpInfo>()?;
+    m.add_class::<MapEntryMeta>()?;
+    m.add_class::<TimelinePoint>()?;
+    Ok(())
+}
+
+/// All the changes in the `OpLog`, or only those of `peer`, parents before their children.
+///
+/// The history trimmed by a shallow snapshot is skipped.
+pub fn changes(doc: &LoroDocInner, peer: Option<PeerID>) -> Vec<loro::ChangeMeta> {
+    let start = loro::VersionVector::from_im_vv(&doc.shallow_since_vv());
+    let mut ans = Vec::new();
+    let oplog_vv = doc.oplog_vv();
+    for (&p, &end) in oplog_vv.iter() {
+        if peer.is_some_and(|peer| peer != p) {
+            continue;
+        }
+        let mut counter = start.get(&p).copied().unwrap_or(0);
+        while counter < end {
+            let Some(change) = doc.get_change(loro::ID::new(p, counter)) else {
+                break;
+            };
+            counter = change.id.counter + change.len as Counter;
+            ans.push(change);
+        }
+    }
+    // A change has a greater lamport than all of its deps.
+    ans.sort_unstable_by_key(|c| (c.lamport, c.id.peer));
+    ans
+}
+
+/// The changes of every peer in counter order, to find them by timestamp.
+///
+/// A peer's timestamps can go backwards when its clock is adjusted or set with
+/// `set_next_commit_timestamp`, so only the prefix of its changes whose timestamps never
+/// decrease is binary searched, and the rest is scanned.
+struct TimestampIndex<'a> {
+    start: loro::VersionVector,
+    by_peer: FxHashMap<PeerID, PeerChanges<'a>>,
+}
+
+struct PeerChanges<'a> {
+    changes: Vec<&'a loro::ChangeMeta>,
+    /// The length of the prefix of `changes` sorted by timestamp.
+    sorted: usize,
+}
+
+impl<'a> TimestampIndex<'a> {
+    /// Index `changes`, ordered by lamport like the ones returned by [changes].
+    fn new(doc: &LoroDocInner, changes: &'a [loro::ChangeMeta]) -> Self {
+        let mut by_peer: FxHashMap<PeerID, Vec<&loro::ChangeMeta>> = FxHashMap::default();
+        for change in changes {
+            // A peer's lamports grow with its counters.
+            by_peer.entry(change.id.peer).or_default().push(change);
+        }
+        let by_peer = by_peer
+            .into_iter()
+            .map(|(peer, changes)| {
+                let sorted = 1 + changes
+                    .windows(2)
+                    .take_while(|w| w[0].timestamp <= w[1].timestamp)
+                    .count();
+                (peer, PeerChanges { changes, sorted })
+            })
+            .collect();
+        Self {
+            start: loro::VersionVector::from_im_vv(&doc.shallow_since_vv()),
+            by_peer,
+        }
+    }
+
+    /// The changes with `start <= timestamp < end`, parents before their children.
+    fn changes_between(&self, start: Timestamp, end: Timestamp) -> Vec<&'a loro::ChangeMeta> {
+        let mut ans = Vec::new();
+        for peer in self.by_peer.values() {
+            let (sorted, rest) = peer.changes.split_at(peer.sorted);
+            let lo = sorted.partition_point(|c| c.timestamp < start);
+            let hi = sorted.partition_point(|c| c.timestamp < end).max(lo);
+            ans.extend_from_slice(&sorted[lo..hi]);
+            ans.extend(
+                rest.iter()
+                    .filter(|c| start <= c.timestamp && c.timestamp < end),
+            );
+        }
+        ans.sort_unstable_by_key(|c| (c.lamport, c.id.peer));
+        ans
+    }
+
+    /// The number of changes of each peer in the version at `ts`, see
+    /// [frontiers_at_timestamp].
+    fn lens_at(&self, ts: Timestamp) -> FxHashMap<PeerID, usize> {
+        let mut lens = self
+            .by_peer
+            .iter()
+            .map(|(peer, changes)| {
+                let (sorted, rest) = changes.changes.split_at(changes.sorted);
+                let mut len = sorted.partition_point(|c| c.timestamp <= ts);
+                if len == sorted.len() {
+                    len += rest.iter().take_while(|c| c.timestamp <= ts).count();
+                }
+                (*peer, len)
+            })
+            .collect::<FxHashMap<_, _>>();
+        loop {
+            let mut changed = false;
+            for (peer, changes) in &self.by_peer {
+                while let Some(last) = lens[peer].checked_sub(1) {
+                    if changes.changes[last]
+                        .deps
+                        .iter()
+                        .all(|dep| dep.counter < self.end(&lens, dep.peer))
+                    {
+                        break;
+                    }
+                    lens.insert(*peer, last);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        lens
+    }
+
+    /// The end counter of `peer` in the version with `lens` changes of each peer.
+    fn end(&self, lens: &FxHashMap<PeerID, usize>, peer: PeerID) -> Counter {
+        match lens.get(&peer).and_then(|n| n.checked_sub(1)) {
+            Some(last) => {
+                let change = self.by_peer[&peer].changes[last];
+                change.id.counter + change.len as Counter
+            }
+            None => self.start.get(&peer).copied().unwrap_or(0),
+        }
+    }
+
+    /// The version vector of the version with `lens` changes of each peer.
+    fn vv(&self, lens: &FxHashMap<PeerID, usize>) -> loro::VersionVector {
+        let mut vv = self.start.clone();
+        for peer in self.by_peer.keys() {
+            let end = self.end(lens, *peer);
+            if end > 0 {
+                vv.set_end(loro::ID::new(*peer, end));
+            }
+        }
+        vv
+    }
+
+    /// The change with the greatest lamport in the version with `lens` changes of each peer.
+    fn last(&self, lens: &FxHashMap<PeerID, usize>) -> Option<&'a loro::ChangeMeta> {
+        lens.iter()
+            .filter_map(|(peer, len)| Some(self.by_peer[peer].changes[len.checked_sub(1)?]))
+            .max_by_key(|c| (c.lamport, c.id.peer))
+    }
+}
+
+/// The changes with `start <= timestamp < end`, parents before their children.
+pub fn changes_between(
+    doc: &LoroDocInner,
+    start: Timestamp,
+    end: Timestamp,
+) -> Vec<loro::ChangeMeta> {
+    let changes = changes(doc, None);
+    TimestampIndex::new(doc, &changes)
+        .changes_between(start, end)
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// The latest version made only of changes with `timestamp <= ts`.
+///
+/// Changes depending on a change made later, which can happen when clocks are skewed, are
+/// left out so the version is causally complete. For the same reason a peer's changes stop
+/// at its first change made after `ts`, even if its timestamps go backwards afterwards.
+pub fn frontiers_at_timestamp(doc: &LoroDocInner, ts: Timestamp) -> loro::Frontiers {
+    let changes = changes(doc, None);
+    let index = TimestampIndex::new(doc, &changes);
+    doc.vv_to_frontiers(&index.vv(&index.lens_at(ts)))
+}
+
+/// The version made of all the changes but the last `n`.
+///
+/// Changes are ordered by lamport, so the version is causally complete.
+pub fn frontiers_before_last(doc: &LoroDocInner, n: usize) -> loro::Frontiers {
+    let changes = changes(doc, None);
+    let mut vv = loro::VersionVector::from_im_vv(&doc.shallow_since_vv());
+    for change in &changes[..changes.len().saturating_sub(n)] {
+        vv.set_end(loro::ID::new(
+            change.id.peer,
+            change.id.counter + change.len as Counter,
+        ));
+    }
+    doc.vv_to_frontiers(&vv)
+}
+
+/// `doc` forked at `frontiers`, like `LoroDoc::fork_at`.
+///
+/// Fails instead of panicking if `frontiers` isn't a version of `doc`: one of its ops isn't
+/// in the `OpLog`, or the version is before the start of a shallow snapshot. Loro can't
+/// fork a shallow document, so its snapshot is imported into a new one checked out at
+/// `frontiers` instead.
+pub fn fork_at(doc: &LoroDocInner, frontiers: &loro::Frontiers) -> PyLoroResult<LoroDocInner> {
+    let oplog_vv = doc.oplog_vv();
+    if let Some(id) = frontiers.iter().find(|id| !oplog_vv.includes_id(*id)) {
+        return Err(LoroError::FrontiersNotFound(id).into());
+    }
+    if !doc.is_shallow() {
+        return Ok(doc.fork_at(frontiers));
+    }
+    let fork = LoroDocInner::new();
+    fork.import(&doc.export(loro::ExportMode::Snapshot)?)?;
+    fork.checkout(frontiers)?;
+    Ok(fork)
+}
+
+/// How [timeline] spaces its points.
+#[derive(Debug, Clone, Copy)]
+pub enum TimelineSpacing {
+    Changes,
+    Timestamp,
+}
+
+/// Up to `points` versions spread evenly over the history, the last one being the latest.
+///
+/// With [TimelineSpacing::Changes], the versions are made of the first `i * len / points`
+/// changes, ordered by lamport. With [TimelineSpacing::Timestamp], they are the versions
+/// at evenly spaced timestamps, see [frontiers_at_timestamp]. A version with no change or
+/// equal to the previous one is skipped.
+pub fn timeline(doc: &LoroDocInner, points: usize, spacing: TimelineSpacing) -> Vec<TimelinePoint> {
+    let changes = changes(doc, None);
+    let (Some(min), Some(max)) = (
+        changes.iter().map(|c| c.timestamp).min(),
+        changes.iter().map(|c| c.timestamp).max(),
+    ) else {
+        return Vec::new();
+    };
+    let start = loro::VersionVector::from_im_vv(&doc.shallow_since_vv());
+    let index = TimestampIndex::new(doc, &changes);
+    let mut ans: Vec<TimelinePoint> = Vec::new();
+    for i in 1..=points {
+        // The number of changes in the version, its last change and its version vector
+        let (len, last, vv) = match spacing {
+            TimelineSpacing::Changes => {
+                let included = &changes[..i * changes.len() / points];
+                let mut vv = start.clone();
+                for change in included {
+                    vv.set_end(loro::ID::new(
+                        change.id.peer,
+                        change.id.counter + change.len as Counter,
+                    ));
+                }
+                (included.len(), included.last(), vv)
+            }
+            TimelineSpacing::Timestamp => {
+                let ts = min + ((max - min) as i128 * i as i128 / points as i128) as Timestamp;
+                let lens = index.lens_at(ts);
+                (lens.values().sum(), index.last(&lens), index.vv(&lens))
+            }
+        };
+        let Some(last) = last else {
+            continue;
+        };
+        if ans.last().is_some_and(|p| p.changes == len) {
+            continue;
+        }
+        ans.push(TimelinePoint {
+            frontiers: doc.vv_to_frontiers(&vv).into(),
+            changes: len,
+            change: last.clone().into(),
+        });
+    }
+    ans
+}
+
+/// A version of the history, returned by `LoroDoc.timeline`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct TimelinePoint {
+    pub frontiers: Frontiers,
+    /// The number of changes in the version.
+    pub changes: usize,
+    /// The change of the version with the greatest lamport.
+    pub change: ChangeMeta,
+}
+
+impl fmt::Display for TimelinePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TimelinePoint(frontiers={}, changes={}, timestamp={})",
+            self.frontiers.__repr__(),
+            self.changes,
+            self.change.timestamp
+        )
+    }
+}
+
+#[pymethods]
+impl TimelinePoint {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// The latest writes to `key` in `map` that are concurrent with each other, with their
+/// lamports, the current value first. A deletion has no value.
+///
+/// Only the history included in the state is read, so a detached document gets the writes of
+/// the version it's checked out at. Concurrent writes are ordered by lamport and then peer,
+/// like the map resolves them. Every change is visited, but only the ones that touch `map`
+/// have their ops decoded.
+pub fn concurrent_map_values(
+    doc: &LoroDocInner,
+    map: &loro::ContainerID,
+    key: &str,
+) -> Vec<(loro::ID, Lamport, Option<loro::LoroValue>)> {
+    let start = loro::VersionVector::from_im_vv(&doc.shallow_since_vv());
+    let mut writes = Vec::new();
+    for (&peer, &end) in doc.state_vv().iter() {
+        let mut counter = start.get(&peer).copied().unwrap_or(0);
+        while counter < end {
+            let Some(change) = doc.get_change(loro::ID::new(peer, counter)) else {
+                break;
+            };
+            // The state may end in the middle of a change.
+            let span_end = (change.id.counter + change.len as Counter).min(end);
+            let span_start = std::mem::replace(&mut counter, span_end);
+            let len = (span_end - span_start) as usize;
+            if !doc
+                .get_changed_containers_in(loro::ID::new(peer, span_start), len)
+                .contains(map)
+            {
+                continue;
+            }
+            let span = loro::IdSpan::new(peer, span_start, span_end);
+            for change in doc.export_json_in_id_span(span) {
+                for op in change.ops {
+                    if &op.container != map {
+                        continue;
+                    }
+                    let value = match op.content {
+                        JsonOpContent::Map(MapOp::Insert { key: k, value }) if &*k == key => {
+                            Some(value)
+                        }
+                        JsonOpContent::Map(MapOp::Delete { key: k }) if &*k == key => None,
+                        _ => continue,
+                    };
+                    let lamport = change.lamport + (op.counter - change.id.counter) as Lamport;
+                    writes.push((lamport, loro::ID::new(peer, op.counter), value));
+                }
+            }
+        }
+    }
+    writes.sort_unstable_by_key(|w| Reverse((w.0, w.1.peer)));
+
+    // A write seen by another write is also seen by one of the latest writes, which have
+    // greater lamports.
+    let mut heads: Vec<(loro::VersionVector, _)> = Vec::new();
+    for (lamport, id, value) in writes {
+        if heads.iter().any(|(vv, _)| vv.includes_id(id)) {
+            continue;
+        }
+        let vv = doc.frontiers_to_vv(&id.into()).unwrap_or_default();
+        heads.push((vv, (id, lamport, value)));
+    }
+    heads.into_iter().map(|(_, write)| write).collect()
+}
+
+/// Find the changes containing op ids.
+struct ChangeIndex<'a> {
+    by_peer: FxHashMap<PeerID, Vec<&'a loro::ChangeMeta>>,
+}
+
+impl<'a> ChangeIndex<'a> {
+    fn new(changes: &'a [loro::ChangeMeta]) -> Self {
+        let mut by_peer: FxHashMap<PeerID, Vec<&loro::ChangeMeta>> = FxHashMap::default();
+        for change in changes {
+            by_peer.entry(change.id.peer).or_default().push(change);
+        }
+        for changes in by_peer.values_mut() {
+            changes.sort_unstable_by_key(|c| c.id.counter);
+        }
+        Self { by_peer }
+    }
+
+    fn find(&self, id: loro::ID) -> Option<&'a loro::ChangeMeta> {
+        let changes = self.by_peer.get(&id.peer)?;
+        let i = changes.partition_point(|c| c.id.counter <= id.counter);
+        let change = *changes.get(i.checked_sub(1)?)?;
+        (id.counter < change.id.counter + change.len as Counter).then_some(change)
+    }
+}
+
+/// The output formats of [change_graph].
+#[derive(Debug, Clone, Copy)]
+pub enum GraphFormat {
+    Mermaid,
+    Dot,
+}
+
+/// Render the change DAG of `doc`, with an edge from every dependency to its dependent.
+pub fn change_graph(doc: &LoroDocInner, format: GraphFormat) -> String {
+    let changes = changes(doc, None);
+    let index = ChangeIndex::new(&changes);
+    let mut out = String::new();
+    match format {
+        GraphFormat::Mermaid => out.push_str("graph TD\n"),
+        GraphFormat::Dot => out.push_str("digraph changes {\n    node [shape=box];\n"),
+    }
+    for change in &changes {
+        let mut lines = vec![
+            change.id.to_string(),
+            format!("peer {}, lamport {}", change.id.peer, change.lamport),
+        ];
+        if let Some(message) = &change.message {
+            lines.push(message.to_string());
+        }
+        let _ = match format {
+            GraphFormat::Mermaid => writeln!(
+                out,
+                "    {}[\"{}\"]",
+                mermaid_node(change.id),
+                lines
+                    .iter()
+                    .map(|l| escape_mermaid(l))
+                    .collect::<Vec<_>>()
+                    .join("<br/>")
+            ),
+            GraphFormat::Dot => writeln!(
+                out,
+                "    \"{}\" [label=\"{}\"];",
+                change.id,
+                lines
+                    .iter()
+                    .map(|l| escape_dot(l))
+                    .collect::<Vec<_>>()
+                    .join("\\n")
+            ),
+        };
+    }
+    for change in &changes {
+        for dep in change.deps.iter() {
+            let Some(parent) = index.find(dep) else {
+                continue;
+            };
+            let _ = match format {
+                GraphFormat::Mermaid => writeln!(
+                    out,
+                    "    {} --> {}",
+                    mermaid_node(parent.id),
+                    mermaid_node(change.id)
+                ),
+                GraphFormat::Dot => {
+                    writeln!(out, "    \"{}\" -> \"{}\";", parent.id, change.id)
+                }
+            };
+        }
+    }
+    if let GraphFormat::Dot = format {
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn mermaid_node(id: loro::ID) -> String {
+    format!("c{}_{}", id.peer, id.counter)
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', " ")
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// An iterator over the changes of a document, returned by `LoroDoc.iter_changes`.
+#[pyclass]
+pub struct ChangeIter {
+    doc: LoroDocInner,
+    changes: std::vec::IntoIter<(loro::ChangeMeta, loro::IdSpan)>,
+}
+
+impl ChangeIter {
+    /// Iterate the changes with ops in `(from, to]`, clamping each change to that range.
+    pub fn new(
+        doc: &LoroDocInner,
+        from: Option<loro::VersionVector>,
+        to: Option<loro::VersionVector>,
+        peer: Option<PeerID>,
+    ) -> Self {
+        let changes = changes(doc, peer)
+            .into_iter()
+            .filter_map(|change| {
+                let peer = change.id.peer;
+                let start = change.id.counter;
+                let end = start + change.len as Counter;
+                let start = match &from {
+                    Some(from) => start.max(from.get(&peer).copied().unwrap_or(0)),
+                    None => start,
+                };
+                let end = match &to {
+                    Some(to) => end.min(to.get(&peer).copied().unwrap_or(0)),
+                    None => end,
+                };
+                (start < end).then(|| (change, loro::IdSpan::new(peer, start, end)))
+            })
+            .collect::<Vec<_>>();
+        Self {
+            doc: doc.clone(),
+            changes: changes.into_iter(),
+        }
+    }
+}
+
+#[pymethods]
+impl ChangeIter {
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<ChangeInfo> {
+        let (meta, span) = self.changes.next()?;
+        Some(ChangeInfo {
+            meta: meta.into(),
+            span,
+            doc: self.doc.clone(),
+            ops: OnceLock::new(),
+        })
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+/// A change yielded by `LoroDoc.iter_changes`.
+#[pyclass(frozen, str)]
+pub struct ChangeInfo {
+    meta: ChangeMeta,
+    span: loro::IdSpan,
+    doc: LoroDocInner,
+    ops: OnceLock<Vec<OpInfo>>,
+}
+
+impl fmt::Display for ChangeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ChangeInfo(id={}, lamport={}, len={}, message={})",
+            self.meta.id.__repr__(),
+            self.meta.lamport,
+            self.span.counter.end - self.span.counter.start,
+            self.meta
+                .message
+                .as_ref()
+                .map_or("None".to_string(), |m| format!("'{m}'"))
+        )
+    }
+}
+
+#[pymethods]
+impl ChangeInfo {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    /// The metadata of the change.
+    #[getter]
+    pub fn meta(&self) -> ChangeMeta {
+        self.meta.clone()
+    }
+
+    /// The ops of the change in the iterated range, decoded on first access.
+    #[getter]
+    pub fn ops(&self) -> Vec<OpInfo> {
+        self.ops
+            .get_or_init(|| decode_ops(&self.doc, self.span))
+            .clone()
+    }
+}
+
+/// An op of a change, described for inspection.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct OpInfo {
+    pub id: ID,
+    /// The container the op applies to.
+    pub container: ContainerID,
+    /// The kind of op, e.g. `insert`, `delete`, `mark` or `move`.
+    pub kind: String,
+    /// The other fields of the op as a JSON object, e.g. `{"pos":0,"text":"abc"}`.
+    pub content: String,
+}
+
+impl fmt::Display for OpInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "OpInfo(id={}, container={}, kind='{}', content='{}')",
+            self.id.__repr__(),
+            self.container.__repr__(),
+            self.kind,
+            self.content
+        )
+    }
+}
+
+#[pymethods]
+impl OpInfo {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// The write that set the current value of a map entry.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct MapEntryMeta {
+    /// The id of the op that wrote the value.
+    pub id: ID,
+    pub peer: PeerID,
+    pub lamport: Lamport,
+}
+
+impl fmt::Display for MapEntryMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MapEntryMeta(id={}, peer={}, lamport={})",
+            self.id.__repr__(),
+            self.peer,
+            self.lamport
+        )
+    }
+}
+
+#[pymethods]
+impl MapEntryMeta {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Describe the ops in `span` using the JSON encoding of the history.
+pub fn decode_ops(doc: &LoroDocInner, span: loro::IdSpan) -> Vec<OpInfo> {
+    let mut ans = Vec::new();
+    for change in doc.export_json_in_id_span(span) {
+        for op in change.ops {
+            let mut content = serde_json::to_value(&op.content).unwrap_or_default();
+            let kind = content
+                .as_object_mut()
+                .and_then(|c| c.remove("type"))
+                .and_then(|t| t.as_str().map(|t| t.to_string()))
+                .unwrap_or_default();
+            ans.push(OpInfo {
+                id: loro::ID::new(change.id.peer, op.counter).into(),
+                container: op.container.into(),
+                kind,
+                content: content.to_string(),
+            });
+        }
+    }
+    ans
+}
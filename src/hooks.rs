@@ -0,0 +1,969 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, OnceLock, Weak,
+    },
+    thread,
+    time::Instant,
+};
+
+use loro::{ContainerTrait, Counter, LoroDoc as LoroDocInner, PeerID};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyCFunction, PyDict, PyString, PyTuple},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    codec, compress,
+    doc::{IdSpan, ImportBlobMetadata},
+    err::{PyLoroError, PyLoroResult},
+    event::{DiffEvent, EventTriggerKind, Subscription, SubscriptionInfo},
+    value::ContainerID,
+};
+
+pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<OperationMetrics>()?;
+    m.add_class::<ImportRequest>()?;
+    m.add_class::<QuotaRequest>()?;
+    Ok(())
+}
+
+/// The prefix of the blobs encrypted by the hook set with `LoroDoc.set_encryption`, which
+/// tells them apart from plain blobs.
+const ENCRYPTED_MAGIC: &[u8] = b"loroenc\x01";
+
+/// A subscription made through the document and where it was made.
+type TrackedSubscription = (SubscriptionInfo, Weak<Mutex<Option<loro::Subscription>>>);
+
+/// The hooks of the live documents, keyed by the address of their oplog.
+///
+/// The weak reference to the oplog tells when the document is gone. It also keeps the
+/// allocation of the oplog, so its address isn't reused by another document meanwhile.
+static REGISTRY: OnceLock<Mutex<FxHashMap<usize, (Weak<dyn Any + Send + Sync>, Arc<DocHooks>)>>> =
+    OnceLock::new();
+
+/// The Python callbacks and guards attached to a document.
+///
+/// They are shared by every `LoroDoc` object wrapping the same document and live as long
+/// as the document, see [DocHooks::of].
+#[derive(Default)]
+pub struct DocHooks {
+    metrics: Mutex<Option<Py<PyAny>>>,
+    import_filter: Mutex<Option<Py<PyAny>>>,
+    encrypt: Mutex<Option<Py<PyAny>>>,
+    decrypt: Mutex<Option<Py<PyAny>>>,
+    quota: Mutex<Option<Py<PyAny>>>,
+    readonly: Mutex<ReadOnlyState>,
+    /// The origin of the import a read-only document is catching up with, see
+    /// [DocHooks::follow_import].
+    followed_import: Mutex<Option<String>>,
+    /// The pre-commit subscription of the timestamp provider.
+    clock: Mutex<Option<loro::Subscription>>,
+    /// The subscriptions made through the document, which are gone once their `Subscription`
+    /// is dropped.
+    subscriptions: Mutex<Vec<TrackedSubscription>>,
+    dispatcher: Dispatcher,
+    /// The counter ranges of the imported changes waiting for their dependencies, by peer.
+    pending: Mutex<FxHashMap<PeerID, (Counter, Counter)>>,
+    pending_hook: Mutex<Option<Py<PyAny>>>,
+    /// The keys of the text styles configured with `LoroDoc.config_text_style`, which loro's
+    /// config can't list.
+    text_style_keys: Arc<Mutex<Vec<String>>>,
+    /// Whether the tagged datetimes read from the document are converted back, see
+    /// `LoroDoc.set_datetime_decoding`.
+    decode_datetime: AtomicBool,
+}
+
+#[derive(Default)]
+struct ReadOnlyState {
+    mode: ReadOnly,
+    /// Whether a read-only document is detached as far as the user is concerned, while it's
+    /// kept detached to make local edits fail. Restored when it's made writable again.
+    detached: bool,
+    /// Whether detached editing was enabled before the document was made read-only.
+    detached_editing: bool,
+}
+
+/// The mode set by `LoroDoc.set_readonly`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReadOnly {
+    #[default]
+    Writable,
+    /// Local edits fail but imports are applied.
+    AllowImport,
+    /// Local edits and imports fail.
+    Frozen,
+}
+
+/// A callback invocation waiting to be run by a [Dispatcher].
+pub type Job = Box<dyn FnOnce(Python<'_>) -> PyResult<()> + Send>;
+
+/// Where the event callbacks of a document run, set by `LoroDoc.set_dispatcher`.
+#[derive(Clone, Default)]
+enum Dispatch {
+    #[default]
+    Inline,
+    /// The sender of the dispatch thread, which runs the jobs or submits them to an executor,
+    /// and stops once it's dropped.
+    Thread(mpsc::Sender<Job>),
+}
+
+/// The shared dispatch setting of a document, cloned into its event callbacks so that they
+/// follow later changes of the setting.
+#[derive(Clone, Default)]
+pub struct Dispatcher(Arc<Mutex<Dispatch>>);
+
+impl Dispatcher {
+    /// Accept "inline", "thread" or a callable executor.
+    pub fn set(&self, dispatcher: &Bound<'_, PyAny>) -> PyResult<()> {
+        let dispatch = if let Ok(name) = dispatcher.downcast::<PyString>() {
+            match name.to_str()? {
+                "inline" => Dispatch::Inline,
+                "thread" => Dispatch::Thread(spawn_dispatch_thread(|py, job| job(py))),
+                other => {
+                    return Err(PyValueError::new_err(format!(
+                        "unknown dispatcher '{}', expected 'inline', 'thread' or a callable",
+                        other
+                    )))
+                }
+            }
+        } else if dispatcher.is_callable() {
+            // Submitting takes the GIL, so it's done by the dispatch thread rather than by the
+            // commit or import emitting the event.
+            let executor = dispatcher.clone().unbind();
+            Dispatch::Thread(spawn_dispatch_thread(move |py, job| {
+                let job = Mutex::new(Some(job));
+                let run = move |args: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| {
+                    let job = job.lock().unwrap().take();
+                    job.map_or(Ok(()), |job| job(args.py()))
+                };
+                let run = PyCFunction::new_closure(py, None, None, run)?;
+                executor.call1(py, (run,))?;
+                Ok(())
+            }))
+        } else {
+            return Err(PyTypeError::new_err(
+                "a dispatcher must be 'inline', 'thread' or a callable",
+            ));
+        };
+        *self.0.lock().unwrap() = dispatch;
+        Ok(())
+    }
+
+    /// Run `job` inline or send it to the dispatch thread.
+    pub fn dispatch(&self, job: Job) {
+        // Cloned so the lock isn't held while waiting for the GIL.
+        let dispatch = self.0.lock().unwrap().clone();
+        match dispatch {
+            // Errors panic like they did before dispatchers existed.
+            Dispatch::Inline => Python::attach(|py| job(py).unwrap()),
+            Dispatch::Thread(sender) => {
+                // The thread only stops once all the senders are dropped, so it's still there.
+                let _ = sender.send(job);
+            }
+        }
+    }
+}
+
+/// Start a thread handling the jobs sent to the returned sender in order with `handle`, until
+/// the sender is dropped. Errors are reported with `sys.unraisablehook`.
+fn spawn_dispatch_thread(
+    handle: impl Fn(Python<'_>, Job) -> PyResult<()> + Send + 'static,
+) -> mpsc::Sender<Job> {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    thread::spawn(move || {
+        for job in receiver {
+            Python::attach(|py| {
+                if let Err(e) = handle(py, job) {
+                    e.write_unraisable(py, None);
+                }
+            });
+        }
+    });
+    sender
+}
+
+fn get(hook: &Mutex<Option<Py<PyAny>>>) -> Option<Py<PyAny>> {
+    Python::attach(|py| hook.lock().unwrap().as_ref().map(|c| c.clone_ref(py)))
+}
+
+fn call_bytes(callback: &Py<PyAny>, bytes: &[u8]) -> PyLoroResult<Vec<u8>> {
+    Python::attach(|py| {
+        let ans = callback.call1(py, (PyBytes::new(py, bytes),))?;
+        let ans = ans.bind(py).downcast::<PyBytes>().map_err(PyErr::from)?;
+        Ok(ans.as_bytes().to_vec())
+    })
+}
+
+fn is_active(subscription: &Weak<Mutex<Option<loro::Subscription>>>) -> bool {
+    let Some(subscription) = subscription.upgrade() else {
+        return false;
+    };
+    subscription.lock().map(|s| s.is_some()).unwrap_or(false)
+}
+
+/// The "file:line" of the Python code calling into the extension.
+fn caller_site() -> Option<String> {
+    Python::attach(|py| {
+        let frame = py
+            .import("sys")
+            .ok()?
+            .call_method1("_getframe", (0,))
+            .ok()?;
+        let file: String = frame
+            .getattr("f_code")
+            .and_then(|code| code.getattr("co_filename"))
+            .and_then(|file| file.extract())
+            .ok()?;
+        let line: u32 = frame.getattr("f_lineno").and_then(|l| l.extract()).ok()?;
+        Some(format!("{}:{}", file, line))
+    })
+}
+
+impl DocHooks {
+    /// The hooks of `doc`, shared with the other `LoroDoc` objects wrapping it.
+    ///
+    /// They live as long as the document itself rather than its `LoroDoc` objects, so hooks
+    /// set through a short-lived object such as `container.doc` keep applying while the
+    /// containers keep the document alive. They're dropped once the document is, the next
+    /// time hooks are created for another document.
+    pub fn of(doc: &LoroDocInner) -> Arc<DocHooks> {
+        let oplog = doc.inner().oplog();
+        let key = Arc::as_ptr(oplog) as usize;
+        let mut registry = REGISTRY.get_or_init(Default::default).lock().unwrap();
+        if let Some((alive, hooks)) = registry.get(&key) {
+            if alive.strong_count() > 0 {
+                return hooks.clone();
+            }
+        }
+        // Dropping the hooks may run Python code that creates documents, so the hooks of the
+        // dead documents are dropped after the lock is released.
+        let mut dead = Vec::new();
+        registry.retain(|_, (alive, hooks)| {
+            let is_alive = alive.strong_count() > 0;
+            if !is_alive {
+                dead.push(hooks.clone());
+            }
+            is_alive
+        });
+        let hooks = Arc::new(DocHooks::default());
+        let alive: Weak<dyn Any + Send + Sync> = Arc::downgrade(oplog);
+        registry.insert(key, (alive, hooks.clone()));
+        drop(registry);
+        drop(dead);
+        hooks
+    }
+
+    /// Wrap a subscription made through the document so it's listed by `active_subscriptions`.
+    pub fn track(
+        &self,
+        subscription: loro::Subscription,
+        kind: &str,
+        target: Option<String>,
+    ) -> Subscription {
+        let info = SubscriptionInfo {
+            kind: kind.to_string(),
+            target,
+            created_at: caller_site(),
+        };
+        let subscription = Subscription(Arc::new(Mutex::new(Some(subscription))));
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.retain(|(_, s)| is_active(s));
+        subscriptions.push((info, Arc::downgrade(&subscription.0)));
+        subscription
+    }
+
+    pub fn active_subscriptions(&self) -> Vec<SubscriptionInfo> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.retain(|(_, s)| is_active(s));
+        subscriptions.iter().map(|(info, _)| info.clone()).collect()
+    }
+
+    /// Unsubscribe all the subscriptions made through the document and return their number.
+    pub fn unsubscribe_all(&self) -> usize {
+        let subscriptions = std::mem::take(&mut *self.subscriptions.lock().unwrap());
+        let mut n = 0;
+        for (_, subscription) in subscriptions {
+            let Some(subscription) = subscription.upgrade() else {
+                continue;
+            };
+            let taken = subscription.lock().unwrap().take();
+            if let Some(taken) = taken {
+                taken.unsubscribe();
+                n += 1;
+            }
+        }
+        n
+    }
+
+    pub fn dispatcher(&self) -> Dispatcher {
+        self.dispatcher.clone()
+    }
+
+    pub fn set_metrics(&self, callback: Option<Py<PyAny>>) {
+        *self.metrics.lock().unwrap() = callback;
+    }
+
+    pub fn set_import_filter(&self, callback: Option<Py<PyAny>>) {
+        *self.import_filter.lock().unwrap() = callback;
+    }
+
+    pub fn set_encryption(&self, encrypt: Option<Py<PyAny>>, decrypt: Option<Py<PyAny>>) {
+        *self.encrypt.lock().unwrap() = encrypt;
+        *self.decrypt.lock().unwrap() = decrypt;
+    }
+
+    /// Encrypt an exported blob, if an encryption hook is set.
+    pub fn encrypt(&self, bytes: Vec<u8>) -> PyLoroResult<Vec<u8>> {
+        let Some(encrypt) = get(&self.encrypt) else {
+            return Ok(bytes);
+        };
+        let mut ans = ENCRYPTED_MAGIC.to_vec();
+        ans.extend(call_bytes(&encrypt, &bytes)?);
+        Ok(ans)
+    }
+
+    /// Fail if an encryption hook is set, for the exports that can't be encrypted.
+    pub fn check_plain_export(&self) -> PyLoroResult<()> {
+        if self.encrypt.lock().unwrap().is_some() {
+            return Err(PyLoroError::Error(
+                "JSON exports can't be encrypted, remove the encryption hook to export JSON"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decrypt a blob before importing it, then decompress it if it was exported compressed.
+    ///
+    /// Fails if the blob is encrypted but no decryption hook is set, or the other way around,
+    /// so plain and encrypted blobs can't be mixed up.
+    pub fn decrypt<'a>(&self, bytes: &'a [u8]) -> PyLoroResult<Cow<'a, [u8]>> {
+        let payload = bytes.strip_prefix(ENCRYPTED_MAGIC);
+        let bytes = match (get(&self.decrypt), payload) {
+            (None, None) => Cow::Borrowed(bytes),
+            (Some(decrypt), Some(payload)) => Cow::Owned(call_bytes(&decrypt, payload)?),
+            (None, Some(_)) => {
+                return Err(PyLoroError::Error(
+                    "the blob is encrypted, set a decryption hook with `set_encryption`"
+                        .to_string(),
+                ))
+            }
+            (Some(_), None) => {
+                return Err(PyLoroError::Error(
+                    "the blob isn't encrypted but the document has a decryption hook".to_string(),
+                ))
+            }
+        };
+        compress::decompress(bytes)
+    }
+
+    /// Record the keys of a text style config set on the document.
+    pub fn add_text_style_keys(&self, keys: &[String]) {
+        let mut known = self.text_style_keys.lock().unwrap();
+        for key in keys {
+            if !known.contains(key) {
+                known.push(key.clone());
+            }
+        }
+    }
+
+    pub fn text_style_keys(&self) -> Arc<Mutex<Vec<String>>> {
+        self.text_style_keys.clone()
+    }
+
+    pub fn set_datetime_decoding(&self, enabled: bool) {
+        if enabled {
+            codec::use_datetime_decoding();
+        }
+        self.decode_datetime.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn decodes_datetime(&self) -> bool {
+        self.decode_datetime.load(Ordering::Relaxed)
+    }
+
+    /// Whether the document of `container` converts its tagged datetimes back when they're
+    /// read. Detached containers don't.
+    pub fn datetime_decoding<C: ContainerTrait>(container: &C) -> bool {
+        codec::datetime_decoding_used()
+            && container
+                .doc()
+                .is_some_and(|doc| DocHooks::of(&doc).decodes_datetime())
+    }
+
+    pub fn set_quota(&self, callback: Option<Py<PyAny>>) {
+        *self.quota.lock().unwrap() = callback;
+    }
+
+    pub fn set_pending_hook(&self, callback: Option<Py<PyAny>>) {
+        *self.pending_hook.lock().unwrap() = callback;
+    }
+
+    /// The spans of the imported changes waiting for their dependencies.
+    pub fn pending_changes(&self) -> Vec<IdSpan> {
+        let mut ans = self
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&peer, &(start, end))| IdSpan::from(loro::IdSpan::new(peer, start, end)))
+            .collect::<Vec<_>>();
+        ans.sort_unstable_by_key(|span| span.peer);
+        ans
+    }
+
+    /// Record the changes `import` left pending, and report the pending changes it applied
+    /// to the pending hook.
+    pub fn track_pending(&self, doc: &LoroDocInner, import: &loro::ImportStatus) {
+        let applied = {
+            let mut pending = self.pending.lock().unwrap();
+            for (&peer, &(start, end)) in import.pending.iter().flat_map(|range| range.iter()) {
+                let range = pending.entry(peer).or_insert((start, end));
+                *range = (range.0.min(start), range.1.max(end));
+            }
+            if pending.is_empty() {
+                return;
+            }
+            let vv = doc.oplog_vv();
+            let mut applied = Vec::new();
+            pending.retain(|&peer, range| {
+                let known = vv.get(&peer).copied().unwrap_or(0);
+                if known > range.0 {
+                    applied.push(loro::IdSpan::new(peer, range.0, known.min(range.1)));
+                    range.0 = known;
+                }
+                range.0 < range.1
+            });
+            applied
+        };
+        if applied.is_empty() {
+            return;
+        }
+        let Some(callback) = get(&self.pending_hook) else {
+            return;
+        };
+        let spans = applied.into_iter().map(IdSpan::from).collect::<Vec<_>>();
+        Python::attach(|py| {
+            if let Err(e) = callback.call1(py, (spans,)) {
+                e.write_unraisable(py, None);
+            }
+        });
+    }
+
+    /// Ask the quota hook whether `doc` may grow by the pending ops of its transaction.
+    pub fn check_commit_quota(&self, doc: &LoroDocInner) -> PyLoroResult<()> {
+        let ops = doc.get_pending_txn_len();
+        if ops == 0 {
+            return Ok(());
+        }
+        self.check_quota(doc, "commit", ops, None)
+    }
+
+    fn check_quota(
+        &self,
+        doc: &LoroDocInner,
+        operation: &'static str,
+        ops: usize,
+        bytes: Option<usize>,
+    ) -> PyLoroResult<()> {
+        let Some(quota) = get(&self.quota) else {
+            return Ok(());
+        };
+        let request = QuotaRequest {
+            operation: operation.to_string(),
+            ops,
+            bytes,
+            doc_ops: committed_ops(doc),
+        };
+        let accepted = Python::attach(|py| quota.call1(py, (request,))?.is_truthy(py))?;
+        if accepted {
+            Ok(())
+        } else {
+            Err(PyLoroError::Error(format!(
+                "{operation} rejected by the quota hook"
+            )))
+        }
+    }
+
+    /// Stamp every change of `doc` with the timestamp returned by `callback`.
+    pub fn set_clock(&self, doc: &LoroDocInner, callback: Option<Py<PyAny>>) {
+        let subscription = callback.map(|callback| {
+            doc.subscribe_pre_commit(Box::new(move |payload| {
+                Python::attach(|py| {
+                    match callback.call0(py).and_then(|ts| ts.extract::<i64>(py)) {
+                        Ok(ts) => {
+                            payload.modifier.set_timestamp(ts);
+                        }
+                        // The commit can't fail from here, it keeps the default timestamp.
+                        Err(e) => e.write_unraisable(py, None),
+                    }
+                });
+                true
+            }))
+        });
+        // Dropping the previous subscription unsubscribes it.
+        *self.clock.lock().unwrap() = subscription;
+    }
+
+    pub fn readonly(&self) -> ReadOnly {
+        self.readonly.lock().unwrap().mode
+    }
+
+    /// Make local edits of `doc` fail, by detaching it with detached editing disabled.
+    ///
+    /// Making it writable again restores whether it was detached and its detached editing
+    /// setting.
+    pub fn set_readonly(&self, doc: &LoroDocInner, mode: ReadOnly) {
+        let mut state = self.readonly.lock().unwrap();
+        let previous = state.mode;
+        if previous == ReadOnly::Writable && mode != ReadOnly::Writable {
+            state.detached = doc.is_detached();
+            state.detached_editing = doc.is_detached_editing_enabled();
+        }
+        state.mode = mode;
+        if mode != ReadOnly::Writable {
+            doc.set_detached_editing(false);
+            doc.detach();
+            return;
+        }
+        if previous != ReadOnly::Writable {
+            doc.set_detached_editing(state.detached_editing);
+            if !state.detached {
+                doc.attach();
+            }
+        }
+    }
+
+    /// Record whether a read-only `doc` is detached after `attach`, `detach` or a checkout,
+    /// and keep it detached.
+    pub fn keep_readonly(&self, doc: &LoroDocInner, detached: bool) {
+        let mut state = self.readonly.lock().unwrap();
+        if state.mode != ReadOnly::Writable {
+            state.detached = detached;
+            doc.detach();
+        }
+    }
+
+    /// Whether `doc` is detached, leaving out the detaching that makes it read-only.
+    pub fn is_detached(&self, doc: &LoroDocInner) -> bool {
+        let state = self.readonly.lock().unwrap();
+        match state.mode {
+            ReadOnly::Writable => doc.is_detached(),
+            _ => state.detached,
+        }
+    }
+
+    /// Check that `doc` accepts imports.
+    ///
+    /// Returns whether the state of the read-only `doc` must follow the import with
+    /// [DocHooks::follow_import], which is when the user didn't detach it.
+    pub fn start_import(&self, doc: &LoroDocInner) -> PyLoroResult<bool> {
+        match self.readonly() {
+            ReadOnly::Writable => Ok(false),
+            ReadOnly::AllowImport => Ok(!self.is_detached(doc)),
+            ReadOnly::Frozen => Err(PyLoroError::Error(
+                "cannot import into a read-only document".to_string(),
+            )),
+        }
+    }
+
+    /// Apply an import with `origin` to the state of a read-only `doc`, which only recorded it
+    /// in its history as it's kept detached.
+    ///
+    /// Loro emits the resulting events as a checkout, [DocHooks::event] reports them as the
+    /// import they are.
+    pub fn follow_import(&self, doc: &LoroDocInner, follow: bool, origin: Option<&str>) {
+        if follow {
+            *self.followed_import.lock().unwrap() = Some(origin.unwrap_or_default().to_string());
+            doc.checkout_to_latest();
+            doc.detach();
+            *self.followed_import.lock().unwrap() = None;
+        }
+    }
+
+    /// How `e` was triggered, telling the imports a read-only document follows from checkouts.
+    pub fn triggered_by(&self, e: &loro::event::DiffEvent<'_>) -> loro::EventTriggerKind {
+        match e.triggered_by {
+            loro::EventTriggerKind::Checkout if self.followed_import.lock().unwrap().is_some() => {
+                loro::EventTriggerKind::Import
+            }
+            kind => kind,
+        }
+    }
+
+    /// Convert an event of the document, reporting the imports followed by a read-only document
+    /// as imports with their origin.
+    pub fn event(&self, e: loro::event::DiffEvent<'_>) -> DiffEvent {
+        let followed = match e.triggered_by {
+            loro::EventTriggerKind::Checkout => self.followed_import.lock().unwrap().clone(),
+            _ => None,
+        };
+        let mut event = DiffEvent::from(e);
+        if let Some(origin) = followed {
+            event.triggered_by = EventTriggerKind::Import;
+            event.origin = origin;
+        }
+        event
+    }
+
+    /// Ask the import filter and the quota hook whether `updates` may be imported into `doc`.
+    ///
+    /// The updates are imported together into a fork of `doc` first, so the hooks see the
+    /// changes they really contain rather than what the headers of the blobs claim. Fails if
+    /// a hook returns a falsy value or raises.
+    pub fn check_import(&self, doc: &LoroDocInner, updates: &[Updates<'_>]) -> PyLoroResult<()> {
+        if !self.checks_imports() {
+            return Ok(());
+        }
+        let trial = TrialImport::new(doc, updates)?;
+        self.check_trial_import(doc, updates, trial)
+    }
+
+    /// Like [DocHooks::check_import] for updates already imported into a fork by `trial`, so
+    /// the document isn't forked again.
+    pub fn check_trial_import(
+        &self,
+        doc: &LoroDocInner,
+        updates: &[Updates<'_>],
+        trial: TrialImport,
+    ) -> PyLoroResult<()> {
+        if !self.checks_imports() {
+            return Ok(());
+        }
+        let bytes: usize = updates.iter().map(|update| update.len()).sum();
+        let request = ImportRequest::new(updates, trial)?;
+        let ops = request.ops();
+        if let Some(filter) = get(&self.import_filter) {
+            let accepted = Python::attach(|py| filter.call1(py, (request,))?.is_truthy(py))?;
+            if !accepted {
+                return Err(PyLoroError::Error(
+                    "import rejected by the import filter".to_string(),
+                ));
+            }
+        }
+        self.check_quota(doc, "import", ops, Some(bytes))
+    }
+
+    fn checks_imports(&self) -> bool {
+        get(&self.import_filter).is_some() || get(&self.quota).is_some()
+    }
+
+    /// Start measuring `operation`. Call [Measure::finish] once it succeeds.
+    ///
+    /// This is a no-op if no metrics hook is set.
+    pub fn measure<'a>(&self, doc: &'a LoroDocInner, operation: &'static str) -> Measure<'a> {
+        let callback = get(&self.metrics);
+        Measure {
+            inner: callback.map(|callback| MeasureInner {
+                callback,
+                doc,
+                operation,
+                start: Instant::now(),
+                ops_before: committed_ops(doc),
+            }),
+        }
+    }
+}
+
+/// The number of ops in the history, leaving out the pending transaction that `len_ops` counts.
+fn committed_ops(doc: &LoroDocInner) -> usize {
+    doc.len_ops() - doc.get_pending_txn_len()
+}
+
+pub struct Measure<'a> {
+    inner: Option<MeasureInner<'a>>,
+}
+
+struct MeasureInner<'a> {
+    callback: Py<PyAny>,
+    doc: &'a LoroDocInner,
+    operation: &'static str,
+    start: Instant,
+    ops_before: usize,
+}
+
+impl Measure<'_> {
+    /// Report the operation to the metrics hook, with the number of bytes it read or wrote.
+    pub fn finish(self, bytes: usize) {
+        let Some(inner) = self.inner else {
+            return;
+        };
+        let metrics = OperationMetrics {
+            operation: inner.operation.to_string(),
+            duration: inner.start.elapsed().as_secs_f64(),
+            bytes,
+            ops: committed_ops(inner.doc).saturating_sub(inner.ops_before),
+        };
+        Python::attach(|py| {
+            // A failing hook must not fail the operation it observes.
+            if let Err(e) = inner.callback.call1(py, (metrics,)) {
+                e.write_unraisable(py, None);
+            }
+        });
+    }
+}
+
+/// The measurements of one document operation, passed to the hook set by
+/// `LoroDoc.set_metrics_hook`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct OperationMetrics {
+    /// The name of the operation, e.g. `import` or `commit`.
+    pub operation: String,
+    /// The wall time of the operation in seconds.
+    pub duration: f64,
+    /// The number of bytes imported or exported, 0 for commits.
+    pub bytes: usize,
+    /// The number of ops the operation added to the document.
+    pub ops: usize,
+}
+
+impl fmt::Display for OperationMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "OperationMetrics(operation='{}', duration={}, bytes={}, ops={})",
+            self.operation, self.duration, self.bytes, self.ops
+        )
+    }
+}
+
+#[pymethods]
+impl OperationMetrics {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// The updates passed to an import.
+#[derive(Clone, Copy)]
+pub enum Updates<'a> {
+    /// A blob exported by `export`.
+    Blob(&'a [u8]),
+    /// The JSON exported by `export_json_updates`.
+    Json(&'a str),
+}
+
+impl Updates<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Updates::Blob(bytes) => bytes.len(),
+            Updates::Json(json) => json.len(),
+        }
+    }
+
+    fn import_into(&self, doc: &LoroDocInner) -> PyLoroResult<loro::ImportStatus> {
+        let status = match self {
+            Updates::Blob(bytes) => doc.import(bytes)?,
+            Updates::Json(json) => doc.import_json_updates(*json)?,
+        };
+        Ok(status)
+    }
+}
+
+/// Updates imported into a fork of a document, to look at what they change before they're
+/// imported into the document itself.
+pub struct TrialImport {
+    fork: LoroDocInner,
+    status: loro::ImportStatus,
+}
+
+impl TrialImport {
+    /// Import `updates` in order into a single fork of `doc`, which is O(n) like
+    /// `LoroDoc.fork`.
+    ///
+    /// The status covers all the updates, so the ops several of them contain are counted
+    /// once, and the changes an update waits for are found in the others.
+    pub fn new(doc: &LoroDocInner, updates: &[Updates<'_>]) -> PyLoroResult<Self> {
+        let fork = doc.fork();
+        let mut pending: FxHashMap<PeerID, (Counter, Counter)> = FxHashMap::default();
+        for update in updates {
+            let status = update.import_into(&fork)?;
+            for (&peer, &(start, end)) in status.pending.iter().flat_map(|p| p.iter()) {
+                let range = pending.entry(peer).or_insert((start, end));
+                *range = (range.0.min(start), range.1.max(end));
+            }
+        }
+        let (before, after) = (doc.oplog_vv(), fork.oplog_vv());
+        // A later update may have provided the dependencies of the pending changes.
+        pending.retain(|peer, range| {
+            range.0 = range.0.max(after.get(peer).copied().unwrap_or(0));
+            range.0 < range.1
+        });
+        let success = after
+            .iter()
+            .filter_map(|(&peer, &end)| {
+                let start = before.get(&peer).copied().unwrap_or(0);
+                (start < end).then_some((peer, (start, end)))
+            })
+            .collect::<FxHashMap<_, _>>();
+        let status = loro::ImportStatus {
+            success: loro::VersionRange::from_map(success),
+            pending: (!pending.is_empty()).then(|| loro::VersionRange::from_map(pending)),
+        };
+        Ok(Self { fork, status })
+    }
+
+    /// Whether the updates were applied in full, without changes waiting for dependencies
+    /// the document lacks.
+    pub fn is_complete(&self) -> bool {
+        self.status.pending.is_none()
+    }
+}
+
+/// An import about to be applied, passed to the filter set by `LoroDoc.set_import_filter`.
+#[pyclass(frozen, str)]
+pub struct ImportRequest {
+    meta: Option<ImportBlobMetadata>,
+    spans: Vec<loro::IdSpan>,
+    /// A fork of the document with the updates imported.
+    fork: LoroDocInner,
+    containers: OnceLock<Vec<ContainerID>>,
+}
+
+impl ImportRequest {
+    fn new(updates: &[Updates<'_>], trial: TrialImport) -> PyLoroResult<Self> {
+        let meta = match updates {
+            [Updates::Blob(bytes)] => Some(LoroDocInner::decode_import_blob_meta(bytes, false)?),
+            _ => None,
+        };
+        let TrialImport { fork, status } = trial;
+        // The changes waiting for their dependencies are applied by a later import, so they
+        // are part of the request too.
+        let mut spans = status
+            .success
+            .iter()
+            .chain(status.pending.iter().flat_map(|pending| pending.iter()))
+            .map(|(&peer, &(start, end))| loro::IdSpan::new(peer, start, end))
+            .filter(|span| span.counter.start < span.counter.end)
+            .collect::<Vec<_>>();
+        spans.sort_unstable_by_key(|span| (span.peer, span.counter.start));
+        Ok(Self {
+            meta: meta.map(Into::into),
+            spans,
+            fork,
+            containers: OnceLock::new(),
+        })
+    }
+
+    fn ops(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|s| (s.counter.end - s.counter.start) as usize)
+            .sum()
+    }
+
+    /// The containers touched by the ops this import would add.
+    fn affected_containers(&self) -> Vec<ContainerID> {
+        let mut seen = FxHashSet::default();
+        let mut ans = Vec::new();
+        for &span in &self.spans {
+            for change in self.fork.export_json_in_id_span(span) {
+                for op in change.ops {
+                    if seen.insert(op.container.clone()) {
+                        ans.push(op.container.into());
+                    }
+                }
+            }
+        }
+        ans
+    }
+}
+
+impl fmt::Display for ImportRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ImportRequest(mode={}, peers={:?}, ops={})",
+            self.meta
+                .as_ref()
+                .map_or("Json".to_string(), |meta| format!("{:?}", meta.mode)),
+            self.peers(),
+            self.ops()
+        )
+    }
+}
+
+#[pymethods]
+impl ImportRequest {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    /// The metadata decoded from the header of the blob, `None` for JSON updates and for
+    /// batches of several blobs.
+    ///
+    /// The header is written by the sender, so `peers` and `spans`, which come from the
+    /// decoded changes, are what filters should trust.
+    #[getter]
+    pub fn meta(&self) -> Option<ImportBlobMetadata> {
+        self.meta.clone()
+    }
+
+    /// The peers that authored the new changes of the import.
+    #[getter]
+    pub fn peers(&self) -> Vec<PeerID> {
+        let mut peers = self.spans.iter().map(|s| s.peer).collect::<Vec<_>>();
+        peers.dedup();
+        peers
+    }
+
+    /// The new ops of the import, sorted by peer.
+    ///
+    /// Ops the document already has are left out, while the ops waiting for missing
+    /// dependencies are included.
+    #[getter]
+    pub fn spans(&self) -> Vec<IdSpan> {
+        self.spans.iter().map(|&s| s.into()).collect()
+    }
+
+    /// The containers the new ops of the import apply to, decoded on first access.
+    #[getter]
+    pub fn containers(&self) -> Vec<ContainerID> {
+        self.containers
+            .get_or_init(|| self.affected_containers())
+            .clone()
+    }
+}
+
+/// The growth a commit or an import would cause, passed to the hook set by
+/// `LoroDoc.set_quota_hook`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct QuotaRequest {
+    /// `commit` or `import`.
+    pub operation: String,
+    /// The number of ops the operation would add.
+    pub ops: usize,
+    /// The size in bytes of the imported blobs or JSON, `None` for commits.
+    pub bytes: Option<usize>,
+    /// The number of ops in the document before the operation.
+    pub doc_ops: usize,
+}
+
+impl fmt::Display for QuotaRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "QuotaRequest(operation='{}', ops={}, bytes={}, doc_ops={})",
+            self.operation,
+            self.ops,
+            self.bytes
+                .map_or("None".to_string(), |bytes| bytes.to_string()),
+            self.doc_ops
+        )
+    }
+}
+
+#[pymethods]
+impl QuotaRequest {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
@@ -3,11 +3,25 @@ use loro::LORO_VERSION;
 use pyo3::prelude::*;
 
 mod awareness;
+mod batch;
+mod blob;
+mod chunk;
+mod codec;
+mod compress;
 mod container;
 mod convert;
+mod display;
 mod doc;
 mod err;
 mod event;
+mod hash;
+mod history;
+mod hooks;
+mod logging;
+mod merge;
+mod msgpack;
+mod peer;
+mod stats;
 mod undo;
 mod value;
 mod version;
@@ -22,6 +36,18 @@ fn loro_py(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     version::register_class(m)?;
     undo::register_class(m)?;
     awareness::register_class(m)?;
+    hooks::register_class(m)?;
+    history::register_class(m)?;
+    stats::register_class(m)?;
+    blob::register_class(m)?;
+    merge::register_class(m)?;
+    codec::register_functions(m)?;
+    compress::register_functions(m)?;
+    msgpack::register_functions(m)?;
+    logging::register_functions(m)?;
+    peer::register_functions(m)?;
+    blob::register_functions(m)?;
+    merge::register_functions(m)?;
     m.add("LORO_VERSION", LORO_VERSION)?;
     Ok(())
 }
@@ -0,0 +1,119 @@
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+use pyo3::prelude::*;
+use tracing::{
+    field::{Field, Visit},
+    subscriber::Interest,
+    Event, Level, Metadata, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+use crate::err::{PyLoroError, PyLoroResult};
+
+/// The name of the Python logger the events are sent to. Each Rust module logs to a child
+/// logger, e.g. `loro.loro_internal.oplog`.
+const LOGGER: &str = "loro";
+/// `logging.WARNING`
+const DEFAULT_LEVEL: u8 = 30;
+
+/// The minimum Python logging level of the forwarded events.
+static LEVEL: AtomicU8 = AtomicU8::new(DEFAULT_LEVEL);
+static INSTALLED: OnceLock<Result<(), String>> = OnceLock::new();
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(enable_logging, m)?)?;
+    Ok(())
+}
+
+/// Forward loro's internal log events to the `logging` module.
+///
+/// Events at `level` or above (a `logging` level such as `logging.INFO`) are logged to the
+/// `loro` logger, or one of its children named after the Rust module, so they can be
+/// configured like any other logger. Call it again to change the level.
+///
+/// Fails if another tracing subscriber is already installed in the process.
+#[pyfunction]
+#[pyo3(signature = (level=DEFAULT_LEVEL))]
+pub fn enable_logging(level: u8) -> PyLoroResult<()> {
+    LEVEL.store(level, Ordering::Relaxed);
+    INSTALLED
+        .get_or_init(|| {
+            tracing::subscriber::set_global_default(tracing_subscriber::registry().with(PyLogger))
+                .map_err(|e| e.to_string())
+        })
+        .clone()
+        .map_err(PyLoroError::Error)
+}
+
+fn python_level(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        Level::DEBUG => 10,
+        Level::TRACE => 5,
+    }
+}
+
+struct PyLogger;
+
+impl<S: Subscriber> Layer<S> for PyLogger {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The level can change at runtime, so the answer can't be cached per callsite.
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        python_level(metadata.level()) >= LEVEL.load(Ordering::Relaxed)
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut message = Message::default();
+        event.record(&mut message);
+        let name = format!("{LOGGER}.{}", metadata.target().replace("::", "."));
+        Python::attach(|py| {
+            let result = py
+                .import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| {
+                    logger.call_method1(
+                        "log",
+                        (python_level(metadata.level()), message.0.trim_start()),
+                    )
+                });
+            // Logging must never turn into an exception in an unrelated call.
+            if let Err(e) = result {
+                e.write_unraisable(py, None);
+            }
+        });
+    }
+}
+
+/// The `message` field followed by the other fields as `key=value`.
+#[derive(Default)]
+struct Message(String);
+
+impl Visit for Message {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.insert_str(0, value);
+        } else {
+            let _ = write!(self.0, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{value:?}"));
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::{collections::HashMap, fmt};
+
+use loro::{ExportMode, LoroDoc as LoroDocInner};
+use pyo3::{exceptions::PyTypeError, prelude::*, types::PyBytes};
+
+use crate::{
+    compress,
+    doc::LoroDoc,
+    err::PyLoroResult,
+    value::{ContainerID, ValueOrContainer},
+};
+
+pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<MergeConflict>()?;
+    Ok(())
+}
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(merge, m)?)?;
+    Ok(())
+}
+
+/// A map key written by both sides of a `merge`.
+///
+/// A value is `None` if the key was deleted. `value` is the one the merge kept.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct MergeConflict {
+    pub container: ContainerID,
+    pub key: String,
+    pub ours: Option<ValueOrContainer>,
+    pub theirs: Option<ValueOrContainer>,
+    pub value: Option<ValueOrContainer>,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[pymethods]
+impl MergeConflict {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Merge two versions of a document derived from `base`, like a three-way merge in Git.
+///
+/// Each argument is a `LoroDoc` or a blob exported from one. Returns a new document with
+/// the history of all three, and the map keys that both `ours` and `theirs` changed since
+/// `base`, sorted by container and key. Loro merges them without failing, keeping one of
+/// the values; the conflicts let the application review them.
+#[pyfunction]
+pub fn merge(
+    base: &Bound<'_, PyAny>,
+    ours: &Bound<'_, PyAny>,
+    theirs: &Bound<'_, PyAny>,
+) -> PyLoroResult<(LoroDoc, Vec<MergeConflict>)> {
+    let doc = LoroDocInner::new();
+    let base = load(&doc, base)?;
+    let ours = load(&doc, ours)?;
+    let theirs = load(&doc, theirs)?;
+
+    let mut ours_keys = HashMap::new();
+    for (container, diff) in doc.diff(&base, &ours)?.iter() {
+        if let loro::event::Diff::Map(map) = diff {
+            for (key, value) in map.updated.iter() {
+                ours_keys.insert((container.clone(), key.to_string()), value.clone());
+            }
+        }
+    }
+    let mut conflicts = Vec::new();
+    for (container, diff) in doc.diff(&base, &theirs)?.iter() {
+        let loro::event::Diff::Map(map) = diff else {
+            continue;
+        };
+        for (key, theirs) in map.updated.iter() {
+            let Some(ours) = ours_keys.remove(&(container.clone(), key.to_string())) else {
+                continue;
+            };
+            conflicts.push(MergeConflict {
+                container: container.into(),
+                key: key.to_string(),
+                ours: ours.map(Into::into),
+                theirs: theirs.clone().map(Into::into),
+                value: doc.get_map(container.clone()).get(key).map(Into::into),
+            });
+        }
+    }
+    conflicts.sort_by_key(|c| (c.container.to_string(), c.key.clone()));
+    Ok((doc.into(), conflicts))
+}
+
+/// Import `source` into `doc` and return its version.
+fn load(doc: &LoroDocInner, source: &Bound<'_, PyAny>) -> PyLoroResult<loro::Frontiers> {
+    if let Ok(source) = source.downcast::<LoroDoc>() {
+        let source = &source.get().doc;
+        doc.import(&source.export(ExportMode::all_updates())?)?;
+        return Ok(source.oplog_frontiers());
+    }
+    let Ok(bytes) = source.downcast::<PyBytes>() else {
+        return Err(PyTypeError::new_err("expected a LoroDoc or bytes").into());
+    };
+    let bytes = compress::decompress(bytes.as_bytes().into())?;
+    let source = LoroDocInner::new();
+    source.import(&bytes)?;
+    doc.import(&bytes)?;
+    Ok(source.oplog_frontiers())
+}
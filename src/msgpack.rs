@@ -0,0 +1,78 @@
+use std::{borrow::Cow, io::Cursor};
+
+use pyo3::{prelude::*, types::PyBytes};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+
+use crate::{
+    err::{PyLoroError, PyLoroResult},
+    value::LoroValue,
+};
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(to_msgpack, m)?)?;
+    m.add_function(wrap_pyfunction!(from_msgpack, m)?)?;
+    Ok(())
+}
+
+/// Serialize a value to msgpack.
+///
+/// Map keys are written in sorted order so the output is deterministic. Container ids are
+/// written as strings prefixed with `🦜:`, e.g. `"🦜:cid:0@<peer>:Text"` or
+/// `"🦜:cid:root-<name>:Map"`. `from_msgpack` reads any string with that prefix back as a
+/// container id, so a user string starting with `"🦜:cid:"` doesn't round-trip as a string.
+#[pyfunction]
+pub fn to_msgpack(value: LoroValue) -> Cow<'static, [u8]> {
+    Cow::Owned(encode(&value.0))
+}
+
+/// Deserialize a value produced by `to_msgpack`.
+#[pyfunction]
+pub fn from_msgpack(data: Bound<'_, PyBytes>) -> PyLoroResult<LoroValue> {
+    decode(data.as_bytes()).map(LoroValue::from)
+}
+
+pub fn encode(value: &loro::LoroValue) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let mut serializer = rmp_serde::Serializer::new(&mut buf).with_human_readable();
+    Sorted(value)
+        .serialize(&mut serializer)
+        .expect("writing msgpack to a Vec can't fail");
+    buf
+}
+
+pub fn decode(data: &[u8]) -> PyLoroResult<loro::LoroValue> {
+    let mut reader = Cursor::new(data);
+    let mut deserializer = rmp_serde::Deserializer::new(&mut reader).with_human_readable();
+    let value = loro::LoroValue::deserialize(&mut deserializer)
+        .map_err(|e| PyLoroError::Error(format!("invalid msgpack: {e}")))?;
+    let trailing = data.len() - reader.position() as usize;
+    if trailing != 0 {
+        return Err(PyLoroError::Error(format!(
+            "invalid msgpack: {trailing} trailing bytes"
+        )));
+    }
+    Ok(value)
+}
+
+/// A value serialized with its map keys sorted, and binaries as msgpack binaries rather than
+/// lists of bytes. Everything else is left to the serde impl of `LoroValue`.
+struct Sorted<'a>(&'a loro::LoroValue);
+
+impl Serialize for Sorted<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            loro::LoroValue::Binary(b) => serializer.serialize_bytes(b),
+            loro::LoroValue::List(list) => serializer.collect_seq(list.iter().map(Sorted)),
+            loro::LoroValue::Map(map) => {
+                let mut entries = map.iter().collect::<Vec<_>>();
+                entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                let mut ser = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    ser.serialize_entry(key, &Sorted(value))?;
+                }
+                ser.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Mutex;
+
+use loro::PeerID;
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyString};
+
+use crate::hash::stable_hash;
+
+pub fn register_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(peer_id_from_string, m)?)?;
+    m.add_function(wrap_pyfunction!(set_rng_seed, m)?)?;
+    Ok(())
+}
+
+/// Derive a stable PeerID from a string such as `"user-42/device-A"`.
+///
+/// The same string always maps to the same PeerID, across processes and versions, so an
+/// application can reuse the id of a user's device instead of picking a random one.
+///
+/// Two writers must never share a PeerID: derive it from something unique to each
+/// concurrent writer (e.g. the user *and* the device or session), not from the user alone.
+/// Distinct strings collide with a probability of about `n² / 2⁶⁵` for `n` strings.
+#[pyfunction]
+pub fn peer_id_from_string(s: &str) -> PeerID {
+    hash_peer_id(s)
+}
+
+/// The state of the generator seeded by `set_rng_seed`, `None` while peer ids are random.
+static RNG: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Seed the PeerIDs picked for new documents, or make them random again with `None`.
+///
+/// Once seeded, `LoroDoc()`, `fork` and `fork_at` draw their PeerIDs from a generator, so a
+/// simulation of several peers or a doctest makes the same changes on every run. The
+/// fractional index jitter of trees stays random: leave it at 0 for reproducible trees.
+///
+/// A seeded process must not edit the same documents as other processes seeded alike, as
+/// they would share PeerIDs.
+#[pyfunction]
+#[pyo3(signature = (seed))]
+pub fn set_rng_seed(seed: Option<u64>) {
+    *RNG.lock().unwrap() = seed;
+}
+
+/// The next PeerID of the generator seeded by `set_rng_seed`, `None` if it isn't seeded.
+pub fn next_peer_id() -> Option<PeerID> {
+    RNG.lock().unwrap().as_mut().map(seeded_peer_id)
+}
+
+/// Step the splitmix64 generator at `state`.
+pub fn seeded_peer_id(state: &mut u64) -> PeerID {
+    loop {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        let peer = z ^ (z >> 31);
+        // `PeerID::MAX` is reserved by loro.
+        if peer != PeerID::MAX {
+            return peer;
+        }
+    }
+}
+
+/// `PeerID::MAX` is reserved by loro, so it's mapped to another value.
+fn hash_peer_id(s: &str) -> PeerID {
+    match stable_hash(s.as_bytes()) {
+        PeerID::MAX => PeerID::MAX - 1,
+        hash => hash,
+    }
+}
+
+/// Accept either a PeerID or a string passed to `peer_id_from_string`.
+pub fn peer_id_from_object(ob: &Bound<'_, PyAny>) -> PyResult<PeerID> {
+    if let Ok(s) = ob.downcast::<PyString>() {
+        return Ok(hash_peer_id(s.to_str()?));
+    }
+    ob.extract::<PeerID>()
+        .map_err(|_| PyValueError::new_err("peer id must be an int or a str"))
+}
+
+pub fn peer_id_from_optional_object(ob: &Bound<'_, PyAny>) -> PyResult<Option<PeerID>> {
+    if ob.is_none() {
+        return Ok(None);
+    }
+    peer_id_from_object(ob).map(Some)
+}
@@ -0,0 +1,319 @@
+use std::{borrow::Cow, fmt};
+
+use loro::{ContainerTrait, LoroDoc as LoroDocInner, LoroValue};
+use pyo3::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    value::{ContainerID, ContainerType},
+    version::Frontiers,
+};
+
+use crate::err::PyLoroResult;
+
+pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<StorageStats>()?;
+    m.add_class::<GcResult>()?;
+    m.add_class::<DocAnalysis>()?;
+    m.add_class::<ContainerAnalysis>()?;
+    Ok(())
+}
+
+/// The size of a document, returned by `LoroDoc.storage_stats`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct StorageStats {
+    /// The size in bytes of the current state, exported without history.
+    pub state_bytes: usize,
+    /// The size in bytes of the whole history, exported as updates.
+    pub oplog_bytes: usize,
+    /// The number of changes in the `OpLog`.
+    pub changes: usize,
+    /// The number of ops in the `OpLog`.
+    pub ops: usize,
+    /// The number of containers reachable from the root containers.
+    pub containers: usize,
+    /// Whether the history before some version has been trimmed by a shallow snapshot.
+    pub is_shallow: bool,
+}
+
+impl fmt::Display for StorageStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "StorageStats(state_bytes={}, oplog_bytes={}, changes={}, ops={}, containers={}, is_shallow={})",
+            self.state_bytes,
+            self.oplog_bytes,
+            self.changes,
+            self.ops,
+            self.containers,
+            if self.is_shallow { "True" } else { "False" }
+        )
+    }
+}
+
+#[pymethods]
+impl StorageStats {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl StorageStats {
+    pub fn new(doc: &LoroDocInner) -> PyLoroResult<Self> {
+        Ok(Self {
+            state_bytes: doc.export(loro::ExportMode::StateOnly(None))?.len(),
+            oplog_bytes: doc.export(loro::ExportMode::all_updates())?.len(),
+            changes: doc.len_changes(),
+            ops: doc.len_ops(),
+            containers: containers(doc).len(),
+            is_shallow: doc.is_shallow(),
+        })
+    }
+}
+
+/// A shallow snapshot trimming the history, returned by `LoroDoc.gc`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct GcResult {
+    /// The shallow snapshot. Load it in place of the document to drop the history.
+    pub snapshot: Vec<u8>,
+    /// The version the history of the snapshot starts from.
+    pub shallow_since: Frontiers,
+    /// How much smaller the snapshot is than a full snapshot, in bytes.
+    pub bytes_saved: usize,
+    /// The number of changes left out of the snapshot.
+    pub changes_dropped: usize,
+}
+
+impl fmt::Display for GcResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "GcResult(snapshot=<{} bytes>, shallow_since={}, bytes_saved={}, changes_dropped={})",
+            self.snapshot.len(),
+            self.shallow_since.__repr__(),
+            self.bytes_saved,
+            self.changes_dropped
+        )
+    }
+}
+
+#[pymethods]
+impl GcResult {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl GcResult {
+    /// Trim the history of `doc` before `frontiers`. Nothing is trimmed if they're empty.
+    pub fn new(doc: &LoroDocInner, frontiers: loro::Frontiers) -> PyLoroResult<Self> {
+        let full = doc.export(loro::ExportMode::Snapshot)?;
+        if frontiers.is_empty() {
+            return Ok(Self {
+                snapshot: full,
+                shallow_since: doc.shallow_since_frontiers().into(),
+                bytes_saved: 0,
+                changes_dropped: 0,
+            });
+        }
+        let snapshot = doc.export(loro::ExportMode::ShallowSnapshot(Cow::Owned(frontiers)))?;
+        let trimmed = LoroDocInner::new();
+        trimmed.import(&snapshot)?;
+        Ok(Self {
+            bytes_saved: full.len().saturating_sub(snapshot.len()),
+            changes_dropped: doc.len_changes().saturating_sub(trimmed.len_changes()),
+            shallow_since: trimmed.shallow_since_frontiers().into(),
+            snapshot,
+        })
+    }
+}
+
+/// The ids of the containers directly nested in `container`.
+///
+/// The metadata map of every tree node counts as a child of the tree.
+pub fn child_containers(container: &loro::Container) -> Vec<loro::ContainerID> {
+    let value = match container {
+        loro::Container::Map(map) => map.get_value(),
+        loro::Container::List(list) => list.get_value(),
+        loro::Container::MovableList(list) => list.get_value(),
+        loro::Container::Tree(tree) => {
+            return tree
+                .get_nodes(false)
+                .into_iter()
+                .filter_map(|node| tree.get_meta(node.id).ok())
+                .map(|meta| meta.id())
+                .collect()
+        }
+        _ => return Vec::new(),
+    };
+    let values: Vec<&LoroValue> = match &value {
+        LoroValue::Map(map) => map.values().collect(),
+        LoroValue::List(list) => list.iter().collect(),
+        _ => Vec::new(),
+    };
+    values
+        .into_iter()
+        .filter_map(|v| match v {
+            LoroValue::Container(id) => Some(id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// All the containers reachable from the root containers, parents before their children.
+pub fn containers(doc: &LoroDocInner) -> Vec<loro::Container> {
+    let LoroValue::Map(roots) = doc.get_value() else {
+        return Vec::new();
+    };
+    let mut ids = roots
+        .values()
+        .filter_map(|v| match v {
+            LoroValue::Container(id) => Some(id.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    ids.sort_unstable_by_key(|id| id.to_string());
+    let mut ans = Vec::new();
+    let mut i = 0;
+    while i < ids.len() {
+        if let Some(container) = doc.get_container(ids[i].clone()) {
+            ids.extend(child_containers(&container));
+            ans.push(container);
+        }
+        i += 1;
+    }
+    ans
+}
+
+/// The per-container statistics of a document, returned by `LoroDoc.analyze`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct DocAnalysis {
+    /// The containers sorted by `deep_size`, largest first.
+    pub containers: Vec<ContainerAnalysis>,
+}
+
+impl fmt::Display for DocAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DocAnalysis(containers={})", self.containers.len())
+    }
+}
+
+#[pymethods]
+impl DocAnalysis {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.containers.len()
+    }
+
+    /// Get the statistics of the container `id`, if it's in the document.
+    pub fn get(&self, id: &ContainerID) -> Option<ContainerAnalysis> {
+        self.containers.iter().find(|c| &c.id == id).cloned()
+    }
+
+    /// The size in bytes of the whole state, the sum of the `size` of every container.
+    #[getter]
+    pub fn total_size(&self) -> usize {
+        self.containers.iter().map(|c| c.size).sum()
+    }
+}
+
+/// The statistics of one container.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct ContainerAnalysis {
+    pub id: ContainerID,
+    pub container_type: ContainerType,
+    /// The number of ops applied to the container.
+    pub ops: usize,
+    /// The size in bytes of the encoded state of the container.
+    pub size: usize,
+    /// The size of the container and all of its descendants.
+    pub deep_size: usize,
+    /// The number of containers directly nested in this one.
+    pub children: usize,
+    /// The depth in the document, 1 for root containers.
+    pub depth: usize,
+    /// Whether the container has been deleted.
+    pub dropped: bool,
+}
+
+impl fmt::Display for ContainerAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ContainerAnalysis(id={}, ops={}, size={}, deep_size={}, children={}, depth={}, dropped={})",
+            self.id.__repr__(),
+            self.ops,
+            self.size,
+            self.deep_size,
+            self.children,
+            self.depth,
+            if self.dropped { "True" } else { "False" }
+        )
+    }
+}
+
+#[pymethods]
+impl ContainerAnalysis {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl DocAnalysis {
+    pub fn new(doc: &LoroDocInner) -> Self {
+        let analysis = doc.analyze();
+        let mut children = FxHashMap::default();
+        for id in analysis.containers.keys() {
+            let ids = doc
+                .get_container(id.clone())
+                .map(|c| child_containers(&c))
+                .unwrap_or_default();
+            children.insert(id.clone(), ids);
+        }
+
+        // Children are deeper than their parent, so visiting the deepest containers first
+        // computes every deep size before it's needed.
+        let mut ids = analysis.containers.keys().cloned().collect::<Vec<_>>();
+        ids.sort_unstable_by_key(|id| std::cmp::Reverse(analysis.containers[id].depth));
+        let mut deep_sizes = FxHashMap::<loro::ContainerID, usize>::default();
+        for id in &ids {
+            let deep_size = analysis.containers[id].size as usize
+                + children[id]
+                    .iter()
+                    .filter_map(|child| deep_sizes.get(child))
+                    .sum::<usize>();
+            deep_sizes.insert(id.clone(), deep_size);
+        }
+
+        let mut containers = ids
+            .into_iter()
+            .map(|id| {
+                let info = &analysis.containers[&id];
+                ContainerAnalysis {
+                    container_type: id.container_type().into(),
+                    ops: info.ops_num as usize,
+                    size: info.size as usize,
+                    deep_size: deep_sizes[&id],
+                    children: children[&id].len(),
+                    depth: info.depth as usize,
+                    dropped: info.dropped,
+                    id: id.into(),
+                }
+            })
+            .collect::<Vec<_>>();
+        containers.sort_by(|a, b| {
+            b.deep_size
+                .cmp(&a.deep_size)
+                .then_with(|| a.depth.cmp(&b.depth))
+                .then_with(|| a.id.to_string().cmp(&b.id.to_string()))
+        });
+        Self { containers }
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use pyo3::prelude::*;
 
 use crate::{
@@ -11,6 +13,8 @@ use crate::{
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<UndoManager>()?;
     m.add_class::<UndoOrRedo>()?;
+    m.add_class::<UndoItemMeta>()?;
+    m.add_class::<CursorWithPos>()?;
     Ok(())
 }
 
@@ -172,16 +176,89 @@ pub enum UndoOrRedo {
     Redo,
 }
 
-#[pyclass(get_all)]
+/// The metadata of an undo stack item, returned by the `on_push` listener and passed back
+/// to the `on_pop` listener.
+///
+/// It usually stores the selection before the change, so it can be restored after undoing
+/// it.
+#[pyclass(get_all, str)]
 #[derive(Debug, Clone)]
 pub struct UndoItemMeta {
     pub value: LoroValue,
     pub cursors: Vec<CursorWithPos>,
 }
 
-#[pyclass(get_all)]
+impl Display for UndoItemMeta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[pymethods]
+impl UndoItemMeta {
+    #[new]
+    #[pyo3(signature = (value=None, cursors=Vec::new()))]
+    pub fn new(value: Option<LoroValue>, cursors: Vec<CursorWithPos>) -> Self {
+        Self {
+            value: value.unwrap_or(LoroValue(loro::LoroValue::Null)),
+            cursors,
+        }
+    }
+
+    /// The stored cursors with their positions in the current state of `doc`, e.g. after
+    /// the item was undone or redone.
+    ///
+    /// Cursors whose container was deleted are left out.
+    pub fn transform_cursors(&self, doc: &LoroDoc) -> Vec<CursorWithPos> {
+        self.cursors
+            .iter()
+            .filter_map(|c| c.transform(doc).ok())
+            .collect()
+    }
+}
+
+#[pyclass(get_all, str)]
 #[derive(Debug, Clone)]
 pub struct CursorWithPos {
     pub cursor: Cursor,
     pub pos: AbsolutePosition,
 }
+
+impl Display for CursorWithPos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[pymethods]
+impl CursorWithPos {
+    #[new]
+    pub fn new(cursor: Cursor, pos: AbsolutePosition) -> Self {
+        Self { cursor, pos }
+    }
+
+    /// Capture `cursor` with its current position in `doc`.
+    ///
+    /// Get the cursor of a selection end with e.g. `LoroText.get_cursor`.
+    #[staticmethod]
+    pub fn capture(doc: &LoroDoc, cursor: Cursor) -> PyLoroResult<Self> {
+        let pos = doc.doc.get_cursor_pos(&cursor.clone().into())?.current;
+        Ok(Self {
+            cursor,
+            pos: pos.into(),
+        })
+    }
+
+    /// This cursor with its position in the current state of `doc`.
+    ///
+    /// Fails if the container of the cursor was deleted.
+    pub fn transform(&self, doc: &LoroDoc) -> PyLoroResult<Self> {
+        let result = doc.doc.get_cursor_pos(&self.cursor.clone().into())?;
+        Ok(Self {
+            cursor: result
+                .update
+                .map_or_else(|| self.cursor.clone(), Into::into),
+            pos: result.current.into(),
+        })
+    }
+}
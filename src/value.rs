@@ -1,10 +1,15 @@
-use loro::{Counter, PeerID};
-use pyo3::prelude::*;
+use loro::{ContainerTrait, Counter, PeerID};
+use pyo3::{
+    exceptions::{PyIndexError, PyKeyError, PyTypeError, PyValueError},
+    prelude::*,
+};
 use std::fmt::Display;
 
 use crate::{
-    container::Container,
+    codec,
+    container::{Container, LoroCounter, LoroList, LoroMap, LoroMovableList, LoroText, LoroTree},
     convert::{loro_value_to_pyobject, pyobject_to_loro_value},
+    hooks::DocHooks,
 };
 
 pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -18,7 +23,7 @@ pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
-#[pyclass(eq, str, get_all, set_all)]
+#[pyclass(eq, hash, frozen, str, get_all)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ID {
     pub peer: u64,
@@ -37,9 +42,13 @@ impl ID {
     pub fn new(peer: u64, counter: i32) -> Self {
         Self { peer, counter }
     }
+
+    pub fn __repr__(&self) -> String {
+        format!("ID(peer={}, counter={})", self.peer, self.counter)
+    }
 }
 
-#[pyclass(eq, str, get_all, set_all)]
+#[pyclass(eq, hash, frozen, str, get_all)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct IdLp {
     pub peer: u64,
@@ -52,6 +61,13 @@ impl Display for IdLp {
     }
 }
 
+#[pymethods]
+impl IdLp {
+    pub fn __repr__(&self) -> String {
+        format!("IdLp(peer={}, lamport={})", self.peer, self.lamport)
+    }
+}
+
 #[pyclass(eq, hash, str)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ContainerType {
@@ -70,6 +86,16 @@ impl Display for ContainerType {
     }
 }
 
+#[pymethods]
+impl ContainerType {
+    pub fn __repr__(&self) -> String {
+        match self {
+            ContainerType::Unknown { kind } => format!("ContainerType.Unknown(kind={})", kind),
+            _ => format!("ContainerType.{}", self),
+        }
+    }
+}
+
 #[pyclass(eq, str, hash)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ContainerID {
@@ -90,12 +116,40 @@ impl Display for ContainerID {
     }
 }
 
+#[pymethods]
+impl ContainerID {
+    pub fn __repr__(&self) -> String {
+        match self {
+            ContainerID::Root {
+                name,
+                container_type,
+            } => format!(
+                "ContainerID.Root(name={:?}, container_type={})",
+                name,
+                container_type.__repr__()
+            ),
+            ContainerID::Normal {
+                peer,
+                counter,
+                container_type,
+            } => format!(
+                "ContainerID.Normal(peer={}, counter={}, container_type={})",
+                peer,
+                counter,
+                container_type.__repr__()
+            ),
+        }
+    }
+}
+
 #[pyclass(eq, str, eq_int)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Ordering {
     Less,
     Equal,
     Greater,
+    /// Neither version includes the other.
+    Concurrent,
 }
 
 impl Display for Ordering {
@@ -114,7 +168,9 @@ impl From<std::cmp::Ordering> for Ordering {
     }
 }
 
-#[pyclass(eq, str, get_all, set_all)]
+/// The id of a tree node. `str()` gives the `counter@peer` form of loro-js, e.g. `12@9876`,
+/// which `TreeID.parse` reads back and the `LoroTree` methods accept in place of a `TreeID`.
+#[pyclass(eq, hash, frozen, get_all)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TreeID {
     pub peer: PeerID,
@@ -133,6 +189,29 @@ impl TreeID {
     pub fn new(peer: PeerID, counter: Counter) -> Self {
         Self { peer, counter }
     }
+
+    /// Parse the `counter@peer` form given by `str()`, e.g. `TreeID.parse("12@9876")`.
+    #[staticmethod]
+    pub fn parse(s: &str) -> PyResult<Self> {
+        s.split_once('@')
+            .and_then(|(counter, peer)| {
+                Some(Self {
+                    peer: peer.parse().ok()?,
+                    counter: counter.parse().ok()?,
+                })
+            })
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("invalid tree id '{}', expected counter@peer", s))
+            })
+    }
+
+    pub fn __str__(&self) -> String {
+        format!("{}@{}", self.counter, self.peer)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("TreeID(peer={}, counter={})", self.peer, self.counter)
+    }
 }
 
 pub type TreeParentId = Option<TreeID>;
@@ -171,15 +250,212 @@ impl ValueOrContainer {
 
         matches!(value.unwrap(), ValueOrContainer::Container { .. })
     }
+
+    /// The id of the container, or of the container referenced by the value.
+    ///
+    /// Return `None` if it's a plain value.
+    pub fn container_id(&self) -> Option<ContainerID> {
+        match self {
+            ValueOrContainer::Container { container } => Some(container.id().into()),
+            ValueOrContainer::Value { value } => match &value.0 {
+                loro::LoroValue::Container(id) => Some(id.into()),
+                _ => None,
+            },
+        }
+    }
+
+    /// The container as a `LoroText`, or `None` if it's a value or another kind of container.
+    pub fn as_text(&self) -> Option<LoroText> {
+        match self.container()? {
+            Container::Text(text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+
+    /// The container as a `LoroMap`, or `None` if it's a value or another kind of container.
+    pub fn as_map(&self) -> Option<LoroMap> {
+        match self.container()? {
+            Container::Map(map) => Some(map.clone()),
+            _ => None,
+        }
+    }
+
+    /// The container as a `LoroList`, or `None` if it's a value or another kind of container.
+    pub fn as_list(&self) -> Option<LoroList> {
+        match self.container()? {
+            Container::List(list) => Some(list.clone()),
+            _ => None,
+        }
+    }
+
+    /// The container as a `LoroMovableList`, or `None` if it's a value or another kind of
+    /// container.
+    pub fn as_movable_list(&self) -> Option<LoroMovableList> {
+        match self.container()? {
+            Container::MovableList(list) => Some(list.clone()),
+            _ => None,
+        }
+    }
+
+    /// The container as a `LoroTree`, or `None` if it's a value or another kind of container.
+    pub fn as_tree(&self) -> Option<LoroTree> {
+        match self.container()? {
+            Container::Tree(tree) => Some(tree.clone()),
+            _ => None,
+        }
+    }
+
+    /// The container as a `LoroCounter`, or `None` if it's a value or another kind of
+    /// container.
+    pub fn as_counter(&self) -> Option<LoroCounter> {
+        match self.container()? {
+            Container::Counter(counter) => Some(counter.clone()),
+            _ => None,
+        }
+    }
+
+    /// Convert to a plain Python object.
+    ///
+    /// Containers are converted to their deep value.
+    pub fn to_py<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        LoroValue::from(self.deep_value())
+            .decoding_datetime(self.decodes_datetime())
+            .into_pyobject(py)
+    }
+
+    /// Compare deep values, so an entry holding a container equals its deep value, another
+    /// entry or container with the same content, and plain Python objects.
+    pub fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        let other = if let Ok(other) = other.downcast::<ValueOrContainer>() {
+            other.get().deep_value()
+        } else if let Ok(other) = other.extract::<Container>() {
+            other.get_deep_value()
+        } else {
+            match pyobject_to_loro_value(other) {
+                Ok(other) => other,
+                Err(_) => return false,
+            }
+        };
+        self.deep_value() == other
+    }
+
+    /// Get the child at `key` of a list or map, either a value or a container.
+    pub fn __getitem__(&self, key: &Bound<'_, PyAny>) -> PyResult<ValueOrContainer> {
+        match self {
+            ValueOrContainer::Value { value } => match &value.0 {
+                loro::LoroValue::List(list) => {
+                    let index = normalize_index(key.extract()?, list.len())?;
+                    Ok(ValueOrContainer::Value {
+                        value: LoroValue(list[index].clone(), value.1),
+                    })
+                }
+                loro::LoroValue::Map(map) => {
+                    let k = key.extract::<&str>()?;
+                    map.get(k)
+                        .map(|v| ValueOrContainer::Value {
+                            value: LoroValue(v.clone(), value.1),
+                        })
+                        .ok_or_else(|| PyKeyError::new_err(k.to_string()))
+                }
+                _ => Err(PyTypeError::new_err("the value is not subscriptable")),
+            },
+            ValueOrContainer::Container { container } => match container {
+                Container::Map(map) => {
+                    let k = key.extract::<&str>()?;
+                    map.0
+                        .get(k)
+                        .map(|v| ValueOrContainer::read_from(&map.0, v))
+                        .ok_or_else(|| PyKeyError::new_err(k.to_string()))
+                }
+                Container::List(list) => {
+                    let index = normalize_index(key.extract()?, list.0.len())?;
+                    let value = list.0.get(index).expect("index is in bounds");
+                    Ok(ValueOrContainer::read_from(&list.0, value))
+                }
+                Container::MovableList(list) => {
+                    let index = normalize_index(key.extract()?, list.0.len())?;
+                    let value = list.0.get(index).expect("index is in bounds");
+                    Ok(ValueOrContainer::read_from(&list.0, value))
+                }
+                _ => Err(PyTypeError::new_err("the container is not subscriptable")),
+            },
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct LoroValue(pub(crate) loro::LoroValue);
+impl ValueOrContainer {
+    /// Wrap a value read from `container`, converting its tagged datetimes back if the
+    /// document of the container has datetime decoding enabled.
+    pub(crate) fn read_from<C: ContainerTrait>(
+        container: &C,
+        value: loro::ValueOrContainer,
+    ) -> Self {
+        ValueOrContainer::from(value).decoding_datetime(DocHooks::datetime_decoding(container))
+    }
+
+    /// Set whether the tagged datetimes of a plain value are converted back.
+    pub(crate) fn decoding_datetime(self, enabled: bool) -> Self {
+        match self {
+            ValueOrContainer::Value { value } => ValueOrContainer::Value {
+                value: value.decoding_datetime(enabled),
+            },
+            container => container,
+        }
+    }
+
+    /// Whether the tagged datetimes of the value are converted back.
+    fn decodes_datetime(&self) -> bool {
+        match self {
+            ValueOrContainer::Value { value } => value.1,
+            ValueOrContainer::Container { container } => container.decodes_datetime(),
+        }
+    }
+
+    fn container(&self) -> Option<&Container> {
+        match self {
+            ValueOrContainer::Container { container } => Some(container),
+            ValueOrContainer::Value { .. } => None,
+        }
+    }
+
+    fn deep_value(&self) -> loro::LoroValue {
+        match self {
+            ValueOrContainer::Value { value } => value.0.clone(),
+            ValueOrContainer::Container { container } => container.get_deep_value(),
+        }
+    }
+}
+
+fn normalize_index(index: isize, len: usize) -> PyResult<usize> {
+    let normalized = if index < 0 {
+        index + len as isize
+    } else {
+        index
+    };
+    if normalized < 0 || normalized as usize >= len {
+        return Err(PyIndexError::new_err("index out of range"));
+    }
+    Ok(normalized as usize)
+}
+
+/// A value converted to and from Python objects.
+///
+/// The flag is set on values read from a document with `LoroDoc.set_datetime_decoding`
+/// enabled, so their tagged datetimes are converted back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoroValue(pub(crate) loro::LoroValue, pub(crate) bool);
+
+impl LoroValue {
+    /// Set whether the tagged datetimes of the value are converted back.
+    pub(crate) fn decoding_datetime(self, enabled: bool) -> Self {
+        Self(self.0, enabled)
+    }
+}
 
 impl<'py> FromPyObject<'py> for LoroValue {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
         let value = pyobject_to_loro_value(ob)?;
-        Ok(Self(value))
+        Ok(value.into())
     }
 }
 
@@ -191,6 +467,6 @@ impl<'py> IntoPyObject<'py> for LoroValue {
     type Error = PyErr;
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        loro_value_to_pyobject(py, self)
+        codec::decoding_datetime(self.1, || loro_value_to_pyobject(py, self))
     }
 }
@@ -8,7 +8,7 @@ use pyo3::{
     basic::CompareOp,
     exceptions::PyNotImplementedError,
     prelude::*,
-    types::{PyBytes, PyDict, PyType},
+    types::{PyBytes, PyDict, PyIterator, PyList, PyType},
 };
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
@@ -16,11 +16,13 @@ pub fn register_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Frontiers>()?;
     m.add_class::<VersionRange>()?;
     m.add_class::<VersionVector>()?;
+    m.add_class::<VersionInfo>()?;
+    m.add_class::<SyncGap>()?;
     Ok(())
 }
 
-#[pyclass(str)]
-#[derive(Debug, Clone, Default)]
+#[pyclass(eq, str, module = "loro")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Frontiers(loro::Frontiers);
 
 impl Display for Frontiers {
@@ -36,6 +38,28 @@ impl Frontiers {
         Self::default()
     }
 
+    pub fn __repr__(&self) -> String {
+        let ids = self
+            .0
+            .iter()
+            .map(|id| ID::from(id).__repr__())
+            .collect::<Vec<_>>();
+        format!("Frontiers([{}])", ids.join(", "))
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        let ids: Vec<ID> = self.0.iter().map(ID::from).collect();
+        PyList::new(py, ids)?.try_iter()
+    }
+
+    pub fn __contains__(&self, id: ID) -> bool {
+        self.0.contains(&loro::ID::from(id))
+    }
+
     #[classmethod]
     pub fn from_id(_cls: &Bound<'_, PyType>, id: ID) -> Self {
         Self(loro::Frontiers::from(loro::ID::from(id)))
@@ -53,6 +77,21 @@ impl Frontiers {
         Cow::Owned(ans)
     }
 
+    pub fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.encode())
+    }
+
+    /// Pickle the frontiers as its encoded bytes, e.g. to keep it in a session store.
+    pub fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        Ok((
+            py.get_type::<Self>().getattr("decode")?,
+            (self.__bytes__(py),),
+        ))
+    }
+
     #[classmethod]
     pub fn decode(_cls: &Bound<'_, PyType>, bytes: Bound<'_, PyBytes>) -> PyLoroResult<Self> {
         let ans = Self(loro::Frontiers::decode(bytes.as_bytes())?);
@@ -89,6 +128,16 @@ impl VersionRange {
         Self(Default::default())
     }
 
+    pub fn __repr__(&self) -> String {
+        let mut spans = self.0.inner().iter().collect::<Vec<_>>();
+        spans.sort_unstable_by_key(|(peer, _)| **peer);
+        let spans = spans
+            .into_iter()
+            .map(|(peer, (start, end))| format!("{}: ({}, {})", peer, start, end))
+            .collect::<Vec<_>>();
+        format!("VersionRange({{{}}})", spans.join(", "))
+    }
+
     #[classmethod]
     pub fn from_map(_cls: &Bound<'_, PyType>, map: Bound<'_, PyDict>) -> PyLoroResult<Self> {
         let mut ans = Self::new();
@@ -178,7 +227,7 @@ impl From<loro::VersionRange> for VersionRange {
     }
 }
 
-#[pyclass(str)]
+#[pyclass(str, module = "loro")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VersionVector(loro::VersionVector);
 
@@ -189,6 +238,16 @@ impl VersionVector {
         Self::default()
     }
 
+    pub fn __repr__(&self) -> String {
+        let mut entries = self.0.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(peer, _)| **peer);
+        let entries = entries
+            .into_iter()
+            .map(|(peer, counter)| format!("{}: {}", peer, counter))
+            .collect::<Vec<_>>();
+        format!("VersionVector({{{}}})", entries.join(", "))
+    }
+
     pub fn diff(&self, rhs: &Self) -> VersionVectorDiff {
         VersionVectorDiff::from(self.0.diff(&rhs.0))
     }
@@ -316,6 +375,21 @@ impl VersionVector {
         Cow::Owned(ans)
     }
 
+    pub fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.encode())
+    }
+
+    /// Pickle the version vector as its encoded bytes, e.g. to keep it in a session store.
+    pub fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        Ok((
+            py.get_type::<Self>().getattr("decode")?,
+            (self.__bytes__(py),),
+        ))
+    }
+
     #[classmethod]
     #[inline(always)]
     pub fn decode(_cls: &Bound<'_, PyType>, bytes: Bound<'_, PyBytes>) -> PyLoroResult<Self> {
@@ -371,6 +445,17 @@ impl Display for VersionVectorDiff {
     }
 }
 
+#[pymethods]
+impl VersionVectorDiff {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "VersionVectorDiff(retreat={}, forward={})",
+            self.retreat.__repr__(),
+            self.forward.__repr__()
+        )
+    }
+}
+
 impl From<loro::VersionVectorDiff> for VersionVectorDiff {
     // TODO: a better way in loro-rs
     fn from(value: loro::VersionVectorDiff) -> Self {
@@ -392,3 +477,73 @@ impl From<loro::VersionVectorDiff> for VersionVectorDiff {
         }
     }
 }
+
+/// The versions of a document's state and history, returned by `LoroDoc.version_info`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct VersionInfo {
+    pub state_vv: VersionVector,
+    pub oplog_vv: VersionVector,
+    pub state_frontiers: Frontiers,
+    pub oplog_frontiers: Frontiers,
+}
+
+impl Display for VersionInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "VersionInfo(state_vv={}, oplog_vv={}, state_frontiers={}, oplog_frontiers={})",
+            self.state_vv.__repr__(),
+            self.oplog_vv.__repr__(),
+            self.state_frontiers.__repr__(),
+            self.oplog_frontiers.__repr__()
+        )
+    }
+}
+
+#[pymethods]
+impl VersionInfo {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// The changes two peers have to exchange to catch up, returned by `LoroDoc.missing_spans`.
+#[pyclass(frozen, get_all, str)]
+#[derive(Debug, Clone)]
+pub struct SyncGap {
+    /// The spans the remote peer has and the local document lacks.
+    pub missing: Vec<IdSpan>,
+    /// The spans the local document has and the remote peer lacks.
+    pub ahead: Vec<IdSpan>,
+}
+
+impl Display for SyncGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spans = |spans: &[IdSpan]| {
+            spans
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "SyncGap(missing=[{}], ahead=[{}])",
+            spans(&self.missing),
+            spans(&self.ahead)
+        )
+    }
+}
+
+#[pymethods]
+impl SyncGap {
+    pub fn __repr__(&self) -> String {
+        self.to_string()
+    }
+
+    /// Whether the peers have changes to exchange.
+    pub fn __bool__(&self) -> bool {
+        !self.missing.is_empty() || !self.ahead.is_empty()
+    }
+}
@@ -0,0 +1,72 @@
+import pytest
+from loro import ID, LoroDoc, LoroMap
+
+
+def test_apply_ops_across_containers():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    doc.apply_ops(
+        [
+            (text, "insert", (0, "Hello")),
+            (doc.get_map("map"), "insert", ("key", 1)),
+            (doc.get_list("list"), "push", ("a",)),
+            (doc.get_counter("counter"), "increment", (2,)),
+        ],
+        commit_msg="batch",
+    )
+    assert doc.get_deep_value() == {
+        "text": "Hello",
+        "map": {"key": 1},
+        "list": ["a"],
+        "counter": 2.0,
+    }
+    assert doc.len_changes == 1
+    assert doc.get_change(ID(doc.peer_id, 0)).message == "batch"
+
+
+def test_apply_ops_resolves_paths():
+    doc = LoroDoc()
+    root = doc.get_map("root")
+    root.insert_container("child", LoroMap())
+    doc.commit()
+    doc.apply_ops([("root/child", "insert", ("x", True)), (root.id, "delete", ("child",))])
+    assert doc.get_deep_value() == {"root": {}}
+
+
+def test_apply_ops_reports_failing_op():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    with pytest.raises(ValueError, match="unsupported op") as excinfo:
+        doc.apply_ops([(text, "insert", (0, "a")), (text, "explode", ())])
+    assert excinfo.value.__notes__ == ["op #1 ('explode') failed"]
+    assert text.to_string() == "a"
+
+
+def test_apply_ops_keeps_error_context():
+    doc = LoroDoc()
+    items = doc.get_list("items")
+    with pytest.raises(BaseException) as excinfo:
+        doc.apply_ops([(items, "push", (1,)), (items, "insert", (10, "x"))])
+    err = excinfo.value
+    assert err.operation == "LoroList.insert"
+    assert err.container_id == items.id
+    assert err.index == 10
+    assert err.__notes__ == ["op #1 ('insert') failed"]
+
+
+def test_apply_ops_commits_pending_ops_first():
+    doc = LoroDoc()
+    doc.get_text("a").insert(0, "pending")
+    doc.apply_ops([(doc.get_text("b"), "insert", (0, "x"))], commit_msg="batch")
+    assert doc.len_changes == 2
+    batch = doc.get_change(ID(doc.peer_id, 7))
+    assert batch.message == "batch"
+    assert batch.len == 1
+
+
+def test_apply_ops_checks_quota():
+    doc = LoroDoc()
+    doc.set_quota_hook(lambda request: False)
+    with pytest.raises(BaseException, match="quota"):
+        doc.apply_ops([(doc.get_text("text"), "insert", (0, "abc"))])
+    assert doc.len_changes == 0
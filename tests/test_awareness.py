@@ -4,3 +4,19 @@ def test_awareness():
     awareness = Awareness(1, 1000)
     awareness.local_state = {"a": 1}
     assert awareness.local_state == {"a": 1}
+
+
+def test_awareness_peers():
+    a = Awareness(1, 1000)
+    b = Awareness(2, 1000)
+    assert a.is_empty() and len(a) == 0
+    a.local_state = {"cursor": 3}
+    b.local_state = {"cursor": 5}
+
+    update = a.apply(b.encode_all())
+    assert update.added == [2]
+    assert a.peers() == [1, 2]
+    assert 2 in a and 3 not in a
+    assert len(a) == 2
+    assert a.all_states[2].state == {"cursor": 5}
+    assert repr(a) == "Awareness(peer=1, peers=[1, 2])"
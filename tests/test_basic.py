@@ -1,4 +1,34 @@
-from loro import LoroDoc, ExportMode, VersionVector
+import asyncio
+import json
+import logging
+import pickle
+
+import pytest
+
+from loro import (
+    ID,
+    ContainerType,
+    EncodedBlobMode,
+    EventTriggerKind,
+    ExportMode,
+    Frontiers,
+    Index,
+    ListDiffItem,
+    LoroDoc,
+    LoroList,
+    LoroMap,
+    Side,
+    StyleConfigMap,
+    TreeID,
+    VersionVector,
+    diff_blobs,
+    enable_logging,
+    inspect_blob,
+    merge,
+    peer_id_from_string,
+    set_max_decompressed_size,
+    set_rng_seed,
+)
 
 def test_basic():
     doc = LoroDoc()
@@ -32,3 +62,731 @@ def test_apply_diff():
     doc1.commit()
     doc2.apply_diff(doc1.diff(f1, doc1.oplog_frontiers))
     assert doc2.get_text("text").to_string() == "abc"
+
+
+def test_identity_types_are_hashable():
+    doc = LoroDoc()
+    tree = doc.get_tree("tree")
+    root = tree.create()
+    widgets = {root: "root-widget"}
+    assert widgets[TreeID(root.peer, root.counter)] == "root-widget"
+
+    assert {ID(1, 2), ID(1, 2), ID(2, 1)} == {ID(1, 2), ID(2, 1)}
+    assert {tree.id: 1}[doc.get_tree("tree").id] == 1
+
+    text = doc.get_text("text")
+    text.insert(0, "abc")
+    cursor = text.get_cursor(1, Side.Middle)
+    assert cursor == text.get_cursor(1, Side.Middle)
+    assert len({cursor, text.get_cursor(1, Side.Middle)}) == 1
+
+
+def test_identity_fields_are_read_only():
+    id = ID(1, 2)
+    with pytest.raises(AttributeError):
+        id.counter = 3
+    assert id == ID(1, 2)
+
+
+def test_get_deep_value_json():
+    doc = LoroDoc()
+    doc.get_map("map").insert("k", [1, "two", None])
+    doc.get_text("text").insert(0, "hi")
+    assert json.loads(doc.get_deep_value_json()) == doc.get_deep_value()
+    assert "\n" in doc.get_deep_value_json(pretty=True)
+
+
+def test_enable_logging_can_change_level():
+    enable_logging(logging.DEBUG)
+    enable_logging()
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "hi")
+    doc.commit()
+    assert doc.get_text("text").to_string() == "hi"
+
+
+def test_storage_stats():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    child = doc.get_map("map").insert_container("list", LoroList())
+    child.push(1)
+    tree = doc.get_tree("tree")
+    tree.create()
+    doc.commit()
+
+    stats = doc.storage_stats()
+    assert stats.changes == doc.len_changes
+    assert stats.ops == doc.len_ops
+    # text, map, tree, the nested list and the metadata map of the tree node
+    assert stats.containers == 5
+    assert stats.oplog_bytes > 0 and stats.state_bytes > 0
+    assert not stats.is_shallow
+
+
+def test_analyze():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    child = doc.get_map("map").insert_container("list", LoroList())
+    child.push(1)
+    doc.commit()
+
+    analysis = doc.analyze()
+    assert len(analysis) == 3
+    map_info = analysis.get(doc.get_map("map").id)
+    list_info = analysis.get(child.id)
+    assert map_info.children == 1 and map_info.depth == 1
+    assert list_info.depth == 2 and list_info.ops == 1
+    assert map_info.deep_size == map_info.size + list_info.deep_size
+    assert analysis.total_size == sum(c.size for c in analysis.containers)
+    sizes = [c.deep_size for c in analysis.containers]
+    assert sizes == sorted(sizes, reverse=True)
+
+
+def test_loro_doc_constructor_configuration():
+    doc = LoroDoc(peer_id=42, record_timestamp=True, detached_editing=True)
+    assert doc.peer_id == 42
+    assert doc.is_detached_editing_enabled
+    doc.get_text("text").insert(0, "a")
+    doc.commit()
+    (change,) = doc.iter_changes()
+    assert change.meta.timestamp > 0
+
+    doc = LoroDoc(merge_interval=0)
+    assert not doc.is_detached_editing_enabled
+
+
+def test_hide_empty_root_containers():
+    doc = LoroDoc(hide_empty_root_containers=True)
+    doc.get_map("unused")
+    doc.get_list("items").push(1)
+    assert doc.get_deep_value() == {"items": [1]}
+
+    doc.set_hide_empty_root_containers(False)
+    assert doc.get_deep_value() == {"unused": {}, "items": [1]}
+
+
+def test_peer_id_from_string():
+    peer = peer_id_from_string("user-42/device-A")
+    assert peer == peer_id_from_string("user-42/device-A")
+    assert peer != peer_id_from_string("user-42/device-B")
+    assert 0 <= peer < 2**64 - 1
+
+    doc = LoroDoc(peer_id="user-42/device-A")
+    assert doc.peer_id == peer
+    doc.peer_id = "user-42/device-B"
+    assert doc.peer_id == peer_id_from_string("user-42/device-B")
+    with pytest.raises(ValueError):
+        doc.peer_id = 1.5
+
+
+def test_gc():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    for i in range(10):
+        doc.peer_id = i
+        text.insert(0, str(i))
+        doc.commit()
+    assert doc.len_changes == 10
+
+    result = doc.gc(keep_last=3)
+    assert result.changes_dropped > 0
+    assert result.bytes_saved >= 0
+    trimmed = LoroDoc()
+    trimmed.import_(result.snapshot)
+    assert trimmed.is_shallow()
+    assert trimmed.shallow_since_frontiers.encode() == result.shallow_since.encode()
+    assert trimmed.get_text("text").to_string() == "9876543210"
+    assert not doc.is_shallow()
+
+    result = doc.gc(keep_since=doc.oplog_frontiers)
+    assert result.changes_dropped >= 9
+
+    result = doc.gc(keep_last=100)
+    assert result.changes_dropped == 0 and result.bytes_saved == 0
+    with pytest.raises(ValueError):
+        doc.gc()
+    with pytest.raises(ValueError):
+        doc.gc(keep_last=1, keep_since=doc.oplog_frontiers)
+
+
+def test_inspect_blob():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    version = doc.oplog_vv
+    snapshot = doc.export(ExportMode.Snapshot())
+
+    result = inspect_blob(snapshot)
+    assert result.valid and result.error is None and not result.pending
+    assert result.meta.change_num == 1
+
+    flipped = snapshot[:20] + bytes([snapshot[20] ^ 0xFF]) + snapshot[21:]
+    for corrupt in [snapshot[:-5], flipped]:
+        result = inspect_blob(corrupt)
+        assert not result.valid and result.error
+
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+    updates = doc.export(ExportMode.Updates(version))
+    assert inspect_blob(updates).pending
+    assert not inspect_blob(updates, doc).pending
+
+    target = LoroDoc()
+    target.import_(snapshot)
+    assert inspect_blob(updates, target).valid
+    assert target.get_text("text").to_string() == "abc"
+
+
+def test_diff_blobs():
+    doc = LoroDoc()
+    doc.get_map("map").insert("a", 1)
+    doc.commit()
+    backup = doc.export(ExportMode.Snapshot())
+    assert diff_blobs(backup, backup).get_diff() == []
+
+    doc.get_map("map").insert("a", 2)
+    doc.commit()
+    ((cid, diff),) = diff_blobs(backup, doc.export(ExportMode.Snapshot())).get_diff()
+    assert cid == doc.get_map("map").id
+    assert diff.diff.updated["a"].value == 2
+
+
+def test_merge():
+    base = LoroDoc()
+    base.get_map("map").insert("title", "draft")
+    base.get_map("map").insert("tags", 0)
+    base.commit()
+
+    ours = base.fork()
+    ours.get_map("map").insert("title", "ours")
+    ours.get_text("text").insert(0, "hello")
+    ours.commit()
+    theirs = base.fork()
+    theirs.get_map("map").insert("title", "theirs")
+    theirs.get_map("map").insert("tags", 1)
+    theirs.commit()
+
+    doc, conflicts = merge(base.export(ExportMode.Snapshot()), ours, theirs)
+    assert doc.get_text("text").to_string() == "hello"
+    value = doc.get_map("map").get_deep_value()
+    assert value["tags"] == 1
+    (conflict,) = conflicts
+    assert conflict.container == doc.get_map("map").id
+    assert conflict.key == "title"
+    assert conflict.ours.value == "ours" and conflict.theirs.value == "theirs"
+    assert conflict.value.value == value["title"]
+
+    with pytest.raises(TypeError):
+        merge(base, ours, "theirs")
+
+
+def test_deep_equal():
+    a, b = LoroDoc(), LoroDoc()
+    a.get_map("map").insert("key", 1)
+    a.commit()
+    assert not a.deep_equal(b)
+
+    b.get_map("map").insert("key", 1)
+    b.commit()
+    assert a.deep_equal(b)
+    assert not a.deep_equal(b, history=True)
+
+    a.import_(b.export(ExportMode.Snapshot()))
+    b.import_(a.export(ExportMode.Snapshot()))
+    assert a.deep_equal(b, history=True)
+
+
+def test_state_hash():
+    a, b = LoroDoc(), LoroDoc()
+    assert a.state_hash() == b.state_hash()
+    a.get_map("map").insert("key", 1)
+    a.get_text("text").insert(0, "abc")
+    a.commit()
+    assert a.state_hash() != b.state_hash()
+
+    b.get_text("text").insert(0, "abc")
+    b.get_map("map").insert("key", 1)
+    b.commit()
+    assert a.state_hash() == b.state_hash()
+    assert a.get_map("map").state_hash() == b.get_map("map").state_hash()
+    assert a.get_map("map").state_hash() != a.get_text("text").state_hash()
+
+    a.config_text_style(StyleConfigMap.default_rich_text_config())
+    a.get_text("text").mark(0, 1, "bold", True)
+    a.commit()
+    assert a.get_text("text").state_hash() != b.get_text("text").state_hash()
+
+
+def test_list_diff_item_accessors():
+    doc = LoroDoc()
+    items = doc.get_list("items")
+    items.push(1)
+    items.push(2)
+    items.push(3)
+    doc.commit()
+    events = []
+    sub = doc.subscribe_root(events.append)
+    items.delete(1, 1)
+    items.push(4)
+    doc.commit()
+    sub.unsubscribe()
+
+    diff = events[0].events[0].diff.diff
+    assert [(item.kind, item.len) for item in diff] == [
+        ("retain", 1),
+        ("delete", 1),
+        ("retain", 1),
+        ("insert", 1),
+    ]
+    assert isinstance(diff[3], ListDiffItem.Insert)
+    assert [v.value for v in diff[3].insert] == [4]
+    assert ListDiffItem.Insert.__match_args__ == ("insert", "is_move")
+    assert ListDiffItem.Delete.__match_args__ == ("delete",)
+    assert ListDiffItem.Retain.__match_args__ == ("retain",)
+
+
+def test_index_coercion():
+    doc = LoroDoc()
+    items = doc.get_map("root").insert_container("items", LoroList())
+    items.push("a")
+    child = items.insert_container(1, LoroMap())
+    child["name"] = "b"
+    doc.commit()
+
+    path = [index for _, index in doc.get_path_to_container(child.id)]
+    assert [str(index) for index in path] == ["root", "items", "1"]
+    mirror = doc.get_deep_value()
+    assert mirror[str(path[0])][str(path[1])][path[2]] == {"name": "b"}
+    with pytest.raises(TypeError):
+        ["a", "b"][path[1]]
+
+    assert doc.get_by_path(["root", "items", 1, "name"]).value == "b"
+    assert doc.get_by_path([Index.Key("root"), "items", 0]).value == "a"
+    with pytest.raises(TypeError):
+        doc.get_by_path(["root", 1.5])
+
+
+def test_container_existence():
+    doc = LoroDoc()
+    root = doc.get_map("root")
+    child = root.insert_container("child", LoroMap())
+    nested = child.insert_container("nested", LoroList())
+    doc.commit()
+    assert doc.has_container(nested.id)
+    assert not doc.is_container_deleted(nested.id)
+    assert not doc.is_container_deleted(root.id)
+
+    root.delete("child")
+    doc.commit()
+    assert doc.has_container(nested.id)
+    assert doc.is_container_deleted(child.id)
+    assert doc.is_container_deleted(nested.id)
+
+    other = LoroDoc()
+    assert not other.has_container(nested.id)
+    assert not other.is_container_deleted(nested.id)
+
+
+def test_delete_root_container():
+    doc = LoroDoc()
+    doc.get_map("settings")["theme"] = "dark"
+    doc.get_list("drafts").push("a")
+    doc.get_text("notes").insert(0, "hi")
+    doc.commit()
+
+    doc.delete_root_container("drafts", ContainerType.List())
+    doc.delete_root_container(doc.get_text("notes").id)
+    doc.commit()
+    assert doc.get_deep_value() == {"settings": {"theme": "dark"}}
+
+    child = doc.get_map("settings").insert_container("nested", LoroMap())
+    with pytest.raises(ValueError):
+        doc.delete_root_container(child.id)
+
+
+def test_import_dir(tmp_path):
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    blobs = []
+    for word in ["a", "b", "c"]:
+        version = doc.oplog_vv
+        text.insert(len(text), word)
+        doc.commit()
+        blobs.append(doc.export(ExportMode.Updates(version)))
+    # Names in the reverse of the causal order, a duplicate and a file to skip
+    for i, blob in enumerate(blobs):
+        (tmp_path / f"{len(blobs) - i:03}.bin").write_bytes(blob)
+    (tmp_path / "copy.bin").write_bytes(blobs[1])
+    (tmp_path / "notes.txt").write_bytes(b"not an update")
+
+    other = LoroDoc()
+    events = []
+    sub = other.subscribe_root(events.append)
+    other.import_dir(tmp_path)
+    sub.unsubscribe()
+    assert other.get_text("text").to_string() == "abc"
+    assert len(events) == 1
+
+    assert LoroDoc().import_dir(str(tmp_path), pattern="00?.bin").pending is None
+    with pytest.raises(FileNotFoundError):
+        LoroDoc().import_dir(tmp_path / "missing")
+
+
+def test_export_chunks():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    text = doc.get_text("text")
+    for i in range(50):
+        text.insert(len(text), f"line {i}\n" * 20)
+        doc.commit()
+    chunks = doc.export_chunks(ExportMode.Snapshot(), 2048)
+    assert len(chunks) > 1
+    assert all(len(chunk) <= 2048 for chunk in chunks)
+
+    other = LoroDoc()
+    for chunk in chunks:
+        assert other.import_(chunk).pending is None
+    assert other.get_text("text").to_string() == text.to_string()
+
+    # A single change larger than a chunk is split between its ops
+    version = doc.oplog_vv
+    doc.get_list("list").insert(0, "x" * 300)
+    doc.get_list("list").insert(0, "y" * 300)
+    doc.commit()
+    chunks = doc.export_chunks(ExportMode.Updates(version), 500)
+    assert len(chunks) == 2
+    for chunk in chunks:
+        other.import_(chunk)
+    assert other.get_deep_value() == doc.get_deep_value()
+
+    with pytest.raises(BaseException, match="doesn't fit"):
+        doc.export_chunks(ExportMode.Updates(version), 50)
+    with pytest.raises(ValueError):
+        doc.export_chunks(ExportMode.StateOnly(None), 1024)
+
+
+
+def test_missing_spans():
+    a = LoroDoc()
+    a.peer_id = 1
+    a.get_text("text").insert(0, "abc")
+    a.commit()
+    b = LoroDoc()
+    b.peer_id = 2
+    b.get_text("text").insert(0, "xy")
+    b.commit()
+
+    gap = a.missing_spans(b.oplog_vv)
+    assert gap
+    assert [(s.peer, s.counter.start, s.counter.end) for s in gap.missing] == [(2, 0, 2)]
+    assert [(s.peer, s.counter.start, s.counter.end) for s in gap.ahead] == [(1, 0, 3)]
+
+    a.import_(b.export(ExportMode.UpdatesInRange(b.missing_spans(a.oplog_vv).ahead)))
+    b.import_(a.export(ExportMode.UpdatesInRange(gap.ahead)))
+    assert a.get_deep_value() == b.get_deep_value()
+    assert not a.missing_spans(b.oplog_vv)
+
+def test_export_compression():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "hello world " * 500)
+    doc.commit()
+    plain = doc.export(ExportMode.Snapshot())
+    compressed = doc.export(ExportMode.Snapshot(), compression="zstd", level=19)
+    assert len(compressed) < len(plain)
+
+    other = LoroDoc()
+    other.import_(compressed)
+    assert other.get_deep_value() == doc.get_deep_value()
+    assert LoroDoc.decode_import_blob_meta(compressed, True).mode == EncodedBlobMode.Snapshot
+
+    with pytest.raises(ValueError, match="unknown compression"):
+        doc.export(ExportMode.Snapshot(), compression="gzip")
+
+
+def test_decompression_is_bounded():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "a" * 100_000)
+    doc.commit()
+    compressed = doc.export(ExportMode.Snapshot(), compression="zstd")
+    set_max_decompressed_size(1000)
+    try:
+        with pytest.raises(BaseException, match="set_max_decompressed_size"):
+            LoroDoc().import_(compressed)
+    finally:
+        set_max_decompressed_size(256 * 1024 * 1024)
+    other = LoroDoc()
+    other.import_(compressed)
+    assert other.get_text("text").to_string() == "a" * 100_000
+
+
+
+def test_export_by_name():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    version = doc.oplog_vv
+    frontiers = doc.oplog_frontiers
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+
+    other = LoroDoc()
+    other.import_(doc.export("snapshot"))
+    assert other.get_text("text").to_string() == "abcdef"
+
+    other = LoroDoc()
+    other.import_(doc.export("updates"))
+    assert other.get_text("text").to_string() == "abcdef"
+    assert doc.export("updates", from_=version) == doc.export(ExportMode.Updates(version))
+
+    other = LoroDoc()
+    other.import_(doc.export("shallow-snapshot", frontiers=frontiers))
+    assert other.is_shallow()
+
+    with pytest.raises(ValueError, match="unknown export mode"):
+        doc.export("everything")
+    with pytest.raises(TypeError, match="unexpected option 'frontiers'"):
+        doc.export("updates", frontiers=frontiers)
+    with pytest.raises(TypeError, match="needs the option 'frontiers'"):
+        doc.export("shallow-snapshot")
+    with pytest.raises(TypeError, match="only accepted with a mode name"):
+        doc.export(ExportMode.Snapshot(), frontiers=frontiers)
+
+
+def test_export_mode_constructors():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    version = doc.oplog_vv
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+
+    assert isinstance(ExportMode.snapshot(), ExportMode.Snapshot)
+    assert ExportMode.updates().from_ == VersionVector()
+    assert doc.export(ExportMode.updates(version)) == doc.export(
+        ExportMode.Updates(version)
+    )
+    assert ExportMode.state_only().frontiers is None
+    mode = ExportMode.updates_in_time_range(10, 20)
+    assert (mode.start_ts, mode.end_ts) == (10, 20)
+
+    with pytest.raises(ValueError, match="is after end_ts"):
+        ExportMode.updates_in_time_range(20, 10)
+    with pytest.raises(ValueError, match="non-empty frontiers"):
+        ExportMode.shallow_snapshot(Frontiers())
+    with pytest.raises(ValueError, match="non-empty frontiers"):
+        doc.export("shallow-snapshot", frontiers=Frontiers())
+    with pytest.raises(TypeError):
+        ExportMode.snapshot_at()  # type: ignore[call-arg]
+
+def test_export_updates_in_time_range():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.set_change_merge_interval(0)
+    text = doc.get_text("text")
+    for i, ts in enumerate([100, 200, 300]):
+        text.insert(i, "abc"[i])
+        doc.set_next_commit_timestamp(ts)
+        doc.commit()
+
+    other = LoroDoc()
+    other.import_(doc.export(ExportMode.UpdatesInTimeRange(0, 200)))
+    assert other.get_text("text").to_string() == "a"
+    other.import_(doc.export(ExportMode.UpdatesInTimeRange(200, 1_000)))
+    assert other.get_text("text").to_string() == "abc"
+
+    assert LoroDoc.decode_import_blob_meta(
+        doc.export(ExportMode.UpdatesInTimeRange(200, 300)), True
+    ).change_num == 1
+
+    other = LoroDoc()
+    for chunk in doc.export_chunks(ExportMode.UpdatesInTimeRange(0, 300), 1024):
+        other.import_(chunk)
+    assert other.get_text("text").to_string() == "ab"
+
+
+def test_import_verify():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    version = doc.oplog_vv
+    snapshot = doc.export(ExportMode.Snapshot())
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+    updates = doc.export(ExportMode.Updates(version))
+
+    other = LoroDoc()
+    status = other.import_(updates)
+    assert status.verify == "partial"
+    assert not status.complete and status.pending is not None
+    assert LoroDoc().import_(updates, verify="partial").pending is not None
+
+    other = LoroDoc()
+    with pytest.raises(BaseException, match="missing from the document"):
+        other.import_(updates, verify="strict")
+    assert other.oplog_vv == VersionVector()
+    status = other.import_(snapshot, verify="strict")
+    assert status.verify == "strict" and status.complete
+    status = other.import_with(updates, "sync", verify="strict")
+    assert status.pending is None
+    assert other.get_text("text").to_string() == "abcdef"
+    json = doc.export_json_updates(VersionVector(), doc.oplog_vv)
+    assert LoroDoc().import_json_updates(json).verify is None
+    assert LoroDoc().import_batch([snapshot]).verify == "partial"
+
+    requests = []
+    filtered = LoroDoc()
+    filtered.set_import_filter(lambda request: requests.append(request) or True)
+    filtered.import_(snapshot, verify="strict")
+    assert [r.peers for r in requests] == [[doc.peer_id]]
+
+    flipped = snapshot[:20] + bytes([snapshot[20] ^ 0xFF]) + snapshot[21:]
+    with pytest.raises(BaseException):
+        LoroDoc().import_(flipped)
+    with pytest.raises(ValueError):
+        other.import_(updates, verify="lenient")
+    with pytest.raises(ValueError):
+        other.import_(updates, verify=False)
+
+
+def test_active_subscriptions():
+    doc = LoroDoc()
+    events = []
+    root = doc.subscribe_root(events.append)
+    text = doc.get_text("text")
+    watched = doc.subscribe(text.id, events.append)
+    dropped = doc.subscribe_jsonpath("$.text", lambda: None)
+    del dropped
+
+    infos = doc.active_subscriptions()
+    assert [info.kind for info in infos] == ["root", "container"]
+    assert infos[0].target is None
+    assert infos[1].target == "cid:root-text:Text"
+    assert infos[0].created_at.startswith(__file__)
+
+    root.unsubscribe()
+    assert [info.kind for info in doc.active_subscriptions()] == ["container"]
+    assert doc.unsubscribe_all() == 1
+    assert doc.active_subscriptions() == []
+    text.insert(0, "abc")
+    doc.commit()
+    assert events == []
+    assert "active=False" in repr(watched)
+
+
+def test_subscribe_once():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    events = []
+    subscription = doc.subscribe_once(text.id, events.append)
+    assert [info.kind for info in doc.active_subscriptions()] == ["once"]
+    for i in range(2):
+        text.insert(i, "a")
+        doc.commit()
+    assert len(events) == 1
+    assert "active=False" in repr(subscription)
+    assert doc.active_subscriptions() == []
+
+
+def test_subscribe_filters_trigger_kinds():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    remote, local = [], []
+    sub_remote = doc.subscribe(text.id, remote.append, kinds={EventTriggerKind.Import})
+    sub_local = doc.subscribe_root(local.append, kinds=[EventTriggerKind.Local])
+
+    text.insert(0, "a")
+    doc.commit()
+    other = LoroDoc()
+    other.get_text("text").insert(0, "b")
+    other.commit()
+    doc.import_(other.export(ExportMode.Snapshot()))
+
+    assert [e.triggered_by for e in remote] == [EventTriggerKind.Import]
+    assert [e.triggered_by for e in local] == [EventTriggerKind.Local]
+    sub_remote.unsubscribe()
+    sub_local.unsubscribe()
+
+
+def test_wait_for_change():
+    async def main():
+        doc = LoroDoc()
+        source = LoroDoc()
+        source.get_text("text").insert(0, "abc")
+        source.commit()
+
+        loop = asyncio.get_running_loop()
+        loop.call_soon(doc.import_, source.export(ExportMode.Snapshot()))
+        event = await doc.wait_for_change(timeout=5)
+        assert event.triggered_by == EventTriggerKind.Import
+        with pytest.raises(asyncio.TimeoutError):
+            await doc.wait_for_change(doc.get_text("text").id, timeout=0.01)
+        assert doc.active_subscriptions() == []
+
+    asyncio.run(main())
+
+
+def test_commit_info():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    assert doc.commit() is None
+
+    doc.get_text("text").insert(0, "abc")
+    doc.get_map("map").insert("k", 1)
+    info = doc.commit()
+    assert info.span.peer == 1
+    assert (info.span.counter.start, info.span.counter.end) == (0, 4)
+    assert info.frontiers.encode() == doc.oplog_frontiers.encode()
+    assert info.ops == 4
+
+    doc.get_text("text").insert(0, "d")
+    info = doc.commit_with(commit_msg="more")
+    assert (info.span.counter.start, info.span.counter.end) == (4, 5)
+    assert doc.commit_with() is None
+
+
+def test_version_info():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    version = doc.oplog_frontiers
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+    doc.checkout(version)
+
+    info = doc.version_info()
+    assert info.state_vv == doc.state_vv
+    assert info.oplog_vv == doc.oplog_vv
+    assert info.state_vv != info.oplog_vv
+    assert info.state_frontiers.encode() == version.encode()
+    assert info.oplog_frontiers.encode() == doc.oplog_frontiers.encode()
+
+
+def test_seeded_peer_ids():
+    assert LoroDoc.new_with_seed(7).peer_id == LoroDoc.new_with_seed(7).peer_id
+    assert LoroDoc.new_with_seed(7).peer_id != LoroDoc.new_with_seed(8).peer_id
+
+    try:
+        set_rng_seed(42)
+        first = [LoroDoc().peer_id, LoroDoc().fork().peer_id]
+        set_rng_seed(42)
+        assert [LoroDoc().peer_id, LoroDoc().fork().peer_id] == first
+        assert len(set(first)) == 2
+    finally:
+        set_rng_seed(None)
+    assert LoroDoc().peer_id not in first
+
+
+def test_pickle_versions_and_cursors():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    text.insert(0, "hello")
+    doc.commit()
+    cursor = text.get_cursor(2, Side.Middle)
+    assert cursor is not None
+
+    assert pickle.loads(pickle.dumps(doc.oplog_vv)) == doc.oplog_vv
+    assert pickle.loads(pickle.dumps(doc.oplog_frontiers)) == doc.oplog_frontiers
+    assert pickle.loads(pickle.dumps(cursor)) == cursor
+    assert bytes(cursor) == cursor.encode()
+    assert Frontiers.decode(bytes(doc.oplog_frontiers)) == doc.oplog_frontiers
+    assert VersionVector.decode(bytes(doc.oplog_vv)) == doc.oplog_vv
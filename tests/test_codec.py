@@ -0,0 +1,61 @@
+import uuid
+from datetime import date, datetime, timezone
+from decimal import Decimal
+
+from loro import (
+    ExportMode,
+    LoroDoc,
+    register_codec,
+    unregister_codec,
+)
+
+
+def test_codec_roundtrip():
+    register_codec(uuid.UUID, str, uuid.UUID)
+    register_codec(Decimal, str, Decimal, tag="decimal")
+    try:
+        doc = LoroDoc()
+        doc_map = doc.get_map("map")
+        key = uuid.uuid4()
+        doc_map.insert("id", key)
+        doc_map.insert("price", Decimal("1.10"))
+
+        assert doc_map["id"].value == key
+        assert doc.get_deep_value() == {"map": {"id": key, "price": Decimal("1.10")}}
+
+        other = LoroDoc()
+        other.import_(doc.export(ExportMode.Snapshot()))
+        assert other.get_map("map")["price"].value == Decimal("1.10")
+    finally:
+        assert unregister_codec(uuid.UUID)
+        assert unregister_codec(Decimal)
+
+    # Without the codec the tagged map is returned as is.
+    assert doc.get_map("map")["price"].value == {"$loro_codec": "decimal", "value": "1.10"}
+
+
+def test_datetime_roundtrip():
+    stamp = datetime(2025, 1, 2, 3, 4, 5, tzinfo=timezone.utc)
+    doc = LoroDoc()
+    doc_map = doc.get_map("map")
+    doc_map.insert("at", stamp)
+    doc_map.insert("day", date(2025, 1, 2))
+
+    assert doc_map["at"].value == {
+        "$loro_codec": "datetime",
+        "value": "2025-01-02T03:04:05+00:00",
+    }
+
+    doc.set_datetime_decoding(True)
+    assert doc_map["at"].value == stamp
+    assert doc_map.get("day", resolve=True) == date(2025, 1, 2)
+    assert doc.get_deep_value() == {"map": {"at": stamp, "day": date(2025, 1, 2)}}
+    assert doc.fork().get_map("map").get_value()["at"] == stamp
+
+    # The flag belongs to the document: the others still read the tagged maps.
+    other = LoroDoc()
+    other.import_(doc.export(ExportMode.Snapshot()))
+    assert other.get_map("map")["at"].value["$loro_codec"] == "datetime"
+
+    doc.set_datetime_decoding(False)
+    assert doc_map["day"].value == {"$loro_codec": "date", "value": "2025-01-02"}
@@ -0,0 +1,38 @@
+import pytest
+
+from loro import LoroDoc, LoroMap
+from loro.contrib.pandas import from_dataframe, to_columns, to_dataframe
+
+
+def make_table():
+    doc = LoroDoc()
+    rows = doc.get_list("rows")
+    for row in [{"name": "a", "score": 1}, {"name": "b", "tag": "x"}]:
+        child = rows.push_container(LoroMap())
+        for key, value in row.items():
+            child.insert(key, value)
+    return doc, rows
+
+
+def test_to_columns_fills_missing_values():
+    _, rows = make_table()
+    assert to_columns(rows) == {
+        "name": ["a", "b"],
+        "score": [1, None],
+        "tag": [None, "x"],
+    }
+
+
+def test_dataframe_roundtrip():
+    pd = pytest.importorskip("pandas")
+    doc, rows = make_table()
+
+    df = to_dataframe(rows)
+    assert list(df["name"]) == ["a", "b"]
+
+    other = doc.get_movable_list("copy")
+    from_dataframe(pd.DataFrame({"x": [1, 2], "y": [0.5, None]}), other)
+    assert other.get_deep_value() == [{"x": 1, "y": 0.5}, {"x": 2, "y": None}]
+
+    from_dataframe(pd.DataFrame({"x": [3]}), other, replace=True)
+    assert other.get_deep_value() == [{"x": 3}]
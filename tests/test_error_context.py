@@ -0,0 +1,31 @@
+import pytest
+
+from loro import LoroDoc, LoroList, TreeID
+
+
+def test_error_carries_operation_context():
+    doc = LoroDoc()
+    items = doc.get_map("root").insert_container("items", LoroList())
+    items.push(1)
+
+    with pytest.raises(BaseException) as excinfo:
+        items.insert(10, "x")
+
+    err = excinfo.value
+    assert err.operation == "LoroList.insert"
+    assert err.container_id == items.id
+    assert err.path == ["root", "items"]
+    assert err.index == 10
+
+
+def test_tree_error_uses_node_as_index():
+    doc = LoroDoc()
+    tree = doc.get_tree("tree")
+    missing = TreeID(42, 0)
+
+    with pytest.raises(BaseException) as excinfo:
+        tree.delete(missing)
+
+    assert excinfo.value.operation == "LoroTree.delete"
+    assert excinfo.value.index == missing
+    assert excinfo.value.path == ["tree"]
@@ -0,0 +1,276 @@
+import datetime
+import json
+import re
+
+import pytest
+
+from loro import (
+    ID,
+    CounterSpan,
+    ExportMode,
+    Frontiers,
+    IdSpan,
+    LoroDoc,
+    Ordering,
+    VersionVector,
+)
+
+
+def make_concurrent_doc():
+    a = LoroDoc()
+    a.peer_id = 1
+    a.get_text("text").insert(0, "a")
+    a.commit_with(commit_msg='first "change"')
+    b = a.fork()
+    b.peer_id = 2
+    a.get_text("text").insert(1, "b")
+    a.commit()
+    b.get_text("text").insert(0, "c")
+    b.commit()
+    a.import_(b.export(ExportMode.Snapshot()))
+    a.get_text("text").insert(0, "d")
+    a.commit()
+    return a
+
+
+def test_export_change_graph_mermaid():
+    doc = make_concurrent_doc()
+    graph = doc.export_change_graph()
+    lines = graph.splitlines()
+    assert lines[0] == "graph TD"
+    assert '    c1_0["0@1<br/>peer 1, lamport 0<br/>first #quot;change#quot;"]' in lines
+    assert "    c1_0 --> c2_0" in lines
+    # The last change merges both branches.
+    assert any(re.fullmatch(r"    c2_0 --> c1_\d+", line) for line in lines)
+
+
+def test_export_change_graph_dot():
+    doc = make_concurrent_doc()
+    graph = doc.export_change_graph("dot")
+    assert graph.startswith("digraph changes {\n")
+    assert '    "0@1" -> "0@2";' in graph.splitlines()
+    with pytest.raises(ValueError):
+        doc.export_change_graph("svg")
+
+
+def test_iter_changes():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.get_text("text").insert(0, "ab")
+    doc.commit_with(commit_msg="add text")
+    start = doc.oplog_vv
+    doc.get_map("map").insert("k", 1)
+    doc.commit_with(commit_msg="set key")
+
+    changes = list(doc.iter_changes())
+    assert [c.meta.message for c in changes] == ["add text", "set key"]
+    (insert,) = changes[0].ops
+    assert insert.kind == "insert"
+    assert insert.container == doc.get_text("text").id
+    assert json.loads(insert.content) == {"pos": 0, "text": "ab"}
+
+    later = list(doc.iter_changes(from_=start))
+    assert [c.meta.message for c in later] == ["set key"]
+    assert [op.kind for op in later[0].ops] == ["insert"]
+    assert list(doc.iter_changes(to=start))[0].meta.message == "add text"
+
+
+def test_get_change_and_ops_of_an_edit():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.get_text("text").insert(0, "a")
+    doc.get_map("map").insert("k", True)
+    doc.commit_with(commit_msg="edit")
+
+    change = doc.get_change(ID(1, 1))
+    assert change.message == "edit"
+    assert change.id == ID(1, 0)
+    span = IdSpan(change.id.peer, CounterSpan(change.id.counter, change.id.counter + change.len))
+    ops = doc.get_changed_ops_in(span)
+    assert [op.id for op in ops] == [ID(1, 0), ID(1, 1)]
+    assert [op.container for op in ops] == [doc.get_text("text").id, doc.get_map("map").id]
+
+
+def test_iter_changes_of_a_peer():
+    doc = make_concurrent_doc()
+    changes = list(doc.iter_changes(peer=2))
+    assert [c.meta.id.peer for c in changes] == [2]
+    counters = [c.meta.id.counter for c in doc.iter_changes(peer=1)]
+    assert counters == sorted(counters) and len(counters) >= 2
+
+
+def test_versions_by_timestamp():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.set_change_merge_interval(0)
+    text = doc.get_text("text")
+    for i, ts in enumerate([10_000, 20_000, 30_000]):
+        text.insert(i, "abc"[i])
+        doc.set_next_commit_timestamp(ts)
+        doc.commit()
+
+    doc.checkout(doc.frontiers_at_timestamp(25_000))
+    assert text.to_string() == "ab"
+    doc.checkout(doc.frontiers_at_timestamp(5_000))
+    assert text.to_string() == ""
+    doc.checkout_to_latest()
+
+    changes = doc.changes_between(15_000, 30_000)
+    assert [c.timestamp for c in changes] == [20_000]
+
+
+def make_doc_with_a_clock_going_backwards():
+    source = LoroDoc()
+    source.peer_id = 1
+    text = source.get_text("text")
+    for i, ts in enumerate([10_000, 30_000, 30_000, 40_000]):
+        text.insert(i, "abcd"[i])
+        source.set_next_commit_timestamp(ts)
+        # Distinct messages keep the commits in separate changes.
+        source.set_next_commit_message(str(i))
+        source.commit()
+    # Local commits never go back in time, but changes from other implementations can.
+    updates = json.loads(source.export_json_updates(VersionVector(), source.oplog_vv))
+    updates["changes"][2]["timestamp"] = 20_000
+    doc = LoroDoc()
+    doc.import_json_updates(json.dumps(updates))
+    return doc
+
+
+def test_versions_by_timestamp_with_a_clock_going_backwards():
+    doc = make_doc_with_a_clock_going_backwards()
+    text = doc.get_text("text")
+
+    # The change at 20s comes after the one at 30s, so it's left out at 25s.
+    doc.checkout(doc.frontiers_at_timestamp(25_000))
+    assert text.to_string() == "a"
+    doc.checkout(doc.frontiers_at_timestamp(35_000))
+    assert text.to_string() == "abc"
+    doc.checkout_to_latest()
+
+    changes = doc.changes_between(15_000, 25_000)
+    assert [c.timestamp for c in changes] == [20_000]
+    changes = doc.changes_between(15_000, 35_000)
+    assert [c.timestamp for c in changes] == [30_000, 20_000]
+
+
+def test_timeline_by_timestamp_with_a_clock_going_backwards():
+    doc = make_doc_with_a_clock_going_backwards()
+    points = doc.timeline(3, by="timestamp")
+    assert [p.changes for p in points] == [1, 3, 4]
+    assert [p.change.timestamp for p in points] == [10_000, 20_000, 40_000]
+
+
+def test_get_value_at():
+    doc = LoroDoc()
+    doc.get_map("map").insert("title", "v1")
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    v1 = doc.oplog_frontiers
+    doc.get_map("map").insert("title", "v2")
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+
+    assert doc.get_value_at(v1) == {"map": {"title": "v1"}, "text": "abc"}
+    assert doc.get_value_at(v1, "map/title") == "v1"
+    assert doc.get_value_at(v1, "text") == "abc"
+    assert doc.get_value_at(v1, "map/missing") is None
+    assert not doc.is_detached()
+    assert doc.get_deep_value() == {"map": {"title": "v2"}, "text": "abcdef"}
+
+
+def test_get_value_at_unknown_version():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    v1 = doc.oplog_frontiers
+    doc.get_text("text").insert(3, "def")
+    doc.commit()
+
+    with pytest.raises(BaseException, match="not contained"):
+        doc.get_value_at(Frontiers.from_id(ID(99, 5)))
+
+    shallow = LoroDoc()
+    shallow.import_(doc.export(ExportMode.shallow_snapshot(doc.oplog_frontiers)))
+    with pytest.raises(BaseException, match="shallow"):
+        shallow.get_value_at(v1)
+    assert shallow.get_value_at(shallow.oplog_frontiers) == {"text": "abcdef"}
+
+
+def test_cmp_frontiers():
+    a = LoroDoc()
+    a.peer_id = 1
+    a.get_text("text").insert(0, "a")
+    a.commit()
+    base = a.oplog_frontiers
+    b = a.fork()
+    b.peer_id = 2
+    a.get_text("text").insert(1, "b")
+    a.commit()
+    b.get_text("text").insert(0, "c")
+    b.commit()
+    a.import_(b.export(ExportMode.Snapshot()))
+
+    left = Frontiers.from_id(ID(1, 1))
+    right = Frontiers.from_id(ID(2, 0))
+    assert a.cmp_frontiers(base, left) == Ordering.Less
+    assert a.cmp_frontiers(left, base) == Ordering.Greater
+    assert a.cmp_frontiers(left, left) == Ordering.Equal
+    assert a.cmp_frontiers(left, right) == Ordering.Concurrent
+    assert a.cmp_with_frontiers(base) == Ordering.Greater
+    with pytest.raises(ValueError):
+        LoroDoc().cmp_frontiers(left, right)
+
+
+def test_timeline():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.set_change_merge_interval(0)
+    text = doc.get_text("text")
+    for i, ts in enumerate([10_000, 11_000, 12_000, 40_000]):
+        text.insert(i, "abcd"[i])
+        doc.set_next_commit_timestamp(ts)
+        doc.commit()
+
+    points = doc.timeline(2)
+    assert [p.changes for p in points] == [2, 4]
+    assert points[-1].change.timestamp == 40_000
+    doc.checkout(points[0].frontiers)
+    assert text.to_string() == "ab"
+    doc.checkout_to_latest()
+
+    points = doc.timeline(3, by="timestamp")
+    assert [p.changes for p in points] == [3, 4]
+    assert [p.change.timestamp for p in points] == [12_000, 40_000]
+    assert len(doc.timeline(10)) == 4
+    assert LoroDoc().timeline(3) == []
+
+    with pytest.raises(ValueError):
+        doc.timeline(0)
+    with pytest.raises(ValueError):
+        doc.timeline(3, by="lamport")
+
+
+def test_change_meta_accessors():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.get_text("text").insert(0, "a")
+    doc.set_next_commit_timestamp(1_700_000_000)
+    doc.commit_with(commit_msg=json.dumps({"kind": "edit", "n": 1}))
+    doc.get_text("text").insert(1, "b")
+    doc.commit_with(commit_msg="not json")
+
+    first = doc.get_change(ID(1, 0))
+    assert first.datetime == datetime.datetime(
+        2023, 11, 14, 22, 13, 20, tzinfo=datetime.timezone.utc
+    )
+    assert first.message_json == {"kind": "edit", "n": 1}
+    assert len(first.deps) == 0
+    assert list(first.deps) == []
+
+    second = doc.get_change(ID(1, 1))
+    assert list(second.deps) == [ID(1, 0)]
+    assert ID(1, 0) in second.deps
+    assert second.deps == Frontiers.from_id(ID(1, 0))
+    with pytest.raises(ValueError):
+        second.message_json
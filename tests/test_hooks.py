@@ -0,0 +1,464 @@
+import gc
+import queue
+import threading
+
+import pytest
+from loro import (
+    CounterSpan,
+    EventTriggerKind,
+    ExportMode,
+    Frontiers,
+    ID,
+    IdSpan,
+    LoroDoc,
+    VersionVector,
+)
+from loro.contrib.fernet import generate_key, set_key
+
+
+def test_metrics_hook_reports_operations():
+    doc = LoroDoc()
+    metrics = []
+    doc.set_metrics_hook(metrics.append)
+
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    snapshot = doc.export(ExportMode.Snapshot())
+
+    other = LoroDoc()
+    other.set_metrics_hook(metrics.append)
+    other.import_(snapshot)
+
+    assert [m.operation for m in metrics] == ["commit", "export", "import"]
+    commit, export, import_ = metrics
+    assert commit.ops == 3 and commit.bytes == 0
+    assert export.bytes == len(snapshot)
+    assert import_.bytes == len(snapshot) and import_.ops == 3
+    assert all(m.duration >= 0 for m in metrics)
+
+    doc.set_metrics_hook(None)
+    doc.commit()
+    assert len(metrics) == 3
+
+
+def test_failing_metrics_hook_does_not_fail_the_operation():
+    doc = LoroDoc()
+
+    def hook(_):
+        raise RuntimeError("boom")
+
+    doc.set_metrics_hook(hook)
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    assert doc.get_text("text").to_string() == "abc"
+
+
+def test_import_filter_rejects_imports():
+    source = LoroDoc()
+    source.peer_id = 7
+    source.get_text("public").insert(0, "hi")
+    source.get_map("private").insert("key", 1)
+    source.commit()
+    updates = source.export(ExportMode.Updates(VersionVector()))
+
+    doc = LoroDoc()
+    requests = []
+
+    def only_public(request):
+        requests.append(request)
+        return all(c.name == "public" for c in request.containers)
+
+    doc.set_import_filter(only_public)
+    with pytest.raises(BaseException, match="import filter"):
+        doc.import_(updates)
+    assert doc.get_deep_value() == {}
+    (request,) = requests
+    assert request.peers == [7]
+    assert request.spans[0].counter.start == 0 and request.spans[0].counter.end == 3
+    assert {c.name for c in request.containers} == {"public", "private"}
+
+    doc.set_import_filter(lambda request: request.peers == [7])
+    doc.import_(updates)
+    assert doc.get_text("public").to_string() == "hi"
+
+    doc.set_import_filter(None)
+    doc.import_(LoroDoc().export(ExportMode.Snapshot()))
+
+
+def test_hooks_live_as_long_as_the_document():
+    source = LoroDoc()
+    source.get_text("text").insert(0, "hi")
+    source.commit()
+    updates = source.export(ExportMode.Snapshot())
+
+    text = LoroDoc().get_text("text")
+    text.doc().set_import_filter(lambda request: False)
+    gc.collect()
+    # Only the container keeps the document alive now.
+    with pytest.raises(BaseException, match="import filter"):
+        text.doc().import_(updates)
+    assert text.to_string() == ""
+
+
+def test_import_filter_applies_to_every_import_path():
+    source = LoroDoc()
+    source.peer_id = 7
+    source.get_text("text").insert(0, "abc")
+    source.commit()
+    updates = source.export(ExportMode.Updates(VersionVector()))
+    json_updates = source.export_json_updates(VersionVector(), source.oplog_vv)
+
+    doc = LoroDoc()
+    requests = []
+
+    def reject(request):
+        requests.append(request)
+        return False
+
+    doc.set_import_filter(reject)
+    # A handle of the same document shares its hooks.
+    handle = doc.get_text("text").doc()
+    with pytest.raises(BaseException, match="import filter"):
+        handle.import_(updates)
+    with pytest.raises(BaseException, match="import filter"):
+        doc.import_json_updates(json_updates)
+    assert doc.get_text("text").to_string() == ""
+    assert requests[-1].meta is None
+    assert requests[-1].peers == [7]
+
+
+def test_import_request_describes_the_decoded_changes():
+    source = LoroDoc()
+    source.peer_id = 7
+    text = source.get_text("text")
+    text.insert(0, "ab")
+    source.commit()
+    doc = LoroDoc()
+    doc.import_(source.export(ExportMode.Snapshot()))
+    text.insert(2, "c")
+    source.commit()
+
+    requests = []
+    doc.set_import_filter(lambda request: requests.append(request) or True)
+    # The blob carries the whole history, but only its last op is new to the document.
+    doc.import_(source.export(ExportMode.Updates(VersionVector())))
+    (request,) = requests
+    assert request.meta.partial_end_vv.includes_id(ID(7, 0))
+    assert [(s.peer, s.counter.start, s.counter.end) for s in request.spans] == [(7, 2, 3)]
+
+
+def test_encryption_hooks_wrap_export_and_import():
+    def encrypt(data):
+        return bytes(b ^ 0x5A for b in data)
+
+    doc = LoroDoc()
+    doc.set_encryption(encrypt, encrypt)
+    doc.get_text("text").insert(0, "secret")
+    doc.commit()
+    blob = doc.export(ExportMode.Snapshot())
+    assert b"secret" not in blob
+
+    with pytest.raises(BaseException, match="encrypted"):
+        LoroDoc().import_(blob)
+    with pytest.raises(BaseException, match="isn't encrypted"):
+        doc.import_(LoroDoc().export(ExportMode.Snapshot()))
+
+    other = LoroDoc()
+    other.set_encryption(None, encrypt)
+    other.import_(blob)
+    assert other.get_text("text").to_string() == "secret"
+    # Without an encrypt callback, the exports are plain.
+    LoroDoc().import_(other.export(ExportMode.Snapshot()))
+
+
+def test_encryption_covers_every_export_path():
+    def encrypt(data):
+        return bytes(b ^ 0x5A for b in data)
+
+    doc = LoroDoc()
+    doc.peer_id = 1
+    text = doc.get_text("text")
+    text.insert(0, "secret")
+    doc.commit()
+    text.doc().set_encryption(encrypt, encrypt)
+
+    chunks = doc.export_chunks(ExportMode.Snapshot(), 1024)
+    assert chunks and all(b"secret" not in chunk for chunk in chunks)
+    with pytest.raises(BaseException, match="encrypted"):
+        doc.export_json_updates(VersionVector(), doc.oplog_vv)
+    with pytest.raises(BaseException, match="encrypted"):
+        doc.export_json_in_id_span(IdSpan(1, CounterSpan(0, 6)))
+
+
+def test_fernet_key():
+    pytest.importorskip("cryptography")
+    key = generate_key()
+    doc = LoroDoc()
+    set_key(doc, key)
+    doc.get_text("text").insert(0, "secret")
+    doc.commit()
+    blob = doc.export(ExportMode.Snapshot())
+
+    other = LoroDoc()
+    set_key(other, key)
+    other.import_(blob)
+    assert other.get_text("text").to_string() == "secret"
+
+
+def test_readonly_document():
+    source = LoroDoc()
+    source.get_text("text").insert(0, "hello")
+    source.commit()
+
+    doc = LoroDoc()
+    doc.set_readonly(True)
+    assert doc.is_readonly()
+    with pytest.raises(BaseException):
+        doc.get_text("text").insert(0, "oops")
+    with pytest.raises(ValueError):
+        doc.set_detached_editing(True)
+
+    doc.import_(source.export(ExportMode.Snapshot()))
+    assert doc.get_text("text").to_string() == "hello"
+    doc.checkout_to_latest()
+    with pytest.raises(BaseException):
+        doc.get_text("text").insert(0, "oops")
+
+    doc.set_readonly(True, allow_import=False)
+    with pytest.raises(BaseException, match="read-only"):
+        doc.import_(source.export(ExportMode.Snapshot()))
+    # The mode is shared by every handle of the document.
+    with pytest.raises(BaseException, match="read-only"):
+        doc.get_text("text").doc().import_(source.export(ExportMode.Snapshot()))
+
+    doc.set_readonly(False)
+    assert not doc.is_readonly()
+    doc.get_text("text").insert(5, "!")
+    doc.commit()
+    assert doc.get_text("text").to_string() == "hello!"
+
+
+def test_readonly_outlives_the_loro_doc_objects():
+    source = LoroDoc()
+    source.get_text("text").insert(0, "hello")
+    source.commit()
+
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    doc.set_readonly(True)
+    del doc
+    gc.collect()
+
+    assert text.doc().is_readonly()
+    assert not text.doc().is_detached()
+    with pytest.raises(BaseException):
+        text.insert(0, "oops")
+    text.doc().import_(source.export(ExportMode.Snapshot()))
+    assert text.to_string() == "hello"
+
+    text.doc().set_readonly(False)
+    assert not text.doc().is_detached()
+    text.insert(5, "!")
+    assert text.to_string() == "hello!"
+
+
+def test_readonly_restores_the_detached_state():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    text.insert(0, "a")
+    doc.commit()
+    before = doc.state_frontiers
+    text.insert(1, "b")
+    doc.commit()
+
+    doc.checkout(before)
+    doc.set_readonly(True)
+    doc.set_readonly(False)
+    assert doc.is_detached()
+    assert doc.state_frontiers == before
+
+    doc.set_detached_editing(True)
+    doc.set_readonly(True)
+    doc.set_readonly(False)
+    assert doc.is_detached() and doc.is_detached_editing_enabled
+    text.insert(1, "c")
+
+
+def test_readonly_document_reports_imports():
+    source = LoroDoc()
+    source.get_text("text").insert(0, "hello")
+    source.commit()
+
+    doc = LoroDoc()
+    doc.set_readonly(True)
+    assert not doc.is_detached()
+    imports = []
+    events = []
+    sub_imports = doc.subscribe_root(imports.append, kinds={EventTriggerKind.Import})
+    sub_events = doc.subscribe_root(events.append)
+    doc.import_with(source.export(ExportMode.Snapshot()), "remote")
+    assert [e.origin for e in imports] == ["remote"]
+    assert [e.triggered_by for e in events] == [EventTriggerKind.Import]
+    assert not doc.is_detached()
+
+    # Checking out an older version is still reported as a checkout.
+    doc.checkout(Frontiers())
+    assert doc.is_detached()
+    assert events[-1].triggered_by == EventTriggerKind.Checkout
+    sub_imports.unsubscribe()
+    sub_events.unsubscribe()
+
+
+def test_quota_hook_vetoes_growth():
+    requests = []
+
+    def at_most_5_ops(request):
+        requests.append(request)
+        return request.doc_ops + request.ops <= 5
+
+    doc = LoroDoc()
+    doc.set_quota_hook(at_most_5_ops)
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    doc.get_text("text").insert(3, "def")
+    with pytest.raises(BaseException, match="quota"):
+        doc.commit()
+    assert requests[-1].operation == "commit" and requests[-1].ops == 3
+    assert requests[-1].bytes is None and requests[-1].doc_ops == 3
+    # The rejected ops stay pending and are checked again by the next commit.
+    doc.set_quota_hook(lambda request: True)
+    assert doc.commit().ops == 3
+
+    source = LoroDoc()
+    source.get_text("text").insert(0, "too long")
+    source.commit()
+    blob = source.export(ExportMode.Snapshot())
+    other = LoroDoc()
+    other.set_quota_hook(at_most_5_ops)
+    with pytest.raises(BaseException, match="quota"):
+        other.import_(blob)
+    assert requests[-1].operation == "import" and requests[-1].ops == 8
+    assert requests[-1].bytes == len(blob)
+    assert other.get_text("text").to_string() == ""
+
+
+def test_quota_hook_counts_the_ops_of_a_batch_once():
+    source = LoroDoc()
+    text = source.get_text("text")
+    text.insert(0, "abc")
+    source.commit()
+    first = source.export(ExportMode.Updates(VersionVector()))
+    version = source.oplog_vv
+    text.insert(3, "de")
+    source.commit()
+    second = source.export(ExportMode.Updates(version))
+
+    requests = []
+    doc = LoroDoc()
+    doc.set_quota_hook(lambda request: requests.append(request) or True)
+    doc.set_import_filter(lambda request: requests.append(request) or True)
+    # The second blob depends on the first one, which is imported twice.
+    doc.import_batch([second, first, first])
+    filter_request, quota_request = requests
+    assert filter_request.meta is None
+    assert [(s.counter.start, s.counter.end) for s in filter_request.spans] == [(0, 5)]
+    assert quota_request.ops == 5
+    assert quota_request.bytes == len(second) + 2 * len(first)
+    assert doc.get_text("text").to_string() == "abcde"
+
+
+def test_timestamp_provider_stamps_changes():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    clock = iter([1000, 5000])
+    doc.set_timestamp_provider(lambda: next(clock))
+    doc.get_text("text").insert(0, "a")
+    doc.commit()
+    doc.get_text("text").insert(1, "b")
+    doc.commit()
+    timestamps = [c.meta.timestamp for c in doc.iter_changes()]
+    assert timestamps == [1000, 5000]
+
+    doc.set_timestamp_provider(None)
+    doc.get_text("text").insert(2, "c")
+    doc.commit()
+
+
+def test_failing_timestamp_provider_keeps_the_default_clock():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.set_record_timestamp(True)
+
+    def clock():
+        raise RuntimeError("clock is down")
+
+    doc.set_timestamp_provider(clock)
+    doc.get_text("text").insert(0, "a")
+    doc.commit()
+    assert doc.get_change(ID(1, 0)).timestamp > 0
+
+
+def test_dispatcher_runs_callbacks_off_the_committing_thread():
+    doc = LoroDoc()
+    threads = []
+    done = threading.Event()
+
+    def callback(event):
+        threads.append(threading.get_ident())
+        done.set()
+
+    sub = doc.subscribe_root(callback)
+    doc.set_dispatcher("thread")
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    assert done.wait(5)
+    assert threads[0] != threading.get_ident()
+
+    # The executor is called from the dispatch thread, not by the commit.
+    jobs = queue.Queue()
+    doc.set_dispatcher(jobs.put)
+    doc.get_text("text").insert(0, "x")
+    doc.commit()
+    job = jobs.get(timeout=5)
+    assert len(threads) == 1 and jobs.empty()
+    job()
+    job()
+    assert threads[1:] == [threading.get_ident()]
+
+    doc.set_dispatcher("inline")
+    doc.get_text("text").insert(0, "y")
+    doc.commit()
+    assert threads[2:] == [threading.get_ident()]
+    sub.unsubscribe()
+
+    with pytest.raises(ValueError):
+        doc.set_dispatcher("asyncio")
+    with pytest.raises(TypeError):
+        doc.set_dispatcher(1)
+
+
+def test_pending_changes_and_hook():
+    source = LoroDoc()
+    source.peer_id = 1
+    source.get_text("text").insert(0, "abc")
+    source.commit()
+    first = source.export(ExportMode.Snapshot())
+    source.get_text("text").insert(3, "def")
+    source.commit()
+    second = source.export(ExportMode.UpdatesInRange([IdSpan(1, CounterSpan(3, 6))]))
+
+    def ranges(spans):
+        return [(s.peer, s.counter.start, s.counter.end) for s in spans]
+
+    doc = LoroDoc()
+    applied = []
+    doc.set_pending_hook(lambda spans: applied.append(ranges(spans)))
+    assert doc.pending_changes() == []
+    doc.import_(second)
+    assert ranges(doc.pending_changes()) == [(1, 3, 6)]
+    assert applied == []
+
+    doc.import_(first)
+    assert doc.pending_changes() == []
+    assert applied == [[(1, 3, 6)]]
+    assert doc.get_text("text").to_string() == "abcdef"
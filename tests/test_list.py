@@ -0,0 +1,30 @@
+from loro import ID, LoroDoc, LoroList
+
+
+def test_list_iter_ids():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    items = doc.get_list("items")
+    items.push("a")
+    items.push("b")
+    items.insert(0, "c")
+    ids = list(items.iter_ids())
+    assert ids == [(0, ID(1, 2)), (1, ID(1, 0)), (2, ID(1, 1))]
+    assert ids == [(i, items.get_id_at(i)) for i in range(len(items))]
+
+    items.delete(1, 1)
+    assert list(items.iter_ids()) == [(0, ID(1, 2)), (1, ID(1, 1))]
+    assert list(LoroList().iter_ids()) == []
+
+
+def test_list_iter_ids_is_lazy():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    items = doc.get_list("items")
+    for value in "abc":
+        items.push(value)
+    ids = items.iter_ids()
+    assert iter(ids) is ids
+    assert next(ids) == (0, ID(1, 0))
+    items.delete(1, 1)
+    assert list(ids) == [(1, ID(1, 2))]
@@ -1,6 +1,16 @@
+import copy
+
 import pytest
 
-from loro import LoroDoc, LoroText
+from loro import (
+    ID,
+    ContainerID,
+    ContainerType,
+    LoroDoc,
+    LoroText,
+    StyleConfigMap,
+    TreeID,
+)
 
 
 def make_text(content: str = "hello"):
@@ -180,3 +190,122 @@ def test_loromap_magic_methods():
 
     with pytest.raises(KeyError):
         del map_obj["missing"]
+
+
+def test_repr_of_containers_and_ids():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.get_map("map")["x"] = 1
+    doc.get_list("list").push("a")
+    doc.get_counter("counter").increment(2)
+    doc.commit()
+
+    assert repr(doc.get_map("map")) == "LoroMap({'x': 1})"
+    assert repr(doc.get_list("list")) == "LoroList(['a'])"
+    assert repr(doc.get_counter("counter")) == "LoroCounter(2.0)"
+    assert repr(doc.get_tree("tree")) == "LoroTree(nodes=0)"
+    assert repr(doc) == (
+        "LoroDoc(peer_id=1, frontiers=Frontiers([ID(peer=1, counter=2)]), detached=False)"
+    )
+
+    assert repr(ID(1, 2)) == "ID(peer=1, counter=2)"
+    assert repr(TreeID(3, 4)) == "TreeID(peer=3, counter=4)"
+    assert (
+        repr(ContainerID.Root("map", ContainerType.Map()))
+        == 'ContainerID.Root(name="map", container_type=ContainerType.Map)'
+    )
+    assert str(ID(1, 2)) == "ID { peer: 1, counter: 2 }"
+    long = LoroDoc().get_list("long")
+    for i in range(30):
+        long.push(i)
+    assert repr(long).endswith(", ...])")
+    assert repr(doc.oplog_vv) == "VersionVector({1: 3})"
+
+    sub = doc.subscribe_root(lambda _: None)
+    assert repr(sub) == "Subscription(active=True)"
+    sub.unsubscribe()
+    assert repr(sub) == "Subscription(active=False)"
+
+
+def test_jupyter_display_hooks():
+    doc = LoroDoc()
+    doc.config_text_style(StyleConfigMap.default_rich_text_config())
+    text = doc.get_text("text")
+    text.insert(0, "Hello <World>")
+    text.mark(start=0, end=5, key="bold", value=True)
+    tree = doc.get_tree("tree")
+    root = tree.create()
+    child = tree.create(root)
+    tree.get_meta(child).insert("name", "leaf")
+    doc.commit()
+
+    assert text._repr_html_() == (
+        '<div class="loro-text" style="white-space: pre-wrap">'
+        "<strong>Hello</strong> &lt;World&gt;</div>"
+    )
+    assert text._repr_markdown_() == "**Hello** \\<World\\>"
+
+    html = tree._repr_html_()
+    assert "<details open><summary>" in html
+    assert "leaf" in html
+    assert tree._repr_markdown_().splitlines()[1].startswith("  - ")
+
+    assert "<td>text</td><td>Text</td>" in doc._repr_html_()
+    assert "| tree | Tree |" in doc._repr_markdown_()
+
+
+def test_only_safe_links_are_rendered():
+    doc = LoroDoc()
+    doc.config_text_style(StyleConfigMap.default_rich_text_config())
+    text = doc.get_text("text")
+    text.insert(0, "safe evil")
+    text.mark(start=0, end=4, key="link", value="https://loro.dev")
+    text.mark(start=5, end=9, key="link", value="javascript:alert(1)")
+
+    html = text._repr_html_()
+    assert '<a href="https://loro.dev">safe</a>' in html
+    assert "javascript" not in html
+    assert html.endswith(" evil</div>")
+    assert text._repr_markdown_() == "[safe](<https://loro.dev>) evil"
+
+
+def test_rich_integration():
+    doc = LoroDoc()
+    doc.config_text_style(StyleConfigMap.default_rich_text_config())
+    doc.get_map("map")["x"] = 1
+    text = doc.get_text("text")
+    text.insert(0, "Hello")
+    text.mark(start=0, end=5, key="bold", value=True)
+    events = []
+    sub = doc.subscribe_root(events.append)
+    doc.get_counter("counter").increment(1)
+    doc.commit()
+    sub.unsubscribe()
+
+    assert doc.get_map("map").__rich_repr__() == [{"x": 1}]
+    assert doc.get_counter("counter").__rich_repr__() == [1.0]
+    fields = dict(events[0].__rich_repr__())
+    assert fields["origin"] == ""
+    assert dict(fields["events"][0].__rich_repr__())["is_unknown"] is False
+
+    pytest.importorskip("rich")
+    rendered = text.__rich__()
+    assert rendered.plain == "Hello"
+    assert str(rendered.spans[0].style) == "bold"
+    tree = doc.get_tree("tree")
+    tree.create()
+    assert len(tree.__rich__().children) == 1
+
+
+def test_loro_doc_copy_forks():
+    doc = LoroDoc()
+    doc.get_text("text").insert(0, "abc")
+    doc.commit()
+    state = {"doc": doc, "tags": ["a"]}
+
+    for clone in (copy.copy(doc), copy.deepcopy(state)["doc"]):
+        assert clone.peer_id != doc.peer_id
+        assert clone.get_deep_value() == {"text": "abc"}
+        clone.get_text("text").insert(3, "d")
+        clone.commit()
+        assert doc.get_text("text").to_string() == "abc"
@@ -1,4 +1,14 @@
-from loro import LoroDoc, LoroList
+import pytest
+from loro import (
+    ID,
+    ExportMode,
+    LoroCounter,
+    LoroDoc,
+    LoroList,
+    LoroMap,
+    LoroMovableList,
+    LoroText,
+)
 
 def test_map():
     doc = LoroDoc()
@@ -12,3 +22,190 @@ def test_map():
     }
     map["key2"] = "value2"
     assert map["key2"].value == "value2"
+
+
+def test_tuple_and_set_values():
+    doc = LoroDoc()
+    doc_map = doc.get_map("map")
+    doc_map.insert("point", (1, 2))
+    assert doc_map["point"].value == [1, 2]
+
+    with pytest.raises(TypeError, match="sorted"):
+        doc_map.insert("tags", {"a", "b"})
+    with pytest.raises(TypeError, match="sorted"):
+        doc_map.insert("tags", frozenset({"a"}))
+
+
+def test_buffer_values_are_binary():
+    doc = LoroDoc()
+    doc_map = doc.get_map("map")
+    doc_map.insert("raw", bytearray(b"abc"))
+    doc_map.insert("view", memoryview(b"xyz"))
+    assert doc_map["raw"].value == b"abc"
+    assert doc_map["view"].value == b"xyz"
+
+
+def test_numpy_values():
+    np = pytest.importorskip("numpy")
+    doc = LoroDoc()
+    doc_map = doc.get_map("map")
+    doc_map.insert("int", np.int64(5))
+    doc_map.insert("float", np.float32(0.5))
+    doc_map.insert("flag", np.bool_(True))
+    doc_map.insert("bytes", np.array([1, 2, 3], dtype=np.uint8))
+
+    assert doc_map["int"].value == 5
+    assert doc_map["float"].value == 0.5
+    assert doc_map["flag"].value is True
+    assert doc_map["bytes"].value == b"\x01\x02\x03"
+
+    with pytest.raises(TypeError, match="tolist"):
+        doc_map.insert("matrix", np.zeros((2, 2)))
+
+
+def test_map_get_all_returns_concurrent_values():
+    a, b = LoroDoc(), LoroDoc()
+    a.peer_id = 1
+    b.peer_id = 2
+    a.get_map("map").insert("title", "base")
+    a.commit()
+    b.import_(a.export(ExportMode.Snapshot()))
+
+    a.get_map("map").insert("title", "from a")
+    a.commit()
+    b.get_map("map").delete("title")
+    b.commit()
+    a.import_(b.export(ExportMode.Snapshot()))
+
+    values = a.get_map("map").get_all("title")
+    assert sorted(values, key=lambda v: v[0]) == [(1, "from a"), (2, None)]
+    current = a.get_map("map").get("title")
+    assert values[0][1] == (current.value if current is not None else None)
+
+    a.get_map("map").insert("title", "resolved")
+    a.commit()
+    assert a.get_map("map").get_all("title") == [(1, "resolved")]
+    assert a.get_map("map").get_all("missing") == []
+    assert LoroMap().get_all("title") == []
+
+
+def test_map_get_all_follows_checkout():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.get_map("map").insert("k", 1)
+    doc.commit()
+    version = doc.state_frontiers
+    doc.get_map("map").insert("k", 2)
+    doc.commit()
+
+    doc.checkout(version)
+    assert doc.get_map("map")["k"].value == 1
+    assert doc.get_map("map").get_all("k") == [(1, 1)]
+    doc.attach()
+    assert doc.get_map("map").get_all("k") == [(1, 2)]
+
+
+def test_map_get_entry_meta():
+    a, b = LoroDoc(), LoroDoc()
+    a.peer_id = 1
+    b.peer_id = 2
+    a.get_map("map").insert("title", "base")
+    a.commit()
+    b.import_(a.export(ExportMode.Snapshot()))
+
+    a.get_map("map").insert("title", "from a")
+    a.commit()
+    b.get_map("map").insert("title", "from b")
+    b.commit()
+    a.import_(b.export(ExportMode.Snapshot()))
+
+    # Same lamport, so the greater peer wins
+    assert a.get_map("map")["title"].value == "from b"
+    meta = a.get_map("map").get_entry_meta("title")
+    assert meta is not None
+    assert meta.id == ID(2, 0)
+    assert meta.peer == 2
+    assert meta.lamport == 1
+
+    a.get_map("map").delete("title")
+    a.commit()
+    assert a.get_map("map").get_entry_meta("title") is None
+    assert a.get_map("map").get_entry_meta("missing") is None
+    assert LoroMap().get_entry_meta("title") is None
+
+
+def test_map_get_entry_meta_follows_checkout():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    doc.get_map("map").insert("k", 1)
+    doc.commit()
+    version = doc.state_frontiers
+    doc.get_map("map").insert("k", 2)
+    doc.commit()
+
+    doc.checkout(version)
+    meta = doc.get_map("map").get_entry_meta("k")
+    assert meta is not None
+    assert meta.id == ID(1, 0)
+    assert meta.lamport == 0
+
+
+def test_get_resolve():
+    doc = LoroDoc()
+    map = doc.get_map("map")
+    map["title"] = "doc"
+    items = map.insert_container("items", LoroList())
+    items.push(1)
+    child = items.insert_container(1, LoroMap())
+    child.insert_container("body", LoroText()).insert(0, "hi")
+    moves = map.insert_container("moves", LoroMovableList())
+    moves.insert_container(0, LoroList()).push("x")
+
+    assert map.get("title", resolve=True) == "doc"
+    assert map.get("items", resolve=True) == [1, {"body": "hi"}]
+    assert map.get("items").container.id == items.id
+    assert items.get(1, resolve=True) == {"body": "hi"}
+    assert items.get(0, resolve=True) == 1
+    assert moves.get(0, resolve=True) == ["x"]
+    assert map.get("missing", resolve=True) is None
+    assert items.get(5, resolve=True) is None
+
+
+def test_get_with_default():
+    doc = LoroDoc()
+    map = doc.get_map("map")
+    map["empty"] = None
+    items = doc.get_list("items")
+    items.push(None)
+
+    assert map.get("missing", 0) == 0
+    assert map.get("missing", default="x", resolve=True) == "x"
+    assert map.get("empty", 0).value is None
+    assert map.get("empty", 0, resolve=True) is None
+    assert "empty" in map and "missing" not in map
+    assert items.get(3, "x") == "x"
+    assert items.get(0, "x", resolve=True) is None
+    assert doc.get_movable_list("moves").get(0, []) == []
+
+
+def test_to_py():
+    doc = LoroDoc()
+    map = doc.get_map("map")
+    items = map.insert_container("items", LoroList())
+    items.push(1)
+    items.insert_container(1, LoroText()).insert(0, "hi")
+    counter = map.insert_container("count", LoroCounter())
+    counter.increment(2)
+    moves = doc.get_movable_list("moves")
+    moves.insert_container(0, LoroMap())["a"] = 1
+    tree = doc.get_tree("tree")
+    tree.create()
+    doc.commit()
+
+    assert map.to_py() == {"items": [1, "hi"], "count": 2.0}
+    assert items.to_py() == [1, "hi"]
+    assert items.get(1).container.to_py() == "hi"
+    assert counter.to_py() == 2.0
+    assert moves.to_py() == [{"a": 1}]
+    assert tree.to_py() == doc.get_deep_value()["tree"]
+    assert map.get("items").to_py() == [1, "hi"]
@@ -0,0 +1,53 @@
+import pytest
+
+from loro import (
+    ContainerID,
+    ContainerType,
+    LoroDoc,
+    LoroText,
+    from_msgpack,
+    to_msgpack,
+)
+
+
+def test_msgpack_roundtrip():
+    value = {
+        "null": None,
+        "flags": [True, False],
+        "ints": [0, 127, 128, -1, -33, 70000, -(2**40), 2**62],
+        "float": 1.5,
+        "str": "héllo" * 10,
+        "bytes": b"\x00\x01",
+    }
+    assert from_msgpack(to_msgpack(value)) == value
+
+
+def test_msgpack_is_standard_and_deterministic():
+    assert to_msgpack({"b": 1, "a": [None, True]}) == b"\x82\xa1a\x92\xc0\xc3\xa1b\x01"
+
+
+def test_doc_deep_value_msgpack():
+    doc = LoroDoc()
+    doc.get_map("map").insert("k", "v")
+    doc.get_map("map").insert_container("text", LoroText()).insert(0, "hi")
+    assert from_msgpack(doc.get_deep_value_msgpack()) == doc.get_deep_value()
+
+
+def test_msgpack_container_ids():
+    doc = LoroDoc()
+    doc.peer_id = 1
+    text = doc.get_map("map").insert_container("text", LoroText())
+    data = to_msgpack(doc.get_map("map").get_value())
+    assert "🦜:cid:0@1:Text".encode() in data
+    assert from_msgpack(data) == {"text": text.id}
+
+    # Strings with the prefix are read back as container ids.
+    assert from_msgpack(to_msgpack("🦜:cid:root-map:Map")) == ContainerID.Root(
+        "map", ContainerType.Map()
+    )
+
+
+def test_msgpack_rejects_truncated_input():
+    data = to_msgpack(["abc"])
+    with pytest.raises(BaseException, match="invalid msgpack"):
+        from_msgpack(data[:-1])
@@ -0,0 +1,90 @@
+import time
+
+from loro import EphemeralStore, ExportMode, LoroDoc, Side
+from loro.presence import Presence
+
+
+def make_peers():
+    a = LoroDoc()
+    a.peer_id = 1
+    a.get_text("text").insert(0, "hello world")
+    a.commit()
+    b = LoroDoc()
+    b.peer_id = 2
+    b.import_(a.export(ExportMode.Snapshot()))
+    return a, b
+
+
+def test_presence_resolves_remote_cursors():
+    a, b = make_peers()
+    presence_a = Presence(a)
+    presence_b = Presence(b)
+    text = a.get_text("text")
+    presence_a.set_user({"name": "Ann"})
+    presence_a.set_cursor(
+        text.get_cursor(6, Side.Middle), anchor=text.get_cursor(11, Side.Middle)
+    )
+
+    presence_b.store.apply(presence_a.store.encode_all())
+    assert presence_a.remote_cursors() == {}
+    # Text inserted before the cursor moves it
+    b.get_text("text").insert(0, ">> ")
+    b.commit()
+    cursors = presence_b.remote_cursors()
+    assert list(cursors) == [1]
+    assert cursors[1].head.pos == 9
+    assert cursors[1].anchor.pos == 14
+    assert cursors[1].user == {"name": "Ann"}
+
+    # The store keeps the latest write by millisecond timestamp, so let the clock move on
+    time.sleep(0.01)
+    presence_a.set_cursor(None)
+    presence_b.store.apply(presence_a.store.encode_all())
+    assert presence_b.remote_cursors() == {}
+
+
+def test_presence_skips_unknown_and_expired_states():
+    a, b = make_peers()
+    a.get_text("text").insert(0, "new ")
+    a.commit()
+    store = EphemeralStore(100)
+    store.set("other", 1)
+    presence_a = Presence(a, timeout=100)
+    presence_a.set_cursor(a.get_text("text").get_cursor(1, Side.Middle))
+
+    presence_b = Presence(store, doc=b)
+    store.apply(presence_a.store.encode_all())
+    # b doesn't have the change the cursor points at yet
+    assert presence_b.remote_cursors() == {}
+    b.import_(a.export(ExportMode.Snapshot()))
+    assert list(presence_b.remote_cursors()) == [1]
+
+    time.sleep(0.3)
+    assert presence_b.remote_cursors() == {}
+
+
+def test_presence_leave():
+    a, b = make_peers()
+    presence_a = Presence(a)
+    presence_a.set_cursor(a.get_text("text").get_cursor(0, Side.Middle))
+    presence_b = Presence(b)
+    presence_b.store.apply(presence_a.store.encode_all())
+    assert list(presence_b.remote_cursors()) == [1]
+
+    time.sleep(0.01)
+    presence_a.leave()
+    presence_b.store.apply(presence_a.store.encode_all())
+    assert presence_b.remote_cursors() == {}
+
+
+def test_presence_skips_malformed_states():
+    a, b = make_peers()
+    presence_a = Presence(a)
+    presence_a.set_cursor(a.get_text("text").get_cursor(0, Side.Middle))
+    presence_b = Presence(b)
+    presence_b.store.apply(presence_a.store.encode_all())
+    presence_b.store.set("presence/3", "not a dict")
+    presence_b.store.set("presence/4", {"head": b"garbage", "user": "Ann"})
+    presence_b.store.set("presence/5", {"head": 42})
+    presence_b.store.set("presence/x", presence_a.store.get("presence/1"))
+    assert list(presence_b.remote_cursors()) == [1]
@@ -0,0 +1,81 @@
+import pytest
+from loro import LoroDict, LoroDoc, LoroMovableList, LoroSeq, LoroText
+
+
+def test_dict_proxy():
+    doc = LoroDoc()
+    settings = LoroDict(doc.get_map("settings"))
+    settings["theme"] = "dark"
+    settings["recent"] = ["a.txt"]
+    settings["window"] = {"width": 800}
+    settings["recent"].append("b.txt")
+    settings["window"]["height"] = 600
+
+    assert doc.get_deep_value() == {
+        "settings": {
+            "theme": "dark",
+            "recent": ["a.txt", "b.txt"],
+            "window": {"width": 800, "height": 600},
+        }
+    }
+    assert settings == {
+        "theme": "dark",
+        "recent": ["a.txt", "b.txt"],
+        "window": {"width": 800, "height": 600},
+    }
+    assert len(settings) == 3 and "theme" in settings and 1 not in settings
+    assert settings.get("missing") is None
+    assert settings.pop("theme") == "dark"
+    settings.update(lang="en")
+    assert sorted(settings) == ["lang", "recent", "window"]
+    with pytest.raises(KeyError):
+        del settings["theme"]
+    # Every edit is committed
+    assert doc.get_pending_txn_len() == 0
+
+
+def test_seq_proxy():
+    doc = LoroDoc()
+    items = LoroSeq(doc.get_movable_list("items"))
+    items.extend([1, 2, 3, 4])
+    items[0] = 0
+    items[1:3] = ["a", "b", "c"]
+    del items[-1]
+    items.insert(-1, {"k": "v"})
+    items.insert(100, "end")
+
+    assert items == [0, "a", "b", {"k": "v"}, "c", "end"]
+    assert items[::2] == [0, "b", "c"]
+    assert items.index("c") == 4
+    assert items.pop() == "end"
+    items.reverse()
+    assert items == ["c", {"k": "v"}, "b", "a", 0]
+    assert isinstance(doc.get_movable_list("items"), LoroMovableList)
+    with pytest.raises(IndexError):
+        items[10]
+    with pytest.raises(ValueError):
+        items[::2] = [1]
+
+
+def test_proxy_batch_and_containers():
+    doc = LoroDoc()
+    data = LoroDict(doc.get_map("data"))
+    commits = []
+    sub = doc.subscribe_root(commits.append)
+    with data.batch():
+        data["a"] = 1
+        data["list"] = []
+        data["list"].append(2)
+    assert len(commits) == 1
+
+    data["text"] = LoroText()
+    # Other containers are returned as is, their edits aren't committed by the proxy
+    data["text"].insert(0, "hi")
+    assert doc.get_pending_txn_len() == 2
+    doc.commit()
+    assert doc.get_deep_value()["data"]["text"] == "hi"
+
+    manual = LoroDict(doc.get_map("manual"), auto_commit=False)
+    manual["x"] = 1
+    assert doc.get_pending_txn_len() == 1
+    sub.unsubscribe()
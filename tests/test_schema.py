@@ -0,0 +1,113 @@
+from dataclasses import dataclass, field
+from typing import List, Optional
+
+import pytest
+
+import loro
+from loro import LoroCounter, LoroDoc, LoroText
+
+
+@loro.schema
+@dataclass
+class Todo:
+    title: str
+    done: bool = False
+
+
+@loro.schema
+@dataclass
+class Board:
+    name: str
+    notes: LoroText
+    votes: LoroCounter
+    owner: Optional[str] = None
+    todos: List[Todo] = field(default_factory=list)
+
+
+def test_bind_reads_and_writes_through_the_map():
+    doc = LoroDoc()
+    board = Board.bind(doc.get_map("board"))
+    board.name = "Sprint"
+    board.notes.insert(0, "Ship it")
+    board.votes = 3
+    board.todos.append(Todo(title="docs"))
+    board.todos.insert(0, Todo(title="tests"))
+    board.todos[-1].done = True
+
+    assert doc.get_deep_value() == {
+        "board": {
+            "name": "Sprint",
+            "notes": "Ship it",
+            "votes": 3.0,
+            "todos": [
+                {"title": "tests", "done": False},
+                {"title": "docs", "done": True},
+            ],
+        }
+    }
+    assert board.owner is None
+    assert [todo.title for todo in board.todos] == ["tests", "docs"]
+
+
+def test_load_and_save_roundtrip():
+    doc = LoroDoc()
+    board = Board.bind(doc.get_map("board"))
+    board.name = "Sprint"
+    board.todos.append(Todo(title="docs", done=True))
+
+    loaded = Board.load(doc.get_map("board"))
+    assert loaded.name == "Sprint"
+    assert loaded.todos == [Todo(title="docs", done=True)]
+
+    copy = Todo.bind(doc.get_map("copy"))
+    Todo.save(copy.container, loaded.todos[0])
+    assert Todo.load(doc.get_map("copy")) == Todo(title="docs", done=True)
+
+
+def test_missing_required_field():
+    doc = LoroDoc()
+    todo = Todo.bind(doc.get_map("todo"))
+    with pytest.raises(KeyError, match="Todo.title"):
+        todo.title
+
+
+@loro.schema
+@dataclass
+class Settings:
+    theme: str = "light"
+
+
+@loro.schema
+@dataclass
+class Profile:
+    settings: Settings
+    todos: List[Todo]
+
+
+def test_reads_do_not_create_containers():
+    doc = LoroDoc()
+    board_map = doc.get_map("board")
+    board_map["name"] = "Sprint"
+    doc.commit()
+    vv = doc.oplog_vv
+
+    loaded = Board.load(board_map)
+    assert loaded.notes.to_string() == ""
+    assert loaded.votes.value == 0
+    assert loaded.todos == []
+
+    profile = Profile.bind(doc.get_map("profile"))
+    assert profile.settings.theme == "light"
+    assert len(profile.todos) == 0
+    assert list(profile.todos) == []
+    assert Profile.load(doc.get_map("profile")) == Profile(settings=Settings(), todos=[])
+    doc.commit()
+    assert doc.oplog_vv == vv
+
+    profile.settings.theme = "dark"
+    profile.todos.append(Todo(title="docs"))
+    assert doc.get_deep_value()["profile"] == {
+        "settings": {"theme": "dark"},
+        "todos": [{"title": "docs", "done": False}],
+    }
+    assert profile.settings == Profile.bind(doc.get_map("profile")).settings
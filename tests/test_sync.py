@@ -0,0 +1,180 @@
+import asyncio
+
+import pytest
+from loro import LoroDoc
+from loro.sync import RelayServer, SyncSession, WebSocketClient
+
+
+def exchange(a, b, messages):
+    """Deliver `messages` from `a` to `b` and the replies back, until both are quiet."""
+    pending = [(b, m) for m in messages]
+    while pending:
+        receiver, message = pending.pop(0)
+        sender = a if receiver is b else b
+        pending.extend((sender, reply) for reply in receiver.receive(message))
+
+
+def test_sync_session_handshake():
+    a, b = LoroDoc(), LoroDoc()
+    a.peer_id = 1
+    b.peer_id = 2
+    a.get_text("text").insert(0, "hello")
+    a.commit()
+    b.get_map("map").insert("key", 1)
+    b.commit()
+
+    sa, sb = SyncSession(a), SyncSession(b)
+    assert not sa.synced
+    exchange(sa, sb, sa.start())
+    exchange(sb, sa, sb.start())
+
+    assert a.get_deep_value() == b.get_deep_value()
+    assert a.get_deep_value() == {"text": "hello", "map": {"key": 1}}
+    assert sa.synced and sb.synced
+
+
+def test_sync_session_forwards_local_updates():
+    a, b = LoroDoc(), LoroDoc()
+    sa, sb = SyncSession(a), SyncSession(b)
+    exchange(sa, sb, sa.start())
+    exchange(sb, sa, sb.start())
+
+    a.get_text("text").insert(0, "abc")
+    a.commit()
+    assert not sa.synced
+    exchange(sa, sb, sa.poll())
+    assert b.get_text("text").to_string() == "abc"
+    assert sa.poll() == [] and sb.poll() == []
+
+    sa.close()
+    a.get_text("text").insert(0, "x")
+    a.commit()
+    assert sa.poll() == []
+
+
+def test_sync_session_rejects_unknown_messages():
+    session = SyncSession(LoroDoc())
+    with pytest.raises(ValueError):
+        session.receive(b"")
+    with pytest.raises(ValueError):
+        session.receive(b"\xff")
+
+
+def test_sync_session_coalesces_pending_updates():
+    a, b = LoroDoc(), LoroDoc()
+    sa, sb = SyncSession(a), SyncSession(b)
+    exchange(sa, sb, sa.start())
+    exchange(sb, sa, sb.start())
+
+    for i in range(3):
+        a.get_list("list").push(i)
+        a.commit()
+    messages = sa.poll(coalesce=True)
+    assert len(messages) == 1
+    exchange(sa, sb, messages)
+    assert b.get_list("list").get_deep_value() == [0, 1, 2]
+    assert sa.synced
+
+
+def test_websocket_client_syncs_with_a_server():
+    websockets = pytest.importorskip("websockets")
+    server_doc = LoroDoc()
+    server_doc.get_text("text").insert(0, "from server")
+    server_doc.commit()
+
+    async def handler(ws, path=None):
+        session = SyncSession(server_doc)
+        for message in session.start():
+            await ws.send(message)
+        async for message in ws:
+            for reply in session.receive(message):
+                await ws.send(reply)
+
+    async def main():
+        async with websockets.serve(handler, "127.0.0.1", 0) as server:
+            port = next(iter(server.sockets)).getsockname()[1]
+            doc = LoroDoc()
+            synced = []
+            client = WebSocketClient(
+                doc, f"ws://127.0.0.1:{port}", on_sync=lambda: synced.append(True)
+            )
+            task = asyncio.ensure_future(client.run())
+            await asyncio.wait_for(client.wait_synced(), 5)
+            assert doc.get_text("text").to_string() == "from server"
+            assert synced == [True]
+
+            doc.get_map("map").insert("key", "from client")
+            doc.commit()
+            for _ in range(100):
+                if server_doc.get_deep_value().get("map") == {"key": "from client"}:
+                    break
+                await asyncio.sleep(0.01)
+            assert server_doc.get_deep_value().get("map") == {"key": "from client"}
+
+            await client.close()
+            await asyncio.wait_for(task, 5)
+
+    asyncio.run(main())
+
+
+def test_relay_server_fans_out_updates_and_snapshots_rooms():
+    pytest.importorskip("websockets")
+    docs = {}
+    snapshots = {}
+
+    async def main():
+        async with RelayServer(
+            lambda room: docs.setdefault(room, LoroDoc()),
+            on_snapshot=snapshots.__setitem__,
+        ) as server:
+            await server.start("127.0.0.1", 0)
+            url = f"ws://127.0.0.1:{server.port}/room"
+            a, b = LoroDoc(), LoroDoc()
+            clients = [WebSocketClient(a, url), WebSocketClient(b, url)]
+            tasks = [asyncio.ensure_future(c.run()) for c in clients]
+            for client in clients:
+                await asyncio.wait_for(client.wait_synced(), 5)
+            assert server.rooms() == ["room"]
+
+            a.get_text("text").insert(0, "hello")
+            a.commit()
+            for _ in range(100):
+                if b.get_text("text").to_string() == "hello":
+                    break
+                await asyncio.sleep(0.01)
+            assert b.get_text("text").to_string() == "hello"
+
+            for client in clients:
+                await client.close()
+            await asyncio.wait_for(asyncio.gather(*tasks), 5)
+
+    asyncio.run(main())
+    restored = LoroDoc()
+    restored.import_(snapshots["room"])
+    assert restored.get_text("text").to_string() == "hello"
+
+
+def test_relay_server_sends_server_edits_to_peers():
+    pytest.importorskip("websockets")
+    docs = {}
+
+    async def main():
+        async with RelayServer(lambda room: docs.setdefault(room, LoroDoc())) as server:
+            await server.start("127.0.0.1", 0)
+            peer = LoroDoc()
+            client = WebSocketClient(peer, f"ws://127.0.0.1:{server.port}/room")
+            task = asyncio.ensure_future(client.run())
+            await asyncio.wait_for(client.wait_synced(), 5)
+
+            docs["room"].get_text("text").insert(0, "from server")
+            docs["room"].commit()
+            for _ in range(100):
+                if peer.get_text("text").to_string() == "from server":
+                    break
+                await asyncio.sleep(0.01)
+            assert peer.get_text("text").to_string() == "from server"
+
+            await client.close()
+            await asyncio.wait_for(task, 5)
+
+    asyncio.run(main())
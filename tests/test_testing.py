@@ -0,0 +1,80 @@
+import pytest
+from loro import ID, TreeID
+from loro.testing import (
+    apply_script,
+    assert_converged,
+    make_doc,
+    make_docs,
+    random_ops,
+    sync_all,
+)
+
+
+def test_make_docs_are_deterministic():
+    docs = make_docs(3, first_peer_id=10, timestamp=1234)
+    assert [doc.peer_id for doc in docs] == [10, 11, 12]
+    docs[0].get_text("text").insert(0, "a")
+    docs[0].commit()
+    (change,) = docs[0].iter_changes()
+    assert change.meta.timestamp == 1234
+    assert make_doc(7, record_timestamp=True).peer_id == 7
+
+
+def test_random_ops_converge():
+    docs = make_docs(3)
+    random_ops(docs, 300, seed=1)
+    assert docs[0].len_ops > 0
+    assert_converged(docs)
+
+    replay = make_docs(3)
+    random_ops(replay, 300, seed=1)
+    sync_all(replay)
+    assert replay[0].get_deep_value() == docs[0].get_deep_value()
+
+
+def test_random_ops_on_some_containers():
+    docs = make_docs(2)
+    random_ops(docs, 50, seed=2, containers=["map"])
+    sync_all(docs)
+    assert set(docs[0].get_deep_value()) == {"map"}
+    with pytest.raises(ValueError):
+        random_ops(docs, 1, containers=["nope"])
+
+
+def test_assert_converged_reports_divergence():
+    a, b = make_docs(2)
+    a.get_map("map").insert("key", 1)
+    a.commit()
+    with pytest.raises(AssertionError, match="diverged"):
+        assert_converged([a, b], sync=False)
+
+
+def test_apply_script():
+    doc = make_doc(1)
+    apply_script(
+        doc,
+        [
+            {"path": "text", "type": "text", "method": "insert", "args": [0, "hi"]},
+            {"path": "map", "type": "map", "method": "insert", "args": ["k", 1]},
+            {"commit": {"message": "first", "origin": "fixture"}},
+            {"path": "tree", "type": "tree", "method": "create", "args": [None]},
+            {"commit": True},
+            {"path": "tree", "method": "create", "args": ["3@1"]},
+            {"path": "text", "method": "delete", "args": [0, 1]},
+        ],
+    )
+    assert doc.get_text("text").to_string() == "i"
+    assert doc.get_map("map").get_deep_value() == {"k": 1}
+    (root,) = doc.get_tree("tree").children(None)
+    assert root == TreeID(peer=1, counter=3)
+    assert len(doc.get_tree("tree").children(root)) == 1
+    assert doc.get_change(ID(1, 0)).message == "first"
+
+    other = make_doc(1)
+    apply_script(
+        other,
+        '[{"path": "text", "type": "text", "method": "insert", "args": [0, "hi"]}]',
+    )
+    assert other.get_text("text").to_string() == "hi"
+    with pytest.raises(ValueError, match="step #0"):
+        apply_script(other, [{"path": "text"}])
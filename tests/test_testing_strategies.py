@@ -0,0 +1,28 @@
+import pytest
+
+pytest.importorskip("hypothesis")
+
+from hypothesis import given, settings  # noqa: E402
+from loro import LoroDoc  # noqa: E402
+from loro.testing import assert_converged, make_docs  # noqa: E402
+from loro.testing.strategies import interleavings, loro_values, replay  # noqa: E402
+
+
+@given(loro_values())
+def test_loro_values_roundtrip(value):
+    doc = LoroDoc()
+    doc.get_map("map").insert("value", value)
+    assert doc.get_map("map").get_deep_value()["value"] == value
+
+
+@settings(max_examples=30, deadline=None)
+@given(interleavings(n_peers=3))
+def test_interleavings_converge(steps):
+    docs = make_docs(3)
+    replay(docs, steps)
+    assert_converged(docs)
+
+
+def test_replay_rejects_unknown_steps():
+    with pytest.raises(ValueError):
+        replay(make_docs(1), [("explode",)])
@@ -1,4 +1,5 @@
-from loro import LoroDoc, StyleConfigMap, TextDelta
+import pytest
+from loro import ID, ExpandType, Frontiers, LoroDoc, LoroText, Side, StyleConfigMap, TextDelta
 
 
 def test_text_get_value():
@@ -39,6 +40,29 @@ def test_text_to_delta():
             assert delta.attributes == None
 
 
+def test_text_delta_constructors_and_equality():
+    assert TextDelta.insert("hi") == TextDelta.Insert("hi")
+    assert TextDelta.insert("hi") == TextDelta.Insert(insert="hi", attributes=None)
+    assert TextDelta.retain(2, {"bold": True}) == TextDelta.Retain(
+        retain=2, attributes={"bold": True}
+    )
+    assert TextDelta.delete(3) == TextDelta.Delete(delete=3)
+    assert TextDelta.insert("hi") != TextDelta.insert("hi", {"bold": True})
+    assert TextDelta.retain(1) != TextDelta.delete(1)
+
+    doc = LoroDoc()
+    doc.config_text_style(StyleConfigMap.default_rich_text_config())
+    text = doc.get_text("text")
+    events = []
+    sub = doc.subscribe_root(events.append)
+    expected = [TextDelta.insert("Hello"), TextDelta.insert("!", {"bold": True})]
+    text.apply_delta(expected)
+    doc.commit()
+    sub.unsubscribe()
+    assert text.to_delta() == expected
+    assert events[0].events[0].diff.diff == expected
+
+
 def test_text_update():
     doc = LoroDoc()
     text = doc.get_text("text")
@@ -46,3 +70,88 @@ def test_text_update():
     text.update("Hello beautiful world...", use_refined_diff=True)
 
     assert text.to_string() == "Hello beautiful world..."
+
+
+def test_text_update_by_line():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    text.insert(0, "a = 1\nb = 2\nc = 3\n")
+    doc.commit()
+    text.update_by_line("a = 1\nb = 20\nc = 3\nd = 4\n", timeout_ms=1000)
+    doc.commit()
+
+    assert text.to_string() == "a = 1\nb = 20\nc = 3\nd = 4\n"
+    # The unchanged lines keep their original ops
+    assert text.get_cursor(0, Side.Middle).id == ID(doc.peer_id, 0)
+
+
+def test_style_config_map_inspection():
+    config = StyleConfigMap({"bold": "after", "link": ExpandType.Null})
+    config.insert("comment", ExpandType.Both)
+    assert config.keys() == ["bold", "link", "comment"]
+    assert list(config) == config.keys() and len(config) == 3
+    assert "bold" in config and "italic" not in config
+    assert config.to_dict() == {
+        "bold": ExpandType.After,
+        "link": ExpandType.Null,
+        "comment": ExpandType.Both,
+    }
+    assert config == StyleConfigMap(
+        {"comment": "both", "link": "none", "bold": "after"}
+    )
+    with pytest.raises(ValueError):
+        StyleConfigMap({"bold": "sideways"})
+
+    assert "bold" in StyleConfigMap.default_rich_text_config().keys()
+    with pytest.raises(ValueError, match="':'"):
+        StyleConfigMap({"a:b": ExpandType.After})
+
+
+def test_style_config_of_document_lists_custom_keys():
+    doc = LoroDoc()
+    doc.config_text_style(StyleConfigMap({"myStyle": "after", "bold": "none"}))
+    config = doc.config.text_style_config()
+    assert config.keys() == ["myStyle", "bold"]
+    assert config.to_dict() == {"myStyle": ExpandType.After, "bold": ExpandType.Null}
+    assert doc.fork().config.text_style_config().keys() == ["myStyle", "bold"]
+
+
+def test_expand_type_accepts_names():
+    assert ExpandType.from_str("After") == ExpandType.After
+    assert ExpandType.from_str("none") == ExpandType.Null
+    all_types = [ExpandType.Before, ExpandType.After, ExpandType.Both, ExpandType.Null]
+    assert [str(e) for e in all_types] == ["before", "after", "both", "none"]
+    assert all(ExpandType.from_str(str(e)) == e for e in all_types)
+
+    config = StyleConfigMap()
+    config.insert("bold", "after")
+    assert config.get("bold") == ExpandType.After
+
+    doc = LoroDoc()
+    doc.config_default_text_style("none")
+    text = doc.get_text("text")
+    text.insert(0, "Hello")
+    text.mark(start=0, end=5, key="custom", value=True)
+    text.insert(5, "!")
+    assert text.to_delta()[-1].attributes is None
+
+
+def test_text_at_version():
+    doc = LoroDoc()
+    doc.config_text_style(StyleConfigMap.default_rich_text_config())
+    text = doc.get_text("text")
+    text.insert(0, "Hello")
+    doc.commit()
+    v1 = doc.oplog_frontiers
+    text.insert(5, " world!")
+    text.mark(start=0, end=5, key="bold", value=True)
+    doc.commit()
+
+    assert text.to_string_at(v1) == "Hello"
+    (delta,) = text.to_delta_at(v1)
+    assert delta.insert == "Hello" and delta.attributes is None
+    assert text.to_string() == "Hello world!"
+    with pytest.raises(BaseException, match="attached"):
+        LoroText().to_string_at(v1)
+    with pytest.raises(BaseException, match="not contained"):
+        text.to_delta_at(Frontiers.from_id(ID(99, 5)))
@@ -1,4 +1,5 @@
-from loro import LoroDoc, PositionType, TextDelta
+import pytest
+from loro import LoroDoc, LoroMap, LoroText, PositionType, TextDelta
 
 
 def test_utf16_insert_and_slice():
@@ -44,3 +45,24 @@ def test_get_container_returns_handler():
     container_id = text.id
     container = doc.get_container(container_id)
     assert container is not None
+
+
+def test_get_container_accepts_serialized_ids():
+    doc = LoroDoc()
+    doc.peer_id = 7
+    text = doc.get_text("text")
+    child = doc.get_map("root").insert_container("child", LoroMap())
+
+    root = doc.get_container("cid:root-text:Text")
+    assert isinstance(root, LoroText)
+    assert root.id == text.id
+
+    nested = doc.get_container(f"cid:{child.id.counter}@{child.id.peer}:Map")
+    assert isinstance(nested, LoroMap)
+    assert nested.id == child.id
+
+    assert doc.get_container("cid:99@7:Map") is None
+    with pytest.raises(ValueError):
+        doc.get_container("not-a-container-id")
+    with pytest.raises(TypeError):
+        doc.get_container(42)
@@ -1,4 +1,5 @@
-from loro import LoroDoc, LoroText,  ValueOrContainer
+import pytest
+from loro import ID, Frontiers, LoroDoc, LoroText, TreeID, ValueOrContainer
 
 
 def test_tree():
@@ -23,3 +24,80 @@ def test_tree():
     value = child_meta.get(key="b")
     assert ValueOrContainer.is_value(value)
     assert value.value == "basic"
+
+
+def test_tree_get_nodes_at():
+    doc = LoroDoc()
+    tree = doc.get_tree("tree")
+    a = tree.create()
+    b = tree.create()
+    tree.get_meta(b).insert("title", "before")
+    doc.commit()
+    before = doc.oplog_frontiers
+
+    tree.mov(b, a)
+    tree.get_meta(b).insert("title", "after")
+    doc.commit()
+
+    nodes = {node.id: (node, meta) for node, meta in tree.get_nodes_at(before)}
+    node, meta = nodes[b]
+    assert node.parent is None and node.index == 1
+    assert meta == {"title": "before"}
+    assert nodes[a][1] == {}
+    assert tree.parent(b) == a
+
+    with pytest.raises(BaseException, match="not contained"):
+        tree.get_nodes_at(Frontiers.from_id(ID(99, 5)))
+
+
+def test_tree_parent_coercion():
+    doc = LoroDoc()
+    tree = doc.get_tree("tree")
+    root = tree.create("root")
+    child = tree.create(root)
+    other = tree.create_at(0, "root")
+    assert tree.children("root") == tree.children(None) == [other, root]
+    assert tree.children_num("root") == 2
+
+    tree.mov(child, "root")
+    assert tree.parent(child) is None
+    tree.mov_to(child, 0, root)
+    assert tree.children(root) == [child]
+
+    tree.delete(root)
+    assert tree.children("deleted") == [root]
+    assert tree.children_num("deleted") == 1
+    with pytest.raises(ValueError):
+        tree.create("deleted")
+    with pytest.raises(ValueError):
+        tree.mov(other, "trash")
+    with pytest.raises(TypeError):
+        tree.children(1)
+
+
+def test_tree_id_string_form():
+    tree_id = TreeID(peer=9876, counter=12)
+    assert str(tree_id) == "12@9876"
+    assert repr(tree_id) == "TreeID(peer=9876, counter=12)"
+    assert TreeID.parse("12@9876") == tree_id
+    for invalid in ["", "12", "12@", "a@1", "1@-1"]:
+        with pytest.raises(ValueError):
+            TreeID.parse(invalid)
+
+    doc = LoroDoc()
+    tree = doc.get_tree("tree")
+    root = tree.create()
+    child = tree.create(str(root))
+    assert str(child) in tree
+    assert tree.parent(str(child)) == root
+    tree.get_meta(str(child)).insert("name", "child")
+    assert tree.get_meta(child)["name"].value == "child"
+    tree.mov(str(child), "root")
+    assert tree.children(None) == [root, child]
+
+    doc.apply_ops(
+        [(tree, "mov", [str(child), str(root)]), (tree, "delete", [str(root)])]
+    )
+    assert tree.is_node_deleted(str(child))
+    with pytest.raises(TypeError):
+        tree.delete(12)
@@ -1,4 +1,4 @@
-from loro import LoroDoc, UndoManager
+from loro import CursorWithPos, LoroDoc, Side, UndoItemMeta, UndoManager
 
 
 def test_undo_manager_group_basic():
@@ -174,4 +174,29 @@ def test_undo_manager_group_undo_count():
     # Undo the initial change
     undo_manager.undo()
     assert text.to_string() == ""
-    assert undo_manager.undo_count() == 0 
\ No newline at end of file
+    assert undo_manager.undo_count() == 0 
+
+def test_undo_restores_cursors_from_item_meta():
+    doc = LoroDoc()
+    text = doc.get_text("text")
+    text.insert(0, "hello world")
+    doc.commit()
+    undo_manager = UndoManager(doc)
+
+    # The selection before the change, at "w".
+    selection = CursorWithPos.capture(doc, text.get_cursor(6, Side.Middle))
+    assert selection.pos.pos == 6
+    undo_manager.set_on_push(
+        lambda kind, span, event: UndoItemMeta(cursors=[selection])
+    )
+    popped = []
+    undo_manager.set_on_pop(lambda kind, span, meta: popped.append(meta))
+
+    text.delete(0, 6)
+    doc.commit()
+    assert selection.transform(doc).pos.pos == 0
+
+    undo_manager.undo()
+    assert text.to_string() == "hello world"
+    (cursor,) = popped[0].transform_cursors(doc)
+    assert cursor.pos.pos == 6
@@ -0,0 +1,63 @@
+from loro import LoroCounter, LoroDoc, LoroList, LoroText
+
+
+def test_value_or_container_helpers():
+    doc = LoroDoc()
+    root = doc.get_map("root")
+    root.insert("data", {"items": [1, 2, 3]})
+    text = root.insert_container("text", LoroText())
+    text.insert(0, "hi")
+
+    data = root.get("data")
+    assert data.to_py() == {"items": [1, 2, 3]}
+    assert data["items"][-1] == 3
+    assert data.container_id() is None
+
+    text_entry = root.get("text")
+    assert text_entry.container_id() == text.id
+    assert text_entry.to_py() == "hi"
+    assert text_entry == root.get("text")
+    assert text_entry == "hi"
+
+    assert doc.get_by_str_path("root") == {"data": {"items": [1, 2, 3]}, "text": "hi"}
+    assert doc.get_by_str_path("root")["text"] == text_entry
+
+
+def test_value_or_container_casts():
+    doc = LoroDoc()
+    root = doc.get_map("root")
+    text = root.insert_container("text", LoroText())
+    counter = root.insert_container("counter", LoroCounter())
+    items = root.insert_container("items", LoroList())
+    root.insert("plain", 1)
+
+    entry = root.get("text")
+    assert entry.as_text().id == text.id
+    assert entry.as_map() is None
+    assert entry.as_list() is None
+    assert root.get("counter").as_counter().id == counter.id
+    assert root.get("counter").as_text() is None
+    assert root.get("items").as_list().id == items.id
+    assert root.get("items").as_movable_list() is None
+    assert doc.get_by_str_path("root").as_map().id == root.id
+    assert root.get("plain").as_tree() is None
+
+
+def test_value_or_container_equality_compares_deep_values():
+    doc = LoroDoc()
+    root = doc.get_map("root")
+    a = root.insert_container("a", LoroText())
+    a.insert(0, "hi")
+    b = root.insert_container("b", LoroText())
+    b.insert(0, "hi")
+    root.insert("plain", "hi")
+
+    # Every pair is equal, so equality stays transitive across containers and values.
+    entries = [root.get("a"), root.get("b"), root.get("plain")]
+    for x in entries:
+        for y in entries:
+            assert x == y
+        assert x == "hi"
+        assert x == a
+        assert x == b
+    assert root.get("a") != "ho"